@@ -0,0 +1,121 @@
+//! Compares `parse` and `parse_borrowed` by allocations and time.
+//!
+//! Run with `cargo bench`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    hint::black_box,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use shell_parser_surfaces_rs::{decode_bytes, parse, parse_borrowed};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ITERATIONS: u32 = 50;
+
+struct Measure {
+    allocations: usize,
+    bytes: usize,
+    time: Duration,
+}
+
+fn measure<F: FnMut()>(mut f: F) -> Measure {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    f();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes;
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    Measure {
+        allocations,
+        bytes,
+        time: start.elapsed() / ITERATIONS,
+    }
+}
+
+fn report(name: &str, input: &str) {
+    let owned = measure(|| {
+        black_box(parse(black_box(input)).unwrap());
+    });
+    let borrowed = measure(|| {
+        black_box(parse_borrowed(black_box(input)).unwrap());
+    });
+
+    println!("{} ({} bytes)", name, input.len());
+    for (label, m) in [("parse", &owned), ("parse_borrowed", &borrowed)] {
+        println!(
+            "  {:<15} {:>9} allocs {:>11} bytes {:>12?}",
+            label, m.allocations, m.bytes, m.time
+        );
+    }
+    println!(
+        "  saved           {:>8.1}% allocs {:>10.1}% bytes",
+        saving(owned.allocations, borrowed.allocations),
+        saving(owned.bytes, borrowed.bytes)
+    );
+}
+
+fn saving(owned: usize, borrowed: usize) -> f64 {
+    if owned == 0 {
+        return 0.0;
+    }
+    (owned - borrowed.min(owned)) as f64 * 100.0 / owned as f64
+}
+
+/// Builds a shell of `count` surfaces with elements, collisions and comments.
+fn synthesize(count: usize) -> String {
+    let mut result = String::from("charset,UTF-8\r\n// synthesized\r\n");
+    for i in 0..count {
+        result.push_str(&format!("// surface {}\r\nsurface{}\r\n{{\r\n", i, i));
+        result.push_str(&format!("element0,base,body{}.png,0,0\r\n", i));
+        result.push_str(&format!("element1,overlay,face{}.png,10,20\r\n", i));
+        result.push_str("collision0,40,56,95,90,Head\r\n");
+        result.push_str("collisionex1,Bust,region,bust.png,255,0,0\r\n");
+        result.push_str("animation0.interval,sometimes\r\n");
+        result.push_str("animation0.pattern0,overlay,101,100,168,67\r\n");
+        result.push_str("}\r\n");
+    }
+    result
+}
+
+fn main() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/surfaces/surfaces01.txt");
+    let buffer = fs::read(path).unwrap();
+    let content = decode_bytes(&buffer).unwrap();
+    report("surfaces01.txt", &content);
+
+    report("synthesized", &synthesize(200));
+}
@@ -1,4 +1,27 @@
 //! AST for `ShellSurfaces`.
+//!
+//! # JSON representation
+//!
+//! With the `serde` feature, every type in this module implements `Serialize` and `Deserialize`.
+//! The representation is the serde default and is kept stable:
+//!
+//! - Structs are objects keyed by their field names (e.g. `{"ids": [...], "lines": [...]}`).
+//! - Unit variants are strings of the variant name (e.g. `"Ascend"`).
+//! - Other variants are objects with the variant name as the only key
+//!   (e.g. `{"Unit": 0}`, `{"Range": [0, 3]}`, `{"Not": {"Unit": 2}}`).
+//! - `Option` is `null` or the value itself.
+//! - A body line is `{"Body": ...}`, or `{"FormattedBody": {"body": ..., "format": ...}}` when it
+//!   keeps the original text of a line not written the canonical way or has a trailing comment.
+//!   See [`LineFormat`].
+//! - `layout` of a brace may be omitted, meaning `"OwnLines"`. See [`BraceLayout`].
+//! - `Charset` is the name written in `surfaces.txt` (e.g. `"UTF-8"`, `"Shift_JIS"`).
+//!   See [`SerdeCharset`](crate::serde_charset::SerdeCharset).
+//!
+//! With the `schema` feature, the JSON Schema of this representation is available from
+//! [`shell_surfaces_schema`](crate::schema::shell_surfaces_schema),
+//! and is shipped as `schema/shell_surfaces.schema.json`.
+
+use std::{collections::BTreeSet, ops::RangeInclusive};
 
 use shell_parser_common_rs::charset::Charset;
 
@@ -14,46 +37,145 @@ pub type CursorMouseIdType = AnimationIdType;
 
 /// Root of `ShellSurfaces`.
 #[derive(Debug, Clone, PartialEq)]
-pub struct ShellSurfaces {
-    header_comments: Vec<CommentLine>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "ShellSurfaces"))]
+pub struct ShellSurfaces<S = String> {
+    header_comments: Vec<CommentLine<S>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_charset"))]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "crate::serde_charset::SerdeCharset")
+    )]
     charset: Charset,
-    braces: Vec<BraceContainer>,
-    footer_comments: Vec<CommentLine>,
+    /// Whether `charset` is written in the file, rather than assumed because the line is missing.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "crate::serde_charset::charset_declared_default")
+    )]
+    charset_declared: bool,
+    braces: Vec<BraceContainer<S>>,
+    footer_comments: Vec<CommentLine<S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct CommentLine {
-    body: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "CommentLine"))]
+pub struct CommentLine<S = String> {
+    body: S,
 }
 
+/// `/* ... */` comment, which may span lines.
 #[derive(Debug, Clone, PartialEq)]
-pub enum LineContainer<T> {
-    Comment(CommentLine),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "BlockComment"))]
+pub struct BlockComment<S = String> {
+    /// Text between `/*` and `*/`, with its newlines.
+    body: S,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "LineContainerRepr<T, S>"))]
+pub enum LineContainer<T, S = String> {
+    Comment(CommentLine<S>),
+    BlockComment(BlockComment<S>),
+    Body(T, LineFormat<S>),
+}
+
+/// How a body line is written, apart from its content.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "LineFormat"))]
+pub struct LineFormat<S = String> {
+    /// Original text of the line, kept when it is not written the canonical way
+    /// (e.g. `element0, base, body.png, 0, 0`).
+    raw: Option<S>,
+    /// Comment after the body, starting with `//` (e.g. `// forehead`).
+    comment: Option<S>,
+}
+
+// JSON representation of `LineContainer`, where a line without a format keeps the `Body` shape.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "LineContainer_for_{T}"))]
+#[serde(rename = "LineContainer")]
+enum LineContainerRepr<T, S> {
+    Comment(CommentLine<S>),
+    BlockComment(BlockComment<S>),
     Body(T),
+    FormattedBody { body: T, format: LineFormat<S> },
+}
+
+/// Borrowed [`LineContainerRepr`] to serialize with.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+#[serde(rename = "LineContainer")]
+enum LineContainerReprRef<'a, T, S> {
+    Comment(&'a CommentLine<S>),
+    BlockComment(&'a BlockComment<S>),
+    Body(&'a T),
+    FormattedBody {
+        body: &'a T,
+        format: &'a LineFormat<S>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct BraceContainer {
-    header_comments: Vec<CommentLine>,
-    body: Brace,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "BraceContainer"))]
+pub struct BraceContainer<S = String> {
+    header_comments: Vec<CommentLine<S>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    layout: BraceLayout,
+    body: Brace<S>,
+}
+
+/// Where `{` and `}` of a brace are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BraceLayout {
+    /// `{` and `}` on their own lines.
+    #[default]
+    OwnLines,
+    /// `{` at the end of the line of the brace name, as in `surface0 {`.
+    SameLine,
+    /// The whole brace on one line, as in `surface5 { element0,base,a.png,0,0 }`.
+    OneLine,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Brace {
-    Descript(Descript),
-    Surface(Surface),
-    SurfaceAppend(SurfaceAppend),
-    SurfaceAlias(SurfaceAlias),
-    Cursor(ShellSurfacesCursor),
-    Tooltip(Tooltip),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Brace"))]
+pub enum Brace<S = String> {
+    Descript(Descript<S>),
+    Surface(Surface<S>),
+    SurfaceAppend(SurfaceAppend<S>),
+    SurfaceAlias(SurfaceAlias<S>),
+    Cursor(ShellSurfacesCursor<S>),
+    Tooltip(Tooltip<S>),
+    /// `/* ... */` between braces, such as a brace commented out.
+    BlockComment(BlockComment<S>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Descript {
-    lines: Vec<LineContainer<DescriptInner>>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Descript"))]
+pub struct Descript<S = String> {
+    lines: Vec<LineContainer<DescriptInner, S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DescriptInner {
     Version(u16),
     MaxWidth(SurfaceSizeType),
@@ -62,46 +184,67 @@ pub enum DescriptInner {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SortOrder {
     Ascend,
     Descend,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Surface {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Surface"))]
+pub struct Surface<S = String> {
     ids: Vec<SurfaceId>,
-    lines: Vec<LineContainer<SurfaceInner>>,
+    lines: Vec<LineContainer<SurfaceInner<S>, S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceAppend {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceAppend"))]
+pub struct SurfaceAppend<S = String> {
     ids: Vec<SurfaceId>,
-    lines: Vec<LineContainer<SurfaceInner>>,
+    lines: Vec<LineContainer<SurfaceInner<S>, S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SurfaceId {
     Unit(SurfaceIdType),
     Range(SurfaceIdType, SurfaceIdType),
+    /// `N-`, every id from `N`.
+    RangeFrom(SurfaceIdType),
+    /// `*`, every id.
+    All,
     Not(Box<SurfaceId>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum SurfaceInner {
-    Element(SurfaceElement),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceInner"))]
+pub enum SurfaceInner<S = String> {
+    Element(SurfaceElement<S>),
     AnimationInterval(SurfaceAnimationInterval),
     AnimationPattern(SurfaceAnimationPattern),
     AnimationOption(SurfaceAnimationOption),
-    AnimationCollision(SurfaceAnimationCollision),
-    AnimationCollisionEx(SurfaceAnimationCollisionEx),
-    Collision(SurfaceCollision),
-    CollisionEx(SurfaceCollisionEx),
+    AnimationCollision(SurfaceAnimationCollision<S>),
+    AnimationCollisionEx(SurfaceAnimationCollisionEx<S>),
+    Collision(SurfaceCollision<S>),
+    CollisionEx(SurfaceCollisionEx<S>),
     SakuraBalloonOffsetX(CoordinateType),
     SakuraBalloonOffsetY(CoordinateType),
     KeroBalloonOffsetX(CoordinateType),
     KeroBalloonOffsetY(CoordinateType),
     BalloonOffsetX(CoordinateType),
     BalloonOffsetY(CoordinateType),
+    /// `charN.balloon.offsetx` of a character other than sakura and kero.
+    CharacterBalloonOffsetX(SurfaceTargetCharacterId, CoordinateType),
+    /// `charN.balloon.offsety` of a character other than sakura and kero.
+    CharacterBalloonOffsetY(SurfaceTargetCharacterId, CoordinateType),
     PointCenterX(CoordinateType),
     PointCenterY(CoordinateType),
     PointKinokoCenterX(CoordinateType),
@@ -111,15 +254,20 @@ pub enum SurfaceInner {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceElement {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceElement"))]
+pub struct SurfaceElement<S = String> {
     id: ElementIdType,
     method: DrawMethod,
-    filename: String,
+    filename: S,
     x: CoordinateType,
     y: CoordinateType,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DrawMethod {
     Base,
     Overlay,
@@ -135,6 +283,8 @@ pub enum DrawMethod {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum DrawMethodOnAnimation {
     Insert(AnimationIdType),
     Start(AnimationIdType),
@@ -146,12 +296,16 @@ pub enum DrawMethodOnAnimation {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SurfaceAnimationInterval {
     id: AnimationIdType,
     intervals: Vec<AnimationInterval>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AnimationInterval {
     Sometimes,
     Rarely,
@@ -166,6 +320,8 @@ pub enum AnimationInterval {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SurfaceAnimationPattern {
     id: AnimationIdType,
     pattern_id: AnimationPatternIdType,
@@ -173,12 +329,16 @@ pub struct SurfaceAnimationPattern {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AnimationPatternDrawMethod {
     Normal(DrawMethod, AnimationPatternProperty),
     Animation(DrawMethodOnAnimation),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimationPatternProperty {
     surface_id: SurfaceIdPointerType,
     weight: u32,
@@ -187,12 +347,16 @@ pub struct AnimationPatternProperty {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SurfaceAnimationOption {
     id: AnimationIdType,
     options: Vec<AnimationOptionKind>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AnimationOptionKind {
     Exclusive(Option<Vec<AnimationIdType>>),
     Background,
@@ -200,36 +364,51 @@ pub enum AnimationOptionKind {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceAnimationCollision {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceAnimationCollision"))]
+pub struct SurfaceAnimationCollision<S = String> {
     id: AnimationIdType,
-    collision: SurfaceCollision,
+    collision: SurfaceCollision<S>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceAnimationCollisionEx {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceAnimationCollisionEx"))]
+pub struct SurfaceAnimationCollisionEx<S = String> {
     id: AnimationIdType,
-    collision: SurfaceCollisionEx,
+    collision: SurfaceCollisionEx<S>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceCollision {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceCollision"))]
+pub struct SurfaceCollision<S = String> {
     id: CollisionIdType,
     start_x: CoordinateType,
     start_y: CoordinateType,
     end_x: CoordinateType,
     end_y: CoordinateType,
-    target_id: String,
+    target_id: S,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceCollisionEx {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceCollisionEx"))]
+pub struct SurfaceCollisionEx<S = String> {
     id: CollisionIdType,
-    target_id: String,
-    kind: CollisionExKind,
+    target_id: S,
+    kind: CollisionExKind<S>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum CollisionExKind {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "CollisionExKind"))]
+pub enum CollisionExKind<S = String> {
     Rect(
         CoordinateType,
         CoordinateType,
@@ -244,43 +423,75 @@ pub enum CollisionExKind {
     ),
     Circle(CoordinateType, CoordinateType, CoordinateType),
     Polygon(Vec<CoordinateType>),
-    Region(String, u8, u8, u8, Option<bool>),
+    Region(S, u8, u8, u8, Option<bool>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceAlias {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceAlias"))]
+pub struct SurfaceAlias<S = String> {
     id: SurfaceTargetCharacterId,
-    lines: Vec<LineContainer<SurfaceAliasInner>>,
+    lines: Vec<LineContainer<SurfaceAliasInner<S>, S>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SurfaceTargetCharacterId {
     Sakura,
     Kero,
     Char(u32),
 }
 
+/// Left side of an alias line.
+///
+/// The left side is an id only when it is written the way the id is displayed: ASCII digits
+/// without a leading zero (`0`, `10`) in the range of [`SurfaceIdType`]. Anything else, such as
+/// `00`, `+1`, `-1` or `4294967296`, is a name, so the line is written back as it was read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "AliasTarget"))]
+pub enum AliasTarget<S = String> {
+    /// A surface id (e.g. `0,[0,100]`), replacing the surface itself.
+    Id(SurfaceIdType),
+    /// A name (e.g. `笑顔,[1]`).
+    Name(S),
+}
+
 #[derive(Debug, Clone, PartialEq)]
-pub struct SurfaceAliasInner {
-    target: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "SurfaceAliasInner"))]
+pub struct SurfaceAliasInner<S = String> {
+    target: AliasTarget<S>,
     surfaces: Vec<SurfaceIdType>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct ShellSurfacesCursor {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "ShellSurfacesCursor"))]
+pub struct ShellSurfacesCursor<S = String> {
     id: SurfaceTargetCharacterId,
-    lines: Vec<LineContainer<ShellSurfacesCursorGesture>>,
+    lines: Vec<LineContainer<ShellSurfacesCursorGesture<S>, S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct ShellSurfacesCursorGesture {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "ShellSurfacesCursorGesture"))]
+pub struct ShellSurfacesCursorGesture<S = String> {
     kind: GestureKind,
     id: CursorMouseIdType,
-    target_collistion: String,
-    filename: String,
+    target_collistion: S,
+    filename: S,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum GestureKind {
     MouseUp,
     MouseDown,
@@ -290,116 +501,578 @@ pub enum GestureKind {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Tooltip {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Tooltip"))]
+pub struct Tooltip<S = String> {
     id: SurfaceTargetCharacterId,
-    lines: Vec<LineContainer<TooltipInner>>,
+    lines: Vec<LineContainer<TooltipInner<S>, S>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct TooltipInner {
-    collision: String,
-    description: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "TooltipInner"))]
+pub struct TooltipInner<S = String> {
+    collision: S,
+    description: S,
 }
 
-impl ShellSurfaces {
+impl<S> ShellSurfaces<S> {
     pub fn new(
-        header_comments: Vec<CommentLine>,
+        header_comments: Vec<CommentLine<S>>,
         charset: Charset,
-        braces: Vec<BraceContainer>,
-        footer_comments: Vec<CommentLine>,
-    ) -> ShellSurfaces {
+        braces: Vec<BraceContainer<S>>,
+        footer_comments: Vec<CommentLine<S>>,
+    ) -> ShellSurfaces<S> {
         ShellSurfaces {
             header_comments,
             charset,
+            charset_declared: true,
             braces,
             footer_comments,
         }
     }
 
-    pub fn header_comments(&self) -> &Vec<CommentLine> {
+    pub fn header_comments(&self) -> &Vec<CommentLine<S>> {
         &self.header_comments
     }
+    pub fn header_comments_mut(&mut self) -> &mut Vec<CommentLine<S>> {
+        &mut self.header_comments
+    }
     pub fn charset(&self) -> &Charset {
         &self.charset
     }
-    pub fn braces(&self) -> &Vec<BraceContainer> {
+    pub fn charset_mut(&mut self) -> &mut Charset {
+        &mut self.charset
+    }
+    /// Whether the `charset` line is written, or `charset` is assumed.
+    pub fn charset_declared(&self) -> &bool {
+        &self.charset_declared
+    }
+    pub fn charset_declared_mut(&mut self) -> &mut bool {
+        &mut self.charset_declared
+    }
+    pub fn braces(&self) -> &Vec<BraceContainer<S>> {
         &self.braces
     }
-    pub fn footer_comments(&self) -> &Vec<CommentLine> {
+    pub fn braces_mut(&mut self) -> &mut Vec<BraceContainer<S>> {
+        &mut self.braces
+    }
+    pub fn footer_comments(&self) -> &Vec<CommentLine<S>> {
         &self.footer_comments
     }
+    pub fn footer_comments_mut(&mut self) -> &mut Vec<CommentLine<S>> {
+        &mut self.footer_comments
+    }
+
+    pub fn push_brace(&mut self, brace: BraceContainer<S>) {
+        self.braces.push(brace);
+    }
+    /// Retains only the braces specified by the predicate.
+    pub fn retain_braces<F>(&mut self, f: F)
+    where
+        F: FnMut(&BraceContainer<S>) -> bool,
+    {
+        self.braces.retain(f);
+    }
+
+    /// Returns `true` if a `surface` brace defines surface `id`, without expanding any range.
+    pub fn defines_surface(&self, id: SurfaceIdType) -> bool {
+        self.braces.iter().any(|v| match v.body() {
+            Brace::Surface(s) => s.defines(id),
+            _ => false,
+        })
+    }
+
+    /// Returns the ranges of surface ids defined by `surface` braces, merged and in ascending
+    /// order.
+    ///
+    /// No range is expanded, so this is as large as the braces, not as the ids they define.
+    pub fn surface_id_ranges(&self) -> Vec<RangeInclusive<SurfaceIdType>> {
+        let bounds = self
+            .braces
+            .iter()
+            .filter_map(|v| match v.body() {
+                Brace::Surface(s) => Some(s.ids()),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(SurfaceId::bounds)
+            .collect();
+
+        let mut result: Vec<RangeInclusive<SurfaceIdType>> = Vec::new();
+        for range in SurfaceId::split(bounds).filter(|v| self.defines_surface(*v.start())) {
+            match result.last_mut() {
+                Some(last) if last.end().checked_add(1) == Some(*range.start()) => {
+                    *last = *last.start()..=*range.end();
+                }
+                _ => result.push(range),
+            }
+        }
+        result
+    }
+
+    /// Returns every surface id defined by `surface` braces, in ascending order.
+    ///
+    /// Ids are yielded one by one from [`ShellSurfaces::surface_id_ranges`], so a large range
+    /// such as `0-4294967295` is never collected. Use [`ShellSurfaces::defines_surface`] to check
+    /// one id.
+    pub fn surface_ids(&self) -> impl Iterator<Item = SurfaceIdType> {
+        self.surface_id_ranges().into_iter().flatten()
+    }
+
+    /// Returns every character configured by `.surface.alias`, `.cursor` and `.tooltips` braces,
+    /// as `charN`.
+    pub fn characters(&self) -> BTreeSet<SurfaceTargetCharacterId> {
+        self.braces
+            .iter()
+            .filter_map(|v| match v.body() {
+                Brace::SurfaceAlias(b) => Some(b.id().normalized()),
+                Brace::Cursor(b) => Some(b.id().normalized()),
+                Brace::Tooltip(b) => Some(b.id().normalized()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
-impl CommentLine {
-    pub fn new(body: String) -> CommentLine {
+impl<S> CommentLine<S> {
+    pub fn new(body: S) -> CommentLine<S> {
         CommentLine { body }
     }
 
-    pub fn body(&self) -> &String {
+    pub fn body(&self) -> &S {
+        &self.body
+    }
+    pub fn body_mut(&mut self) -> &mut S {
+        &mut self.body
+    }
+}
+
+impl<S> BlockComment<S> {
+    pub fn new(body: S) -> BlockComment<S> {
+        BlockComment { body }
+    }
+
+    pub fn body(&self) -> &S {
         &self.body
     }
+    pub fn body_mut(&mut self) -> &mut S {
+        &mut self.body
+    }
+}
+
+impl<S: AsRef<str>> BlockComment<S> {
+    /// Number of lines the comment spans.
+    pub fn lines_len(&self) -> usize {
+        let body = self.body.as_ref();
+        body.matches('\n').count() + body.matches('\r').count() - body.matches("\r\n").count() + 1
+    }
+}
+
+impl<T, S> LineContainer<T, S> {
+    /// A body line written the canonical way.
+    pub fn body_line(body: T) -> LineContainer<T, S> {
+        LineContainer::Body(body, LineFormat::default())
+    }
+
+    /// Returns the body, or `None` for a comment line.
+    pub fn body(&self) -> Option<&T> {
+        match self {
+            LineContainer::Body(v, _) => Some(v),
+            LineContainer::Comment(_) | LineContainer::BlockComment(_) => None,
+        }
+    }
+    /// Returns the body, or `None` for a comment line.
+    ///
+    /// The original text of the line is dropped, since it may no longer match the body.
+    pub fn body_mut(&mut self) -> Option<&mut T> {
+        match self {
+            LineContainer::Body(v, format) => {
+                *format.raw_mut() = None;
+                Some(v)
+            }
+            LineContainer::Comment(_) | LineContainer::BlockComment(_) => None,
+        }
+    }
+    /// Returns the format, or `None` for a comment line.
+    pub fn format(&self) -> Option<&LineFormat<S>> {
+        match self {
+            LineContainer::Body(_, v) => Some(v),
+            LineContainer::Comment(_) | LineContainer::BlockComment(_) => None,
+        }
+    }
+}
+
+impl<T, S: AsRef<str>> LineContainer<T, S> {
+    /// Number of lines the line spans, which is more than 1 only for a block comment.
+    pub fn lines_len(&self) -> usize {
+        match self {
+            LineContainer::BlockComment(v) => v.lines_len(),
+            LineContainer::Comment(_) | LineContainer::Body(..) => 1,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, S: serde::Serialize> serde::Serialize for LineContainer<T, S> {
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        match self {
+            LineContainer::Comment(v) => LineContainerReprRef::Comment(v),
+            LineContainer::BlockComment(v) => LineContainerReprRef::BlockComment(v),
+            LineContainer::Body(body, format) if format.is_empty() => {
+                LineContainerReprRef::Body(body)
+            }
+            LineContainer::Body(body, format) => {
+                LineContainerReprRef::FormattedBody { body, format }
+            }
+        }
+        .serialize(serializer)
+    }
 }
 
-impl BraceContainer {
-    pub fn new(header_comments: Vec<CommentLine>, body: Brace) -> BraceContainer {
+#[cfg(feature = "serde")]
+impl<T, S> From<LineContainerRepr<T, S>> for LineContainer<T, S> {
+    fn from(value: LineContainerRepr<T, S>) -> Self {
+        match value {
+            LineContainerRepr::Comment(v) => LineContainer::Comment(v),
+            LineContainerRepr::BlockComment(v) => LineContainer::BlockComment(v),
+            LineContainerRepr::Body(body) => LineContainer::body_line(body),
+            LineContainerRepr::FormattedBody { body, format } => LineContainer::Body(body, format),
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+impl<T: schemars::JsonSchema, S: schemars::JsonSchema> schemars::JsonSchema
+    for LineContainer<T, S>
+{
+    fn schema_name() -> String {
+        LineContainerRepr::<T, S>::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        LineContainerRepr::<T, S>::json_schema(gen)
+    }
+}
+
+impl<S> LineFormat<S> {
+    pub fn new(raw: Option<S>, comment: Option<S>) -> LineFormat<S> {
+        LineFormat { raw, comment }
+    }
+
+    pub fn raw(&self) -> &Option<S> {
+        &self.raw
+    }
+    pub fn raw_mut(&mut self) -> &mut Option<S> {
+        &mut self.raw
+    }
+    pub fn comment(&self) -> &Option<S> {
+        &self.comment
+    }
+    pub fn comment_mut(&mut self) -> &mut Option<S> {
+        &mut self.comment
+    }
+
+    /// Whether the line is written the canonical way, without a trailing comment.
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_none() && self.comment.is_none()
+    }
+}
+
+impl<S> Default for LineFormat<S> {
+    fn default() -> Self {
+        LineFormat::new(None, None)
+    }
+}
+
+impl<S> BraceContainer<S> {
+    pub fn new(header_comments: Vec<CommentLine<S>>, body: Brace<S>) -> BraceContainer<S> {
         BraceContainer {
             header_comments,
+            layout: BraceLayout::default(),
             body,
         }
     }
 
-    pub fn header_comments(&self) -> &Vec<CommentLine> {
+    pub fn header_comments(&self) -> &Vec<CommentLine<S>> {
         &self.header_comments
     }
-    pub fn body(&self) -> &Brace {
+    pub fn header_comments_mut(&mut self) -> &mut Vec<CommentLine<S>> {
+        &mut self.header_comments
+    }
+    pub fn layout(&self) -> &BraceLayout {
+        &self.layout
+    }
+    pub fn layout_mut(&mut self) -> &mut BraceLayout {
+        &mut self.layout
+    }
+    pub fn body(&self) -> &Brace<S> {
         &self.body
     }
+    pub fn body_mut(&mut self) -> &mut Brace<S> {
+        &mut self.body
+    }
 }
 
-impl Descript {
-    pub fn new(lines: Vec<LineContainer<DescriptInner>>) -> Descript {
+impl<S> Descript<S> {
+    pub fn new(lines: Vec<LineContainer<DescriptInner, S>>) -> Descript<S> {
         Descript { lines }
     }
 
-    pub fn lines(&self) -> &Vec<LineContainer<DescriptInner>> {
+    pub fn lines(&self) -> &Vec<LineContainer<DescriptInner, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<DescriptInner, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<DescriptInner, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<DescriptInner, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
 }
 
-impl Surface {
-    pub fn new(ids: Vec<SurfaceId>, lines: Vec<LineContainer<SurfaceInner>>) -> Surface {
+impl<S> Surface<S> {
+    pub fn new(ids: Vec<SurfaceId>, lines: Vec<LineContainer<SurfaceInner<S>, S>>) -> Surface<S> {
         Surface { ids, lines }
     }
 
     pub fn ids(&self) -> &Vec<SurfaceId> {
         &self.ids
     }
-    pub fn lines(&self) -> &Vec<LineContainer<SurfaceInner>> {
+    pub fn ids_mut(&mut self) -> &mut Vec<SurfaceId> {
+        &mut self.ids
+    }
+    pub fn lines(&self) -> &Vec<LineContainer<SurfaceInner<S>, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<SurfaceInner<S>, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<SurfaceInner<S>, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<SurfaceInner<S>, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
+
+    /// Returns the concrete surface ids defined by this brace, in ascending order.
+    pub fn defined_ids(&self) -> impl Iterator<Item = SurfaceIdType> + '_ {
+        SurfaceId::expand(&self.ids)
+    }
+
+    /// Returns `true` if this brace defines surface `id`.
+    pub fn defines(&self, id: SurfaceIdType) -> bool {
+        SurfaceId::defines(&self.ids, id)
+    }
+
+    /// Returns `true` if lines of this brace apply to surface `id`, which includes surfaces
+    /// defined by other braces and matched by `*` or `N-`.
+    pub fn is_target(&self, id: SurfaceIdType) -> bool {
+        SurfaceId::matches(&self.ids, id)
+    }
 }
 
-impl SurfaceAppend {
-    pub fn new(ids: Vec<SurfaceId>, lines: Vec<LineContainer<SurfaceInner>>) -> SurfaceAppend {
+impl<S> SurfaceAppend<S> {
+    pub fn new(
+        ids: Vec<SurfaceId>,
+        lines: Vec<LineContainer<SurfaceInner<S>, S>>,
+    ) -> SurfaceAppend<S> {
         SurfaceAppend { ids, lines }
     }
 
     pub fn ids(&self) -> &Vec<SurfaceId> {
         &self.ids
     }
-    pub fn lines(&self) -> &Vec<LineContainer<SurfaceInner>> {
+    pub fn ids_mut(&mut self) -> &mut Vec<SurfaceId> {
+        &mut self.ids
+    }
+    pub fn lines(&self) -> &Vec<LineContainer<SurfaceInner<S>, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<SurfaceInner<S>, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<SurfaceInner<S>, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<SurfaceInner<S>, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
+
+    /// Returns `true` if lines of this brace are appended to surface `id`.
+    pub fn is_target(&self, id: SurfaceIdType) -> bool {
+        SurfaceId::matches(&self.ids, id)
+    }
 }
 
-impl SurfaceElement {
+impl<S> SurfaceInner<S> {
+    /// `{id}.balloon.offsetx`. Sakura and kero use their own variants, also when written as
+    /// `char0` and `char1`.
+    pub fn character_balloon_offset_x(id: SurfaceTargetCharacterId, v: CoordinateType) -> Self {
+        match id.index() {
+            0 => SurfaceInner::SakuraBalloonOffsetX(v),
+            1 => SurfaceInner::KeroBalloonOffsetX(v),
+            _ => SurfaceInner::CharacterBalloonOffsetX(id, v),
+        }
+    }
+
+    /// `{id}.balloon.offsety`. Sakura and kero use their own variants, also when written as
+    /// `char0` and `char1`.
+    pub fn character_balloon_offset_y(id: SurfaceTargetCharacterId, v: CoordinateType) -> Self {
+        match id.index() {
+            0 => SurfaceInner::SakuraBalloonOffsetY(v),
+            1 => SurfaceInner::KeroBalloonOffsetY(v),
+            _ => SurfaceInner::CharacterBalloonOffsetY(id, v),
+        }
+    }
+
+    /// Returns the character of a balloon offset, or `None` for the generic `balloon.offset*`
+    /// and other lines.
+    pub fn balloon_offset_character(&self) -> Option<SurfaceTargetCharacterId> {
+        match self {
+            SurfaceInner::SakuraBalloonOffsetX(_) | SurfaceInner::SakuraBalloonOffsetY(_) => {
+                Some(SurfaceTargetCharacterId::Sakura)
+            }
+            SurfaceInner::KeroBalloonOffsetX(_) | SurfaceInner::KeroBalloonOffsetY(_) => {
+                Some(SurfaceTargetCharacterId::Kero)
+            }
+            SurfaceInner::CharacterBalloonOffsetX(id, _)
+            | SurfaceInner::CharacterBalloonOffsetY(id, _) => Some(id.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl SurfaceId {
+    /// Returns `true` if `id` is in this unit or range. `Not` never contains any id.
+    pub fn contains(&self, id: SurfaceIdType) -> bool {
+        match self {
+            SurfaceId::Unit(v) => *v == id,
+            SurfaceId::Range(start, end) => (*start..=*end).contains(&id),
+            SurfaceId::RangeFrom(start) => *start <= id,
+            SurfaceId::All => true,
+            SurfaceId::Not(_) => false,
+        }
+    }
+
+    /// Returns `true` if this id is `*` or `N-`, which never ends.
+    pub fn is_open(&self) -> bool {
+        matches!(self, SurfaceId::RangeFrom(_) | SurfaceId::All)
+    }
+
+    /// Returns `true` if `id` is contained in `ids` and not excluded by any `Not`.
+    pub fn matches(ids: &[SurfaceId], id: SurfaceIdType) -> bool {
+        ids.iter().any(|v| v.contains(id))
+            && !ids.iter().any(|v| match v {
+                SurfaceId::Not(inner) => inner.contains(id),
+                _ => false,
+            })
+    }
+
+    /// Returns `true` if `ids` define surface `id`, which is in a unit or range and not excluded
+    /// by any `Not`.
+    pub fn defines(ids: &[SurfaceId], id: SurfaceIdType) -> bool {
+        ids.iter().any(|v| !v.is_open() && v.contains(id)) && SurfaceId::matches(ids, id)
+    }
+
+    /// Expands `ids` into the concrete surface ids they define, in ascending order.
+    ///
+    /// Ids are yielded one by one, so a large range such as `0-4294967295` is never collected.
+    /// `*` and `N-` define no id, since they only apply to surfaces defined elsewhere.
+    /// Use [`SurfaceId::expand_within`] to resolve them against defined surfaces.
+    pub fn expand(ids: &[SurfaceId]) -> impl Iterator<Item = SurfaceIdType> + '_ {
+        let mut ranges: Vec<(SurfaceIdType, SurfaceIdType)> = ids
+            .iter()
+            .filter_map(|v| match v {
+                SurfaceId::Unit(v) => Some((*v, *v)),
+                SurfaceId::Range(start, end) => Some((*start, *end)),
+                SurfaceId::RangeFrom(_) | SurfaceId::All | SurfaceId::Not(_) => None,
+            })
+            .collect();
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(SurfaceIdType, SurfaceIdType)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        merged
+            .into_iter()
+            .flat_map(|(start, end)| start..=end)
+            .filter(move |v| SurfaceId::matches(ids, *v))
+    }
+
+    /// Returns the ids where this id starts or stops containing ids, which are the start and one
+    /// past the end of each unit or range.
+    pub(crate) fn bounds(&self) -> Vec<SurfaceIdType> {
+        match self {
+            SurfaceId::Unit(v) => [Some(*v), v.checked_add(1)].into_iter().flatten().collect(),
+            SurfaceId::Range(start, end) => [Some(*start), end.checked_add(1)]
+                .into_iter()
+                .flatten()
+                .collect(),
+            SurfaceId::RangeFrom(start) => vec![*start],
+            SurfaceId::All => Vec::new(),
+            SurfaceId::Not(inner) => inner.bounds(),
+        }
+    }
+
+    /// Splits every surface id into ranges starting at `0` and at each of `bounds`.
+    ///
+    /// When `bounds` are collected from [`SurfaceId::bounds`] of some ids, every id in a range is
+    /// matched and defined by the same of them.
+    pub(crate) fn split(
+        bounds: BTreeSet<SurfaceIdType>,
+    ) -> impl Iterator<Item = RangeInclusive<SurfaceIdType>> {
+        let starts: Vec<SurfaceIdType> = std::iter::once(0)
+            .chain(bounds.into_iter().filter(|v| *v != 0))
+            .collect();
+        (0..starts.len())
+            .map(move |i| starts[i]..=starts.get(i + 1).map_or(SurfaceIdType::MAX, |v| v - 1))
+    }
+
+    /// Expands `ids` into the concrete surface ids, taking ids matched by `*` and `N-` from
+    /// `defined`.
+    pub fn expand_within(
+        ids: &[SurfaceId],
+        defined: &BTreeSet<SurfaceIdType>,
+    ) -> BTreeSet<SurfaceIdType> {
+        let mut result: BTreeSet<SurfaceIdType> = SurfaceId::expand(ids).collect();
+        result.extend(defined.iter().filter(|v| SurfaceId::matches(ids, **v)));
+        result
+    }
+}
+
+impl<S> SurfaceElement<S> {
     pub fn new(
         id: ElementIdType,
         method: DrawMethod,
-        filename: String,
+        filename: S,
         x: CoordinateType,
         y: CoordinateType,
-    ) -> SurfaceElement {
+    ) -> SurfaceElement<S> {
         SurfaceElement {
             id,
             method,
@@ -412,18 +1085,33 @@ impl SurfaceElement {
     pub fn id(&self) -> &ElementIdType {
         &self.id
     }
+    pub fn id_mut(&mut self) -> &mut ElementIdType {
+        &mut self.id
+    }
     pub fn method(&self) -> &DrawMethod {
         &self.method
     }
-    pub fn filename(&self) -> &String {
+    pub fn method_mut(&mut self) -> &mut DrawMethod {
+        &mut self.method
+    }
+    pub fn filename(&self) -> &S {
         &self.filename
     }
+    pub fn filename_mut(&mut self) -> &mut S {
+        &mut self.filename
+    }
     pub fn x(&self) -> &CoordinateType {
         &self.x
     }
+    pub fn x_mut(&mut self) -> &mut CoordinateType {
+        &mut self.x
+    }
     pub fn y(&self) -> &CoordinateType {
         &self.y
     }
+    pub fn y_mut(&mut self) -> &mut CoordinateType {
+        &mut self.y
+    }
 }
 
 impl SurfaceAnimationInterval {
@@ -434,9 +1122,15 @@ impl SurfaceAnimationInterval {
     pub fn id(&self) -> &AnimationIdType {
         &self.id
     }
+    pub fn id_mut(&mut self) -> &mut AnimationIdType {
+        &mut self.id
+    }
     pub fn intervals(&self) -> &Vec<AnimationInterval> {
         &self.intervals
     }
+    pub fn intervals_mut(&mut self) -> &mut Vec<AnimationInterval> {
+        &mut self.intervals
+    }
 }
 
 impl SurfaceAnimationPattern {
@@ -455,12 +1149,21 @@ impl SurfaceAnimationPattern {
     pub fn id(&self) -> &AnimationIdType {
         &self.id
     }
+    pub fn id_mut(&mut self) -> &mut AnimationIdType {
+        &mut self.id
+    }
     pub fn pattern_id(&self) -> &AnimationPatternIdType {
         &self.pattern_id
     }
+    pub fn pattern_id_mut(&mut self) -> &mut AnimationPatternIdType {
+        &mut self.pattern_id
+    }
     pub fn method(&self) -> &AnimationPatternDrawMethod {
         &self.method
     }
+    pub fn method_mut(&mut self) -> &mut AnimationPatternDrawMethod {
+        &mut self.method
+    }
 }
 
 impl AnimationPatternProperty {
@@ -481,15 +1184,27 @@ impl AnimationPatternProperty {
     pub fn surface_id(&self) -> &SurfaceIdPointerType {
         &self.surface_id
     }
+    pub fn surface_id_mut(&mut self) -> &mut SurfaceIdPointerType {
+        &mut self.surface_id
+    }
     pub fn weight(&self) -> &u32 {
         &self.weight
     }
+    pub fn weight_mut(&mut self) -> &mut u32 {
+        &mut self.weight
+    }
     pub fn x(&self) -> &CoordinateType {
         &self.x
     }
+    pub fn x_mut(&mut self) -> &mut CoordinateType {
+        &mut self.x
+    }
     pub fn y(&self) -> &CoordinateType {
         &self.y
     }
+    pub fn y_mut(&mut self) -> &mut CoordinateType {
+        &mut self.y
+    }
 }
 
 impl SurfaceAnimationOption {
@@ -500,46 +1215,70 @@ impl SurfaceAnimationOption {
     pub fn id(&self) -> &AnimationIdType {
         &self.id
     }
+    pub fn id_mut(&mut self) -> &mut AnimationIdType {
+        &mut self.id
+    }
     pub fn options(&self) -> &Vec<AnimationOptionKind> {
         &self.options
     }
+    pub fn options_mut(&mut self) -> &mut Vec<AnimationOptionKind> {
+        &mut self.options
+    }
 }
 
-impl SurfaceAnimationCollision {
-    pub fn new(id: AnimationIdType, collision: SurfaceCollision) -> SurfaceAnimationCollision {
+impl<S> SurfaceAnimationCollision<S> {
+    pub fn new(
+        id: AnimationIdType,
+        collision: SurfaceCollision<S>,
+    ) -> SurfaceAnimationCollision<S> {
         SurfaceAnimationCollision { id, collision }
     }
 
     pub fn id(&self) -> &AnimationIdType {
         &self.id
     }
-    pub fn collision(&self) -> &SurfaceCollision {
+    pub fn id_mut(&mut self) -> &mut AnimationIdType {
+        &mut self.id
+    }
+    pub fn collision(&self) -> &SurfaceCollision<S> {
         &self.collision
     }
+    pub fn collision_mut(&mut self) -> &mut SurfaceCollision<S> {
+        &mut self.collision
+    }
 }
 
-impl SurfaceAnimationCollisionEx {
-    pub fn new(id: AnimationIdType, collision: SurfaceCollisionEx) -> SurfaceAnimationCollisionEx {
+impl<S> SurfaceAnimationCollisionEx<S> {
+    pub fn new(
+        id: AnimationIdType,
+        collision: SurfaceCollisionEx<S>,
+    ) -> SurfaceAnimationCollisionEx<S> {
         SurfaceAnimationCollisionEx { id, collision }
     }
 
     pub fn id(&self) -> &AnimationIdType {
         &self.id
     }
-    pub fn collision(&self) -> &SurfaceCollisionEx {
+    pub fn id_mut(&mut self) -> &mut AnimationIdType {
+        &mut self.id
+    }
+    pub fn collision(&self) -> &SurfaceCollisionEx<S> {
         &self.collision
     }
+    pub fn collision_mut(&mut self) -> &mut SurfaceCollisionEx<S> {
+        &mut self.collision
+    }
 }
 
-impl SurfaceCollision {
+impl<S> SurfaceCollision<S> {
     pub fn new(
         id: CollisionIdType,
         start_x: CoordinateType,
         start_y: CoordinateType,
         end_x: CoordinateType,
         end_y: CoordinateType,
-        target_id: String,
-    ) -> SurfaceCollision {
+        target_id: S,
+    ) -> SurfaceCollision<S> {
         SurfaceCollision {
             id,
             start_x,
@@ -553,29 +1292,47 @@ impl SurfaceCollision {
     pub fn id(&self) -> &CollisionIdType {
         &self.id
     }
+    pub fn id_mut(&mut self) -> &mut CollisionIdType {
+        &mut self.id
+    }
     pub fn start_x(&self) -> &CoordinateType {
         &self.start_x
     }
+    pub fn start_x_mut(&mut self) -> &mut CoordinateType {
+        &mut self.start_x
+    }
     pub fn start_y(&self) -> &CoordinateType {
         &self.start_y
     }
+    pub fn start_y_mut(&mut self) -> &mut CoordinateType {
+        &mut self.start_y
+    }
     pub fn end_x(&self) -> &CoordinateType {
         &self.end_x
     }
+    pub fn end_x_mut(&mut self) -> &mut CoordinateType {
+        &mut self.end_x
+    }
     pub fn end_y(&self) -> &CoordinateType {
         &self.end_y
     }
-    pub fn target_id(&self) -> &String {
+    pub fn end_y_mut(&mut self) -> &mut CoordinateType {
+        &mut self.end_y
+    }
+    pub fn target_id(&self) -> &S {
         &self.target_id
     }
+    pub fn target_id_mut(&mut self) -> &mut S {
+        &mut self.target_id
+    }
 }
 
-impl SurfaceCollisionEx {
+impl<S> SurfaceCollisionEx<S> {
     pub fn new(
         id: CollisionIdType,
-        target_id: String,
-        kind: CollisionExKind,
-    ) -> SurfaceCollisionEx {
+        target_id: S,
+        kind: CollisionExKind<S>,
+    ) -> SurfaceCollisionEx<S> {
         SurfaceCollisionEx {
             id,
             target_id,
@@ -586,66 +1343,144 @@ impl SurfaceCollisionEx {
     pub fn id(&self) -> &CollisionIdType {
         &self.id
     }
-    pub fn target_id(&self) -> &String {
+    pub fn id_mut(&mut self) -> &mut CollisionIdType {
+        &mut self.id
+    }
+    pub fn target_id(&self) -> &S {
         &self.target_id
     }
-    pub fn kind(&self) -> &CollisionExKind {
+    pub fn target_id_mut(&mut self) -> &mut S {
+        &mut self.target_id
+    }
+    pub fn kind(&self) -> &CollisionExKind<S> {
         &self.kind
     }
+    pub fn kind_mut(&mut self) -> &mut CollisionExKind<S> {
+        &mut self.kind
+    }
+}
+
+impl SurfaceTargetCharacterId {
+    /// Number of the character. `sakura` is 0 and `kero` is 1.
+    pub fn index(&self) -> u32 {
+        match self {
+            SurfaceTargetCharacterId::Sakura => 0,
+            SurfaceTargetCharacterId::Kero => 1,
+            SurfaceTargetCharacterId::Char(v) => *v,
+        }
+    }
+
+    /// Returns the same character as `charN` (e.g. `char0` for `sakura`).
+    pub fn normalized(&self) -> SurfaceTargetCharacterId {
+        SurfaceTargetCharacterId::Char(self.index())
+    }
 }
 
-impl SurfaceAlias {
+impl<S> SurfaceAlias<S> {
     pub fn new(
         id: SurfaceTargetCharacterId,
-        lines: Vec<LineContainer<SurfaceAliasInner>>,
-    ) -> SurfaceAlias {
+        lines: Vec<LineContainer<SurfaceAliasInner<S>, S>>,
+    ) -> SurfaceAlias<S> {
         SurfaceAlias { id, lines }
     }
 
     pub fn id(&self) -> &SurfaceTargetCharacterId {
         &self.id
     }
-    pub fn lines(&self) -> &Vec<LineContainer<SurfaceAliasInner>> {
+    pub fn id_mut(&mut self) -> &mut SurfaceTargetCharacterId {
+        &mut self.id
+    }
+    pub fn lines(&self) -> &Vec<LineContainer<SurfaceAliasInner<S>, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<SurfaceAliasInner<S>, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<SurfaceAliasInner<S>, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<SurfaceAliasInner<S>, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
 }
 
-impl SurfaceAliasInner {
-    pub fn new(target: String, surfaces: Vec<SurfaceIdType>) -> SurfaceAliasInner {
+impl<'a, S: From<&'a str>> From<&'a str> for AliasTarget<S> {
+    /// Reads `text` as an id when it is written as one, otherwise as a name.
+    /// See [`AliasTarget`] for the rule.
+    fn from(text: &'a str) -> Self {
+        let canonical =
+            text.bytes().all(|v| v.is_ascii_digit()) && (text == "0" || !text.starts_with('0'));
+        match text.parse::<SurfaceIdType>() {
+            Ok(id) if canonical => AliasTarget::Id(id),
+            _ => AliasTarget::Name(S::from(text)),
+        }
+    }
+}
+
+impl<S> SurfaceAliasInner<S> {
+    pub fn new(target: AliasTarget<S>, surfaces: Vec<SurfaceIdType>) -> SurfaceAliasInner<S> {
         SurfaceAliasInner { target, surfaces }
     }
 
-    pub fn target(&self) -> &String {
+    pub fn target(&self) -> &AliasTarget<S> {
         &self.target
     }
+    pub fn target_mut(&mut self) -> &mut AliasTarget<S> {
+        &mut self.target
+    }
     pub fn surfaces(&self) -> &Vec<SurfaceIdType> {
         &self.surfaces
     }
+    pub fn surfaces_mut(&mut self) -> &mut Vec<SurfaceIdType> {
+        &mut self.surfaces
+    }
 }
 
-impl ShellSurfacesCursor {
+impl<S> ShellSurfacesCursor<S> {
     pub fn new(
         id: SurfaceTargetCharacterId,
-        lines: Vec<LineContainer<ShellSurfacesCursorGesture>>,
-    ) -> ShellSurfacesCursor {
+        lines: Vec<LineContainer<ShellSurfacesCursorGesture<S>, S>>,
+    ) -> ShellSurfacesCursor<S> {
         ShellSurfacesCursor { id, lines }
     }
 
     pub fn id(&self) -> &SurfaceTargetCharacterId {
         &self.id
     }
-    pub fn lines(&self) -> &Vec<LineContainer<ShellSurfacesCursorGesture>> {
+    pub fn id_mut(&mut self) -> &mut SurfaceTargetCharacterId {
+        &mut self.id
+    }
+    pub fn lines(&self) -> &Vec<LineContainer<ShellSurfacesCursorGesture<S>, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<ShellSurfacesCursorGesture<S>, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<ShellSurfacesCursorGesture<S>, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<ShellSurfacesCursorGesture<S>, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
 }
 
-impl ShellSurfacesCursorGesture {
+impl<S> ShellSurfacesCursorGesture<S> {
     pub fn new(
         kind: GestureKind,
         id: CursorMouseIdType,
-        target_collistion: String,
-        filename: String,
-    ) -> ShellSurfacesCursorGesture {
+        target_collistion: S,
+        filename: S,
+    ) -> ShellSurfacesCursorGesture<S> {
         ShellSurfacesCursorGesture {
             kind,
             id,
@@ -657,42 +1492,595 @@ impl ShellSurfacesCursorGesture {
     pub fn kind(&self) -> &GestureKind {
         &self.kind
     }
+    pub fn kind_mut(&mut self) -> &mut GestureKind {
+        &mut self.kind
+    }
     pub fn id(&self) -> &CursorMouseIdType {
         &self.id
     }
-    pub fn target_collistion(&self) -> &String {
+    pub fn id_mut(&mut self) -> &mut CursorMouseIdType {
+        &mut self.id
+    }
+    pub fn target_collistion(&self) -> &S {
         &self.target_collistion
     }
-    pub fn filename(&self) -> &String {
+    pub fn target_collistion_mut(&mut self) -> &mut S {
+        &mut self.target_collistion
+    }
+    pub fn filename(&self) -> &S {
         &self.filename
     }
+    pub fn filename_mut(&mut self) -> &mut S {
+        &mut self.filename
+    }
 }
 
-impl Tooltip {
-    pub fn new(id: SurfaceTargetCharacterId, lines: Vec<LineContainer<TooltipInner>>) -> Tooltip {
+impl<S> Tooltip<S> {
+    pub fn new(
+        id: SurfaceTargetCharacterId,
+        lines: Vec<LineContainer<TooltipInner<S>, S>>,
+    ) -> Tooltip<S> {
         Tooltip { id, lines }
     }
 
     pub fn id(&self) -> &SurfaceTargetCharacterId {
         &self.id
     }
-    pub fn lines(&self) -> &Vec<LineContainer<TooltipInner>> {
+    pub fn id_mut(&mut self) -> &mut SurfaceTargetCharacterId {
+        &mut self.id
+    }
+    pub fn lines(&self) -> &Vec<LineContainer<TooltipInner<S>, S>> {
         &self.lines
     }
+    pub fn lines_mut(&mut self) -> &mut Vec<LineContainer<TooltipInner<S>, S>> {
+        &mut self.lines
+    }
+
+    pub fn push_line(&mut self, line: LineContainer<TooltipInner<S>, S>) {
+        self.lines.push(line);
+    }
+    /// Retains only the lines specified by the predicate.
+    pub fn retain_lines<F>(&mut self, f: F)
+    where
+        F: FnMut(&LineContainer<TooltipInner<S>, S>) -> bool,
+    {
+        self.lines.retain(f);
+    }
 }
 
-impl TooltipInner {
-    pub fn new(collision: String, description: String) -> TooltipInner {
+impl<S> TooltipInner<S> {
+    pub fn new(collision: S, description: S) -> TooltipInner<S> {
         TooltipInner {
             collision,
             description,
         }
     }
 
-    pub fn collision(&self) -> &String {
+    pub fn collision(&self) -> &S {
         &self.collision
     }
-    pub fn description(&self) -> &String {
+    pub fn collision_mut(&mut self) -> &mut S {
+        &mut self.collision
+    }
+    pub fn description(&self) -> &S {
         &self.description
     }
+    pub fn description_mut(&mut self) -> &mut S {
+        &mut self.description
+    }
+}
+
+/// Conversion of a node borrowing its strings, such as one from
+/// [`parse_borrowed`](crate::parse::parse_borrowed), into the owned form.
+pub trait IntoOwned {
+    type Owned;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+impl<S: Into<String>> IntoOwned for ShellSurfaces<S> {
+    type Owned = ShellSurfaces;
+
+    fn into_owned(self) -> Self::Owned {
+        ShellSurfaces {
+            header_comments: self.header_comments.into_owned(),
+            charset: self.charset,
+            charset_declared: self.charset_declared,
+            braces: self.braces.into_owned(),
+            footer_comments: self.footer_comments.into_owned(),
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for BlockComment<S> {
+    type Owned = BlockComment;
+
+    fn into_owned(self) -> Self::Owned {
+        BlockComment::new(self.body.into())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for CommentLine<S> {
+    type Owned = CommentLine;
+
+    fn into_owned(self) -> Self::Owned {
+        CommentLine::new(self.body.into())
+    }
+}
+
+impl<T: IntoOwned, S: Into<String>> IntoOwned for LineContainer<T, S> {
+    type Owned = LineContainer<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            LineContainer::Comment(v) => LineContainer::Comment(v.into_owned()),
+            LineContainer::BlockComment(v) => LineContainer::BlockComment(v.into_owned()),
+            LineContainer::Body(v, format) => {
+                LineContainer::Body(v.into_owned(), format.into_owned())
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for LineFormat<S> {
+    type Owned = LineFormat;
+
+    fn into_owned(self) -> Self::Owned {
+        LineFormat::new(self.raw.map(Into::into), self.comment.map(Into::into))
+    }
+}
+
+impl<S: Into<String>> IntoOwned for BraceContainer<S> {
+    type Owned = BraceContainer;
+
+    fn into_owned(self) -> Self::Owned {
+        let mut result =
+            BraceContainer::new(self.header_comments.into_owned(), self.body.into_owned());
+        result.layout = self.layout;
+        result
+    }
+}
+
+impl<S: Into<String>> IntoOwned for Brace<S> {
+    type Owned = Brace;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Brace::Descript(v) => Brace::Descript(v.into_owned()),
+            Brace::Surface(v) => Brace::Surface(v.into_owned()),
+            Brace::SurfaceAppend(v) => Brace::SurfaceAppend(v.into_owned()),
+            Brace::SurfaceAlias(v) => Brace::SurfaceAlias(v.into_owned()),
+            Brace::Cursor(v) => Brace::Cursor(v.into_owned()),
+            Brace::Tooltip(v) => Brace::Tooltip(v.into_owned()),
+            Brace::BlockComment(v) => Brace::BlockComment(v.into_owned()),
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for Descript<S> {
+    type Owned = Descript;
+
+    fn into_owned(self) -> Self::Owned {
+        Descript::new(self.lines.into_owned())
+    }
+}
+
+impl IntoOwned for DescriptInner {
+    type Owned = DescriptInner;
+
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
+
+impl<S: Into<String>> IntoOwned for Surface<S> {
+    type Owned = Surface;
+
+    fn into_owned(self) -> Self::Owned {
+        Surface::new(self.ids, self.lines.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceAppend<S> {
+    type Owned = SurfaceAppend;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceAppend::new(self.ids, self.lines.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceInner<S> {
+    type Owned = SurfaceInner;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            SurfaceInner::Element(v) => SurfaceInner::Element(v.into_owned()),
+            SurfaceInner::AnimationInterval(v) => SurfaceInner::AnimationInterval(v),
+            SurfaceInner::AnimationPattern(v) => SurfaceInner::AnimationPattern(v),
+            SurfaceInner::AnimationOption(v) => SurfaceInner::AnimationOption(v),
+            SurfaceInner::AnimationCollision(v) => SurfaceInner::AnimationCollision(v.into_owned()),
+            SurfaceInner::AnimationCollisionEx(v) => {
+                SurfaceInner::AnimationCollisionEx(v.into_owned())
+            }
+            SurfaceInner::Collision(v) => SurfaceInner::Collision(v.into_owned()),
+            SurfaceInner::CollisionEx(v) => SurfaceInner::CollisionEx(v.into_owned()),
+            SurfaceInner::SakuraBalloonOffsetX(v) => SurfaceInner::SakuraBalloonOffsetX(v),
+            SurfaceInner::SakuraBalloonOffsetY(v) => SurfaceInner::SakuraBalloonOffsetY(v),
+            SurfaceInner::KeroBalloonOffsetX(v) => SurfaceInner::KeroBalloonOffsetX(v),
+            SurfaceInner::KeroBalloonOffsetY(v) => SurfaceInner::KeroBalloonOffsetY(v),
+            SurfaceInner::BalloonOffsetX(v) => SurfaceInner::BalloonOffsetX(v),
+            SurfaceInner::BalloonOffsetY(v) => SurfaceInner::BalloonOffsetY(v),
+            SurfaceInner::CharacterBalloonOffsetX(id, v) => {
+                SurfaceInner::CharacterBalloonOffsetX(id, v)
+            }
+            SurfaceInner::CharacterBalloonOffsetY(id, v) => {
+                SurfaceInner::CharacterBalloonOffsetY(id, v)
+            }
+            SurfaceInner::PointCenterX(v) => SurfaceInner::PointCenterX(v),
+            SurfaceInner::PointCenterY(v) => SurfaceInner::PointCenterY(v),
+            SurfaceInner::PointKinokoCenterX(v) => SurfaceInner::PointKinokoCenterX(v),
+            SurfaceInner::PointKinokoCenterY(v) => SurfaceInner::PointKinokoCenterY(v),
+            SurfaceInner::PointBaseposX(v) => SurfaceInner::PointBaseposX(v),
+            SurfaceInner::PointBaseposY(v) => SurfaceInner::PointBaseposY(v),
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceElement<S> {
+    type Owned = SurfaceElement;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceElement::new(self.id, self.method, self.filename.into(), self.x, self.y)
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceAnimationCollision<S> {
+    type Owned = SurfaceAnimationCollision;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceAnimationCollision::new(self.id, self.collision.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceAnimationCollisionEx<S> {
+    type Owned = SurfaceAnimationCollisionEx;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceAnimationCollisionEx::new(self.id, self.collision.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceCollision<S> {
+    type Owned = SurfaceCollision;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceCollision::new(
+            self.id,
+            self.start_x,
+            self.start_y,
+            self.end_x,
+            self.end_y,
+            self.target_id.into(),
+        )
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceCollisionEx<S> {
+    type Owned = SurfaceCollisionEx;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceCollisionEx::new(self.id, self.target_id.into(), self.kind.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for CollisionExKind<S> {
+    type Owned = CollisionExKind;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            CollisionExKind::Rect(a, b, c, d) => CollisionExKind::Rect(a, b, c, d),
+            CollisionExKind::Ellipse(a, b, c, d) => CollisionExKind::Ellipse(a, b, c, d),
+            CollisionExKind::Circle(a, b, c) => CollisionExKind::Circle(a, b, c),
+            CollisionExKind::Polygon(v) => CollisionExKind::Polygon(v),
+            CollisionExKind::Region(filename, r, g, b, flag) => {
+                CollisionExKind::Region(filename.into(), r, g, b, flag)
+            }
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceAlias<S> {
+    type Owned = SurfaceAlias;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceAlias::new(self.id, self.lines.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for AliasTarget<S> {
+    type Owned = AliasTarget;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            AliasTarget::Id(v) => AliasTarget::Id(v),
+            AliasTarget::Name(v) => AliasTarget::Name(v.into()),
+        }
+    }
+}
+
+impl<S: Into<String>> IntoOwned for SurfaceAliasInner<S> {
+    type Owned = SurfaceAliasInner;
+
+    fn into_owned(self) -> Self::Owned {
+        SurfaceAliasInner::new(self.target.into_owned(), self.surfaces)
+    }
+}
+
+impl<S: Into<String>> IntoOwned for ShellSurfacesCursor<S> {
+    type Owned = ShellSurfacesCursor;
+
+    fn into_owned(self) -> Self::Owned {
+        ShellSurfacesCursor::new(self.id, self.lines.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for ShellSurfacesCursorGesture<S> {
+    type Owned = ShellSurfacesCursorGesture;
+
+    fn into_owned(self) -> Self::Owned {
+        ShellSurfacesCursorGesture::new(
+            self.kind,
+            self.id,
+            self.target_collistion.into(),
+            self.filename.into(),
+        )
+    }
+}
+
+impl<S: Into<String>> IntoOwned for Tooltip<S> {
+    type Owned = Tooltip;
+
+    fn into_owned(self) -> Self::Owned {
+        Tooltip::new(self.id, self.lines.into_owned())
+    }
+}
+
+impl<S: Into<String>> IntoOwned for TooltipInner<S> {
+    type Owned = TooltipInner;
+
+    fn into_owned(self) -> Self::Owned {
+        TooltipInner::new(self.collision.into(), self.description.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod shell_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_editing_in_place() {
+            let mut case = ShellSurfaces::new(
+                vec![],
+                Charset::UTF8,
+                vec![
+                    BraceContainer::new(
+                        vec![],
+                        Brace::Surface(Surface::new(
+                            vec![SurfaceId::Unit(0)],
+                            vec![
+                                LineContainer::body_line(SurfaceInner::Collision(
+                                    SurfaceCollision::new(0, 10, 10, 100, 100, "Head".to_string()),
+                                )),
+                                LineContainer::Comment(CommentLine::new("".to_string())),
+                            ],
+                        )),
+                    ),
+                    BraceContainer::new(vec![], Brace::Descript(Descript::new(vec![]))),
+                ],
+                vec![],
+            );
+
+            for brace in case.braces_mut() {
+                if let Brace::Surface(s) = brace.body_mut() {
+                    for line in s.lines_mut() {
+                        if let Some(SurfaceInner::Collision(c)) = line.body_mut() {
+                            *c.start_x_mut() = 20;
+                            c.target_id_mut().push_str("Top");
+                        }
+                    }
+                    s.retain_lines(|v| v.body().is_some());
+                    s.push_line(LineContainer::body_line(SurfaceInner::PointCenterX(5)));
+                }
+            }
+            case.retain_braces(|v| !matches!(v.body(), Brace::Descript(_)));
+
+            assert_eq!(
+                case.braces(),
+                &vec![BraceContainer::new(
+                    vec![],
+                    Brace::Surface(Surface::new(
+                        vec![SurfaceId::Unit(0)],
+                        vec![
+                            LineContainer::body_line(SurfaceInner::Collision(
+                                SurfaceCollision::new(0, 20, 10, 100, 100, "HeadTop".to_string())
+                            )),
+                            LineContainer::body_line(SurfaceInner::PointCenterX(5)),
+                        ],
+                    )),
+                )]
+            );
+        }
+    }
+
+    mod alias_target {
+        use super::*;
+
+        #[test]
+        fn success_when_id() {
+            assert_eq!(AliasTarget::<String>::from("0"), AliasTarget::Id(0));
+            assert_eq!(AliasTarget::<String>::from("10"), AliasTarget::Id(10));
+            assert_eq!(
+                AliasTarget::<String>::from("4294967295"),
+                AliasTarget::Id(4294967295)
+            );
+        }
+
+        #[test]
+        fn success_when_name() {
+            for case in ["笑顔", "00", "01", "+1", "-1", "1 ", "4294967296", ""] {
+                assert_eq!(
+                    AliasTarget::<String>::from(case),
+                    AliasTarget::Name(case.to_string())
+                );
+            }
+        }
+    }
+
+    mod surface_inner {
+        use super::*;
+
+        #[test]
+        fn success_when_character_balloon_offset() {
+            let cases: [(SurfaceTargetCharacterId, SurfaceInner, SurfaceInner); 5] = [
+                (
+                    SurfaceTargetCharacterId::Sakura,
+                    SurfaceInner::SakuraBalloonOffsetX(10),
+                    SurfaceInner::SakuraBalloonOffsetY(10),
+                ),
+                (
+                    SurfaceTargetCharacterId::Char(0),
+                    SurfaceInner::SakuraBalloonOffsetX(10),
+                    SurfaceInner::SakuraBalloonOffsetY(10),
+                ),
+                (
+                    SurfaceTargetCharacterId::Kero,
+                    SurfaceInner::KeroBalloonOffsetX(10),
+                    SurfaceInner::KeroBalloonOffsetY(10),
+                ),
+                (
+                    SurfaceTargetCharacterId::Char(1),
+                    SurfaceInner::KeroBalloonOffsetX(10),
+                    SurfaceInner::KeroBalloonOffsetY(10),
+                ),
+                (
+                    SurfaceTargetCharacterId::Char(2),
+                    SurfaceInner::CharacterBalloonOffsetX(SurfaceTargetCharacterId::Char(2), 10),
+                    SurfaceInner::CharacterBalloonOffsetY(SurfaceTargetCharacterId::Char(2), 10),
+                ),
+            ];
+            for (id, x, y) in cases {
+                assert_eq!(SurfaceInner::character_balloon_offset_x(id.clone(), 10), x);
+                assert_eq!(SurfaceInner::character_balloon_offset_y(id, 10), y);
+            }
+        }
+    }
+
+    mod surface_target_character_id {
+        use super::*;
+
+        #[test]
+        fn success_when_normalized() {
+            assert_eq!(
+                SurfaceTargetCharacterId::Sakura.normalized(),
+                SurfaceTargetCharacterId::Char(0)
+            );
+            assert_eq!(
+                SurfaceTargetCharacterId::Kero.normalized(),
+                SurfaceTargetCharacterId::Char(1)
+            );
+            assert_eq!(
+                SurfaceTargetCharacterId::Char(2).normalized(),
+                SurfaceTargetCharacterId::Char(2)
+            );
+        }
+    }
+
+    mod surface_id {
+        use super::*;
+
+        #[test]
+        fn success_when_matched_ids() {
+            let case = vec![
+                SurfaceId::Range(1, 30),
+                SurfaceId::Not(Box::new(SurfaceId::Unit(15))),
+                SurfaceId::Not(Box::new(SurfaceId::Range(20, 25))),
+            ];
+            assert!(SurfaceId::matches(&case, 1));
+            assert!(SurfaceId::matches(&case, 26));
+            assert!(!SurfaceId::matches(&case, 0));
+            assert!(!SurfaceId::matches(&case, 15));
+            assert!(!SurfaceId::matches(&case, 22));
+
+            let result: Vec<_> = SurfaceId::expand(&case).collect();
+            assert_eq!(result.len(), 23);
+            assert!(!result.contains(&15));
+            assert!(SurfaceId::defines(&case, 26));
+            assert!(!SurfaceId::defines(&case, 15));
+        }
+
+        #[test]
+        fn success_when_overlapping_and_large_ids() {
+            let case = vec![
+                SurfaceId::Range(5, 8),
+                SurfaceId::Unit(2),
+                SurfaceId::Range(3, 6),
+                SurfaceId::Unit(8),
+            ];
+            assert_eq!(
+                SurfaceId::expand(&case).collect::<Vec<_>>(),
+                vec![2, 3, 4, 5, 6, 7, 8]
+            );
+
+            let case = vec![SurfaceId::Range(0, SurfaceIdType::MAX)];
+            let mut result = SurfaceId::expand(&case);
+            assert_eq!(result.next(), Some(0));
+            assert_eq!(result.next(), Some(1));
+            assert!(SurfaceId::defines(&case, SurfaceIdType::MAX));
+        }
+
+        #[test]
+        fn success_when_open_ids() {
+            let case = vec![
+                SurfaceId::RangeFrom(10),
+                SurfaceId::Not(Box::new(SurfaceId::RangeFrom(20))),
+            ];
+            assert!(SurfaceId::matches(&case, 10));
+            assert!(SurfaceId::matches(&case, 19));
+            assert!(!SurfaceId::matches(&case, 9));
+            assert!(!SurfaceId::matches(&case, 20));
+            assert_eq!(SurfaceId::expand(&case).next(), None);
+
+            let defined = BTreeSet::from([0, 10, 15, 20]);
+            assert_eq!(
+                SurfaceId::expand_within(&case, &defined),
+                BTreeSet::from([10, 15])
+            );
+
+            let case = vec![SurfaceId::All, SurfaceId::Not(Box::new(SurfaceId::Unit(0)))];
+            assert!(SurfaceId::matches(&case, 1000));
+            assert!(!SurfaceId::matches(&case, 0));
+            assert_eq!(
+                SurfaceId::expand_within(&case, &defined),
+                BTreeSet::from([10, 15, 20])
+            );
+        }
+
+        #[test]
+        fn success_when_only_not() {
+            let case = vec![SurfaceId::Not(Box::new(SurfaceId::Unit(15)))];
+            assert!(!SurfaceId::matches(&case, 0));
+            assert_eq!(SurfaceId::expand(&case).next(), None);
+        }
+    }
 }
@@ -0,0 +1,95 @@
+//! Prints semantic differences between two shells.
+//!
+//! ```text
+//! surfaces-diff [--format text|json] <OLD> <NEW>
+//! ```
+//!
+//! `OLD` and `NEW` are surfaces files or shell directories containing `surfaces*.txt`;
+//! all files in a directory are read as one shell.
+//! Comments, brace order and layout are ignored.
+//!
+//! Exit codes: `0` when there are no differences, `1` when there are,
+//! `2` when arguments or files are invalid.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use shell_parser_surfaces_rs::{diff::diff, files::read_shell};
+
+const USAGE: &str = "Usage: surfaces-diff [--format text|json] <OLD> <NEW>";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+struct Args {
+    format: Format,
+    old: PathBuf,
+    new: PathBuf,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut format = Format::Text;
+    let mut positionals = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some(v) => return Err(format!("unknown format: {}", v)),
+                    None => return Err("--format needs a value".to_string()),
+                }
+            }
+            "-h" | "--help" => return Err(String::new()),
+            v if v.starts_with('-') => return Err(format!("unknown option: {}", v)),
+            v => positionals.push(PathBuf::from(v)),
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    match (positionals.next(), positionals.next(), positionals.next()) {
+        (Some(old), Some(new), None) => Ok(Args { format, old, new }),
+        (_, _, Some(_)) => Err("too many arguments".to_string()),
+        _ => Err("two paths are needed".to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let (old, new) = match (read_shell(&args.old), read_shell(&args.new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let changes = diff(&old, &new);
+    match args.format {
+        Format::Text => {
+            for change in changes.iter() {
+                println!("{}", change);
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(&changes).unwrap()),
+    }
+
+    if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
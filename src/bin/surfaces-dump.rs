@@ -0,0 +1,233 @@
+//! Prints the resolved view of surfaces after appends and aliases.
+//!
+//! ```text
+//! surfaces-dump [--format table|json] [--character <CHARACTER>] <PATH> [SURFACE]
+//! ```
+//!
+//! `PATH` is a surfaces file or a shell directory containing `surfaces*.txt`;
+//! all files in a directory are read as one shell.
+//! `SURFACE` is a surface id or an alias name of `CHARACTER` (`sakura` by default).
+//! All surfaces are printed when it is omitted.
+//!
+//! Exit codes: `0` on success, `1` when the surface is not found or a file cannot be parsed,
+//! `2` when arguments or files are invalid.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use serde::Serializer;
+
+use shell_parser_surfaces_rs::{
+    files::read_shell,
+    resolve::{alias_surfaces, resolve_surface, resolve_surfaces, ResolvedSurface},
+    AliasTarget, SurfaceTargetCharacterId,
+};
+
+const USAGE: &str =
+    "Usage: surfaces-dump [--format table|json] [--character <CHARACTER>] <PATH> [SURFACE]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+}
+
+struct Args {
+    format: Format,
+    character: SurfaceTargetCharacterId,
+    path: PathBuf,
+    surface: Option<String>,
+}
+
+fn parse_character(value: &str) -> Option<SurfaceTargetCharacterId> {
+    match value {
+        "sakura" => Some(SurfaceTargetCharacterId::Sakura),
+        "kero" => Some(SurfaceTargetCharacterId::Kero),
+        v => v
+            .strip_prefix("char")
+            .and_then(|n| n.parse().ok())
+            .map(SurfaceTargetCharacterId::Char),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut format = Format::Table;
+    let mut character = SurfaceTargetCharacterId::Sakura;
+    let mut positionals = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("table") => Format::Table,
+                    Some("json") => Format::Json,
+                    Some(v) => return Err(format!("unknown format: {}", v)),
+                    None => return Err("--format needs a value".to_string()),
+                }
+            }
+            "--character" => {
+                character = match args.next().as_deref().map(|v| (v, parse_character(v))) {
+                    Some((_, Some(v))) => v,
+                    Some((v, None)) => return Err(format!("unknown character: {}", v)),
+                    None => return Err("--character needs a value".to_string()),
+                }
+            }
+            "-h" | "--help" => return Err(String::new()),
+            v if v.starts_with('-') => return Err(format!("unknown option: {}", v)),
+            v => positionals.push(v.to_string()),
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    let path = match positionals.next() {
+        Some(v) => PathBuf::from(v),
+        None => return Err("no path is given".to_string()),
+    };
+    let surface = positionals.next();
+    if positionals.next().is_some() {
+        return Err("too many arguments".to_string());
+    }
+
+    Ok(Args {
+        format,
+        character,
+        path,
+        surface,
+    })
+}
+
+fn print_table(surfaces: impl Iterator<Item = ResolvedSurface>) {
+    for surface in surfaces {
+        println!("surface{}", surface.id());
+        if !surface.aliases().is_empty() {
+            let aliases: Vec<String> = surface
+                .aliases()
+                .iter()
+                .map(|v| format!("{}:{}", v.character(), v.target()))
+                .collect();
+            println!("  aliases: {}", aliases.join(" "));
+        }
+        if !surface.elements().is_empty() {
+            println!("  elements:");
+            for v in surface.elements() {
+                println!(
+                    "    {:>4}  {:<16}  {:<24}  {},{}",
+                    v.id(),
+                    v.method().to_string(),
+                    v.filename(),
+                    v.x(),
+                    v.y()
+                );
+            }
+        }
+        if !surface.animations().is_empty() {
+            println!("  animations:");
+            for a in surface.animations() {
+                let intervals: Vec<String> = a.intervals().iter().map(|v| v.to_string()).collect();
+                let options: Vec<String> = a.options().iter().map(|v| v.to_string()).collect();
+                println!(
+                    "    {:>4}  interval: {}{}",
+                    a.id(),
+                    if intervals.is_empty() {
+                        "-".to_string()
+                    } else {
+                        intervals.join("+")
+                    },
+                    if options.is_empty() {
+                        String::new()
+                    } else {
+                        format!("  option: {}", options.join("+"))
+                    }
+                );
+                for p in a.patterns() {
+                    println!("          {}", p);
+                }
+                for c in a.collisions() {
+                    println!("          {}", c);
+                }
+                for c in a.collisions_ex() {
+                    println!("          {}", c);
+                }
+            }
+        }
+        if !surface.collisions().is_empty() || !surface.collisions_ex().is_empty() {
+            println!("  collisions:");
+            for c in surface.collisions() {
+                println!(
+                    "    {:>4}  {:<16}  rect {},{},{},{}",
+                    c.id(),
+                    c.target_id(),
+                    c.start_x(),
+                    c.start_y(),
+                    c.end_x(),
+                    c.end_y()
+                );
+            }
+            for c in surface.collisions_ex() {
+                println!("    {:>4}  {:<16}  {}", c.id(), c.target_id(), c.kind());
+            }
+        }
+        if !surface.offsets().is_empty() {
+            println!("  offsets:");
+            for (key, value) in surface.offsets() {
+                println!("    {:<24}  {}", key.to_string(), value);
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let shell_surfaces = match read_shell(&args.path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(1);
+        }
+    };
+
+    // Surfaces are printed one by one, since a range such as `surface0-4294967295` is too large
+    // to collect.
+    let surfaces: Box<dyn Iterator<Item = ResolvedSurface>> = match args.surface.as_deref() {
+        None => Box::new(resolve_surfaces(&shell_surfaces)),
+        Some(name) => {
+            // An alias may be named by an id, which means the surface itself only without one.
+            let target = AliasTarget::from(name);
+            let ids =
+                alias_surfaces(&shell_surfaces, &args.character, &target).unwrap_or_else(|| {
+                    match target {
+                        AliasTarget::Id(id) => vec![id],
+                        AliasTarget::Name(_) => Vec::new(),
+                    }
+                });
+            let surfaces: Vec<_> = ids
+                .into_iter()
+                .filter_map(|id| resolve_surface(&shell_surfaces, id))
+                .collect();
+            if surfaces.is_empty() {
+                eprintln!("error: surface `{}` is not found", name);
+                return ExitCode::from(1);
+            }
+            Box::new(surfaces.into_iter())
+        }
+    };
+
+    match args.format {
+        Format::Table => print_table(surfaces),
+        Format::Json => {
+            let mut serializer = serde_json::Serializer::pretty(std::io::stdout().lock());
+            serializer.collect_seq(surfaces).unwrap();
+            println!();
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,144 @@
+//! Rewrites `surfaces*.txt` of a shell into a canonical layout.
+//!
+//! ```text
+//! surfaces-fmt [--check] [--indent <N>] [--lf] [--sort] [--keep-braces] <PATH>...
+//! ```
+//!
+//! `PATH` is a surfaces file or a shell directory containing `surfaces*.txt`.
+//! Files are written back in the encoding they were read in, keeping a UTF-8 BOM.
+//!
+//! - `--check`: does not write files, and lists files needing reformatting.
+//! - `--indent <N>`: indents lines inside braces with `N` spaces instead of a tab.
+//! - `--lf`: writes `\n` instead of `\r\n`.
+//! - `--sort`: sorts braces by kind and surface id.
+//! - `--keep-braces`: keeps `surface0 {` and one-line braces instead of putting `{` and `}`
+//!   on their own lines.
+//!
+//! Exit codes: `0` on success, `1` when `--check` finds files needing reformatting
+//! or a file cannot be parsed, `2` when arguments or files are invalid.
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use shell_parser_surfaces_rs::{
+    files::{encode_detected, surfaces_paths},
+    format::{format_surfaces, FormatOptions, Newline},
+    parse_bytes,
+};
+
+const USAGE: &str =
+    "Usage: surfaces-fmt [--check] [--indent <N>] [--lf] [--sort] [--keep-braces] <PATH>...";
+
+struct Args {
+    check: bool,
+    options: FormatOptions,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut check = false;
+    let mut options = FormatOptions::default();
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--indent" => {
+                let width: usize = match args.next().map(|v| v.parse()) {
+                    Some(Ok(v)) => v,
+                    Some(Err(_)) => return Err("--indent needs a number".to_string()),
+                    None => return Err("--indent needs a value".to_string()),
+                };
+                options = options.indent(&" ".repeat(width));
+            }
+            "--lf" => options = options.newline(Newline::Lf),
+            "--sort" => options = options.sort_braces(true),
+            "--keep-braces" => options = options.keep_brace_layout(true),
+            "-h" | "--help" => return Err(String::new()),
+            v if v.starts_with('-') => return Err(format!("unknown option: {}", v)),
+            v => paths.push(PathBuf::from(v)),
+        }
+    }
+    if paths.is_empty() {
+        return Err("no path is given".to_string());
+    }
+
+    Ok(Args {
+        check,
+        options,
+        paths,
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut files = Vec::new();
+    for path in args.paths.iter() {
+        match surfaces_paths(path) {
+            Ok(v) if v.is_empty() => {
+                eprintln!("error: {}: no surfaces*.txt is found", path.display());
+                return ExitCode::from(2);
+            }
+            Ok(v) => files.extend(v),
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let mut failed = false;
+    for file in files {
+        let buffer = match fs::read(&file) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        let (shell_surfaces, encoding) = match parse_bytes(&buffer) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let formatted = format_surfaces(&shell_surfaces, &args.options);
+        let formatted = match encode_detected(&formatted, &encoding) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == buffer {
+            continue;
+        }
+        if args.check {
+            println!("{}", file.display());
+            failed = true;
+        } else if let Err(e) = fs::write(&file, formatted) {
+            eprintln!("error: {}: {}", file.display(), e);
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
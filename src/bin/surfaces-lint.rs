@@ -0,0 +1,213 @@
+//! Checks `surfaces*.txt` of a shell.
+//!
+//! ```text
+//! surfaces-lint [--format human|json|sarif] [--deny-warnings] <PATH>...
+//! ```
+//!
+//! `PATH` is a surfaces file or a shell directory containing `surfaces*.txt`.
+//!
+//! Exit codes: `0` when no error is found, `1` when errors are found
+//! (or warnings with `--deny-warnings`), `2` when arguments or files are invalid.
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde_json::json;
+use shell_parser_surfaces_rs::{
+    files::{read_surfaces_file, surfaces_paths},
+    lint::{lint_decoded, Diagnostic, Severity},
+};
+
+const USAGE: &str = "Usage: surfaces-lint [--format human|json|sarif] [--deny-warnings] <PATH>...";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Human,
+    Json,
+    Sarif,
+}
+
+struct Args {
+    format: Format,
+    deny_warnings: bool,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut format = Format::Human;
+    let mut deny_warnings = false;
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("human") => Format::Human,
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    Some(v) => return Err(format!("unknown format: {}", v)),
+                    None => return Err("--format needs a value".to_string()),
+                }
+            }
+            "--deny-warnings" => deny_warnings = true,
+            "-h" | "--help" => return Err(String::new()),
+            v if v.starts_with('-') => return Err(format!("unknown option: {}", v)),
+            v => paths.push(PathBuf::from(v)),
+        }
+    }
+    if paths.is_empty() {
+        return Err("no path is given".to_string());
+    }
+
+    Ok(Args {
+        format,
+        deny_warnings,
+        paths,
+    })
+}
+
+fn lint_file(path: &Path) -> Vec<Diagnostic> {
+    match read_surfaces_file(path) {
+        Ok((content, encoding)) => lint_decoded(&content, &encoding),
+        Err(e) => vec![Diagnostic::new(
+            Severity::Error,
+            "decode-error".to_string(),
+            1,
+            format!("failed to decode: {}", e),
+        )],
+    }
+}
+
+fn print_human(results: &[(PathBuf, Vec<Diagnostic>)]) {
+    let mut errors = 0;
+    let mut warnings = 0;
+    for (path, diagnostics) in results {
+        for d in diagnostics {
+            let severity = match d.severity() {
+                Severity::Error => {
+                    errors += 1;
+                    "error"
+                }
+                Severity::Warning => {
+                    warnings += 1;
+                    "warning"
+                }
+            };
+            println!(
+                "{}:{}: {}[{}]: {}",
+                path.display(),
+                d.line(),
+                severity,
+                d.code(),
+                d.message()
+            );
+        }
+    }
+    println!(
+        "{} file(s) checked: {} error(s), {} warning(s)",
+        results.len(),
+        errors,
+        warnings
+    );
+}
+
+fn print_json(results: &[(PathBuf, Vec<Diagnostic>)]) {
+    let files: Vec<_> = results
+        .iter()
+        .map(|(path, diagnostics)| {
+            json!({
+                "path": path.display().to_string(),
+                "diagnostics": diagnostics,
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&files).unwrap());
+}
+
+fn print_sarif(results: &[(PathBuf, Vec<Diagnostic>)]) {
+    let sarif_results: Vec<_> = results
+        .iter()
+        .flat_map(|(path, diagnostics)| {
+            diagnostics.iter().map(move |d| {
+                json!({
+                    "ruleId": d.code(),
+                    "level": match d.severity() {
+                        Severity::Error => "error",
+                        Severity::Warning => "warning",
+                    },
+                    "message": { "text": d.message() },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": path.display().to_string() },
+                            "region": { "startLine": d.line() },
+                        },
+                    }],
+                })
+            })
+        })
+        .collect();
+    let sarif = json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "surfaces-lint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "results": sarif_results,
+        }],
+    });
+    println!("{}", serde_json::to_string_pretty(&sarif).unwrap());
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut results = Vec::new();
+    for path in args.paths.iter() {
+        let files = match surfaces_paths(path) {
+            Ok(v) if v.is_empty() => {
+                eprintln!("error: {}: no surfaces*.txt is found", path.display());
+                return ExitCode::from(2);
+            }
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                return ExitCode::from(2);
+            }
+        };
+        for file in files {
+            let diagnostics = lint_file(&file);
+            results.push((file, diagnostics));
+        }
+    }
+
+    match args.format {
+        Format::Human => print_human(&results),
+        Format::Json => print_json(&results),
+        Format::Sarif => print_sarif(&results),
+    }
+
+    let failed = results.iter().flat_map(|(_, v)| v).any(|d| {
+        d.severity() == &Severity::Error
+            || (args.deny_warnings && d.severity() == &Severity::Warning)
+    });
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,285 @@
+//! Language server for `surfaces*.txt` over stdio.
+//!
+//! ```text
+//! surfaces-lsp
+//! ```
+//!
+//! Provides diagnostics of [`lint`](shell_parser_surfaces_rs::lint), and hover, go-to-definition,
+//! completion and document symbols of [`ide`](shell_parser_surfaces_rs::ide).
+//! Documents are synchronized in full.
+//!
+//! Exit codes: `0` after `shutdown` and `exit`, `1` on protocol errors.
+
+use std::{collections::HashMap, error::Error, process::ExitCode};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{
+        Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as RequestTrait,
+    },
+    CompletionItemKind, CompletionOptions, CompletionParams, DiagnosticSeverity,
+    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
+    HoverContents, HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind,
+    NumberOrString, OneOf, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use shell_parser_surfaces_rs::{
+    ide::{self, KeywordKind, SymbolKind},
+    lint::{lint_str, Severity},
+};
+
+type Documents = HashMap<Url, String>;
+
+fn to_position(position: &ide::Position) -> lsp_types::Position {
+    lsp_types::Position::new(*position.line() as u32, *position.character() as u32)
+}
+
+fn from_position(position: &lsp_types::Position) -> ide::Position {
+    ide::Position::new(position.line as usize, position.character as usize)
+}
+
+fn to_range(range: &ide::Range) -> lsp_types::Range {
+    lsp_types::Range::new(to_position(range.start()), to_position(range.end()))
+}
+
+fn diagnostics(text: &str) -> Vec<lsp_types::Diagnostic> {
+    let lines: Vec<&str> = ide::lines(text).collect();
+    lint_str(text)
+        .into_iter()
+        .map(|v| {
+            let line = v.line().saturating_sub(1);
+            let end = lines
+                .get(line)
+                .map(|l| l.encode_utf16().count())
+                .unwrap_or(0);
+            lsp_types::Diagnostic {
+                range: lsp_types::Range::new(
+                    lsp_types::Position::new(line as u32, 0),
+                    lsp_types::Position::new(line as u32, end as u32),
+                ),
+                severity: Some(match v.severity() {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                }),
+                code: Some(NumberOrString::String(v.code().clone())),
+                source: Some("surfaces".to_string()),
+                message: v.message().clone(),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn publish_diagnostics(
+    connection: &Connection,
+    uri: Url,
+    diagnostics: Vec<lsp_types::Diagnostic>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+    connection
+        .sender
+        .send(Message::Notification(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            params,
+        )))?;
+    Ok(())
+}
+
+fn handle_notification(
+    connection: &Connection,
+    documents: &mut Documents,
+    notification: Notification,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: lsp_types::DidOpenTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            publish_diagnostics(
+                connection,
+                uri.clone(),
+                diagnostics(&params.text_document.text),
+            )?;
+            documents.insert(uri, params.text_document.text);
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: lsp_types::DidChangeTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            if let Some(change) = params.content_changes.into_iter().last() {
+                let uri = params.text_document.uri;
+                publish_diagnostics(connection, uri.clone(), diagnostics(&change.text))?;
+                documents.insert(uri, change.text);
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: lsp_types::DidCloseTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            documents.remove(&params.text_document.uri);
+            publish_diagnostics(connection, params.text_document.uri, Vec::new())?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn respond<P, R, F>(id: RequestId, params: serde_json::Value, f: F) -> Response
+where
+    P: DeserializeOwned,
+    R: Serialize,
+    F: FnOnce(P) -> R,
+{
+    match serde_json::from_value(params) {
+        Ok(params) => Response::new_ok(id, f(params)),
+        Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+    }
+}
+
+fn hover(documents: &Documents, params: HoverParams) -> Option<lsp_types::Hover> {
+    let position = params.text_document_position_params;
+    let text = documents.get(&position.text_document.uri)?;
+    ide::hover(text, from_position(&position.position)).map(|v| lsp_types::Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: v.contents().clone(),
+        }),
+        range: Some(to_range(v.range())),
+    })
+}
+
+fn definition(documents: &Documents, params: GotoDefinitionParams) -> GotoDefinitionResponse {
+    let position = params.text_document_position_params;
+    let uri = position.text_document.uri;
+    let ranges = match documents.get(&uri) {
+        Some(text) => ide::definition(text, from_position(&position.position)),
+        None => Vec::new(),
+    };
+    GotoDefinitionResponse::Array(
+        ranges
+            .iter()
+            .map(|v| Location::new(uri.clone(), to_range(v)))
+            .collect(),
+    )
+}
+
+fn completion(documents: &Documents, params: CompletionParams) -> Vec<lsp_types::CompletionItem> {
+    let position = params.text_document_position;
+    let items = match documents.get(&position.text_document.uri) {
+        Some(text) => ide::completion(text, from_position(&position.position)),
+        None => Vec::new(),
+    };
+    items
+        .into_iter()
+        .map(|v| lsp_types::CompletionItem {
+            label: v.label().clone(),
+            kind: Some(match v.kind() {
+                KeywordKind::Line => CompletionItemKind::PROPERTY,
+                KeywordKind::DrawMethod | KeywordKind::AnimationMethod => {
+                    CompletionItemKind::METHOD
+                }
+                _ => CompletionItemKind::ENUM_MEMBER,
+            }),
+            detail: Some(v.detail().clone()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[allow(deprecated)]
+fn document_symbols(documents: &Documents, params: DocumentSymbolParams) -> DocumentSymbolResponse {
+    let symbols = match documents.get(&params.text_document.uri) {
+        Some(text) => ide::document_symbols(text),
+        None => Vec::new(),
+    };
+    DocumentSymbolResponse::Nested(
+        symbols
+            .into_iter()
+            .map(|v| lsp_types::DocumentSymbol {
+                name: v.name().clone(),
+                detail: None,
+                kind: match v.kind() {
+                    SymbolKind::Descript => lsp_types::SymbolKind::NAMESPACE,
+                    SymbolKind::Surface | SymbolKind::SurfaceAppend => lsp_types::SymbolKind::CLASS,
+                    SymbolKind::SurfaceAlias => lsp_types::SymbolKind::ENUM,
+                    SymbolKind::Cursor | SymbolKind::Tooltip => lsp_types::SymbolKind::OBJECT,
+                },
+                tags: None,
+                deprecated: None,
+                range: to_range(v.range()),
+                selection_range: to_range(v.selection_range()),
+                children: None,
+            })
+            .collect(),
+    )
+}
+
+fn handle_request(documents: &Documents, request: Request) -> Response {
+    let Request { id, method, params } = request;
+    match method.as_str() {
+        HoverRequest::METHOD => respond(id, params, |p| hover(documents, p)),
+        GotoDefinition::METHOD => respond(id, params, |p| definition(documents, p)),
+        Completion::METHOD => respond(id, params, |p| completion(documents, p)),
+        DocumentSymbolRequest::METHOD => respond(id, params, |p| document_symbols(documents, p)),
+        _ => Response::new_err(
+            id,
+            ErrorCode::MethodNotFound as i32,
+            format!("unknown method: {}", method),
+        ),
+    }
+}
+
+fn main_loop(connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut documents = Documents::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = handle_request(&documents, request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                handle_notification(connection, &mut documents, notification)?
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![",".to_string(), "+".to_string()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    main_loop(&connection)?;
+
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(1)
+        }
+    }
+}
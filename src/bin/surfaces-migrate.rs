@@ -0,0 +1,131 @@
+//! Rewrites legacy animation lines of `surfaces*.txt` into `animationN.` syntax.
+//!
+//! ```text
+//! surfaces-migrate [--check] <PATH>...
+//! ```
+//!
+//! `PATH` is a surfaces file or a shell directory containing `surfaces*.txt`.
+//! Files are written back in the encoding they were read in, keeping a UTF-8 BOM.
+//! Lines which could not be converted are reported and kept as they are.
+//!
+//! - `--check`: does not write files, and lists files having legacy lines.
+//!
+//! Exit codes: `0` on success, `1` when some lines could not be converted,
+//! `--check` finds legacy lines or a file cannot be parsed or written, `2` when arguments or files are invalid.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use shell_parser_surfaces_rs::{
+    files::{read_surfaces_file, surfaces_paths, write_surfaces_file},
+    migrate::migrate_str,
+};
+
+const USAGE: &str = "Usage: surfaces-migrate [--check] <PATH>...";
+
+struct Args {
+    check: bool,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut check = false;
+    let mut paths = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => return Err(String::new()),
+            v if v.starts_with('-') => return Err(format!("unknown option: {}", v)),
+            v => paths.push(PathBuf::from(v)),
+        }
+    }
+    if paths.is_empty() {
+        return Err("no path is given".to_string());
+    }
+
+    Ok(Args { check, paths })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("error: {}", e);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut files = Vec::new();
+    for path in args.paths.iter() {
+        match surfaces_paths(path) {
+            Ok(v) if v.is_empty() => {
+                eprintln!("error: {}: no surfaces*.txt is found", path.display());
+                return ExitCode::from(2);
+            }
+            Ok(v) => files.extend(v),
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let mut failed = false;
+    for file in files {
+        let (content, encoding) = match read_surfaces_file(&file) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let migration = match migrate_str(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("error: {}:{}: failed to parse", file.display(), e.line());
+                failed = true;
+                continue;
+            }
+        };
+        for line in migration.unconverted() {
+            eprintln!(
+                "{}:{}: could not convert `{}`",
+                file.display(),
+                line.line(),
+                line.text()
+            );
+            failed = true;
+        }
+        if migration.converted().is_empty() {
+            continue;
+        }
+
+        if args.check {
+            println!("{}", file.display());
+            failed = true;
+            continue;
+        }
+        match write_surfaces_file(&file, migration.output(), &encoding) {
+            Ok(_) => println!(
+                "{}: converted {} line(s)",
+                file.display(),
+                migration.converted().len()
+            ),
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
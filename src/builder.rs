@@ -0,0 +1,478 @@
+//! Builders for constructing [`ShellSurfaces`] programmatically.
+//!
+//! [`ShellSurfaces`]: crate::ast::ShellSurfaces
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AliasTarget, AnimationIdType, AnimationInterval, AnimationOptionKind,
+    AnimationPatternDrawMethod, AnimationPatternIdType, AnimationPatternProperty, Brace,
+    BraceContainer, CollisionExKind, CollisionIdType, CommentLine, CoordinateType,
+    CursorMouseIdType, Descript, DescriptInner, DrawMethod, DrawMethodOnAnimation, ElementIdType,
+    GestureKind, LineContainer, ShellSurfaces, ShellSurfacesCursor, ShellSurfacesCursorGesture,
+    SortOrder, Surface, SurfaceAlias, SurfaceAliasInner, SurfaceAnimationCollision,
+    SurfaceAnimationCollisionEx, SurfaceAnimationInterval, SurfaceAnimationOption,
+    SurfaceAnimationPattern, SurfaceAppend, SurfaceCollision, SurfaceCollisionEx, SurfaceElement,
+    SurfaceId, SurfaceIdPointerType, SurfaceIdType, SurfaceInner, SurfaceSizeType,
+    SurfaceTargetCharacterId, Tooltip, TooltipInner,
+};
+
+/// Builder of [`ShellSurfaces`].
+///
+/// Comments added by [`comment`] become header comments of the next brace,
+/// or footer comments when no brace follows.
+///
+/// [`ShellSurfaces`]: crate::ast::ShellSurfaces
+/// [`comment`]: ShellSurfacesBuilder::comment
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::{
+///     builder::ShellSurfacesBuilder, parse, CollisionExKind, DrawMethod, SurfaceTargetCharacterId,
+/// };
+///
+/// let shell_surfaces = ShellSurfacesBuilder::new(Charset::UTF8)
+///     .descript(|d| d.version(1))
+///     .surface(0, |s| {
+///         s.element(0, DrawMethod::Base, "body.png", 0, 0)
+///             .collision(0, 40, 56, 95, 90, "Head")
+///             .collision_ex(1, "Bust", CollisionExKind::Circle(100, 200, 20))
+///     })
+///     .tooltip(SurfaceTargetCharacterId::Sakura, |t| t.tooltip("Head", "頭"))
+///     .build();
+///
+/// assert_eq!(shell_surfaces.braces().len(), 3);
+/// assert_eq!(parse(&shell_surfaces.to_string()).unwrap(), shell_surfaces);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShellSurfacesBuilder {
+    header_comments: Vec<CommentLine>,
+    charset: Charset,
+    braces: Vec<BraceContainer>,
+    pending_comments: Vec<CommentLine>,
+}
+
+/// Builder of lines in a brace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinesBuilder<T> {
+    lines: Vec<LineContainer<T>>,
+}
+
+pub type DescriptBuilder = LinesBuilder<DescriptInner>;
+pub type SurfaceBuilder = LinesBuilder<SurfaceInner>;
+pub type SurfaceAliasBuilder = LinesBuilder<SurfaceAliasInner>;
+pub type CursorBuilder = LinesBuilder<ShellSurfacesCursorGesture>;
+pub type TooltipBuilder = LinesBuilder<TooltipInner>;
+
+impl ShellSurfacesBuilder {
+    pub fn new(charset: Charset) -> ShellSurfacesBuilder {
+        ShellSurfacesBuilder {
+            header_comments: Vec::new(),
+            charset,
+            braces: Vec::new(),
+            pending_comments: Vec::new(),
+        }
+    }
+
+    /// Adds a comment line before `charset`.
+    pub fn header_comment(mut self, body: &str) -> Self {
+        self.header_comments
+            .push(CommentLine::new(body.to_string()));
+        self
+    }
+
+    /// Adds a comment line before the next brace.
+    pub fn comment(mut self, body: &str) -> Self {
+        self.pending_comments
+            .push(CommentLine::new(body.to_string()));
+        self
+    }
+
+    pub fn brace(mut self, body: Brace) -> Self {
+        let header_comments = std::mem::take(&mut self.pending_comments);
+        self.braces.push(BraceContainer::new(header_comments, body));
+        self
+    }
+
+    pub fn descript<F>(self, f: F) -> Self
+    where
+        F: FnOnce(DescriptBuilder) -> DescriptBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::Descript(Descript::new(lines)))
+    }
+
+    pub fn surface<F>(self, id: SurfaceIdType, f: F) -> Self
+    where
+        F: FnOnce(SurfaceBuilder) -> SurfaceBuilder,
+    {
+        self.surfaces(vec![SurfaceId::Unit(id)], f)
+    }
+
+    pub fn surfaces<F>(self, ids: Vec<SurfaceId>, f: F) -> Self
+    where
+        F: FnOnce(SurfaceBuilder) -> SurfaceBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::Surface(Surface::new(ids, lines)))
+    }
+
+    pub fn surface_append<F>(self, ids: Vec<SurfaceId>, f: F) -> Self
+    where
+        F: FnOnce(SurfaceBuilder) -> SurfaceBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::SurfaceAppend(SurfaceAppend::new(ids, lines)))
+    }
+
+    pub fn surface_alias<F>(self, id: SurfaceTargetCharacterId, f: F) -> Self
+    where
+        F: FnOnce(SurfaceAliasBuilder) -> SurfaceAliasBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::SurfaceAlias(SurfaceAlias::new(id, lines)))
+    }
+
+    pub fn cursor<F>(self, id: SurfaceTargetCharacterId, f: F) -> Self
+    where
+        F: FnOnce(CursorBuilder) -> CursorBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::Cursor(ShellSurfacesCursor::new(id, lines)))
+    }
+
+    pub fn tooltip<F>(self, id: SurfaceTargetCharacterId, f: F) -> Self
+    where
+        F: FnOnce(TooltipBuilder) -> TooltipBuilder,
+    {
+        let lines = f(LinesBuilder::new()).build();
+        self.brace(Brace::Tooltip(Tooltip::new(id, lines)))
+    }
+
+    pub fn build(self) -> ShellSurfaces {
+        ShellSurfaces::new(
+            self.header_comments,
+            self.charset,
+            self.braces,
+            self.pending_comments,
+        )
+    }
+}
+
+impl<T> LinesBuilder<T> {
+    pub fn new() -> LinesBuilder<T> {
+        LinesBuilder { lines: Vec::new() }
+    }
+
+    pub fn comment(mut self, body: &str) -> Self {
+        self.lines
+            .push(LineContainer::Comment(CommentLine::new(body.to_string())));
+        self
+    }
+
+    pub fn line(mut self, line: T) -> Self {
+        self.lines.push(LineContainer::body_line(line));
+        self
+    }
+
+    pub fn build(self) -> Vec<LineContainer<T>> {
+        self.lines
+    }
+}
+
+impl<T> Default for LinesBuilder<T> {
+    fn default() -> Self {
+        LinesBuilder::new()
+    }
+}
+
+impl LinesBuilder<DescriptInner> {
+    pub fn version(self, version: u16) -> Self {
+        self.line(DescriptInner::Version(version))
+    }
+
+    pub fn max_width(self, width: SurfaceSizeType) -> Self {
+        self.line(DescriptInner::MaxWidth(width))
+    }
+
+    pub fn collision_sort(self, order: SortOrder) -> Self {
+        self.line(DescriptInner::CollistionSort(order))
+    }
+
+    pub fn animation_sort(self, order: SortOrder) -> Self {
+        self.line(DescriptInner::AnimationSort(order))
+    }
+}
+
+impl LinesBuilder<SurfaceInner> {
+    pub fn element(
+        self,
+        id: ElementIdType,
+        method: DrawMethod,
+        filename: &str,
+        x: CoordinateType,
+        y: CoordinateType,
+    ) -> Self {
+        self.line(SurfaceInner::Element(SurfaceElement::new(
+            id,
+            method,
+            filename.to_string(),
+            x,
+            y,
+        )))
+    }
+
+    pub fn interval(self, id: AnimationIdType, intervals: Vec<AnimationInterval>) -> Self {
+        self.line(SurfaceInner::AnimationInterval(
+            SurfaceAnimationInterval::new(id, intervals),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn pattern(
+        self,
+        id: AnimationIdType,
+        pattern_id: AnimationPatternIdType,
+        method: DrawMethod,
+        surface_id: SurfaceIdPointerType,
+        weight: u32,
+        x: CoordinateType,
+        y: CoordinateType,
+    ) -> Self {
+        let property = AnimationPatternProperty::new(surface_id, weight, x, y);
+        self.line(SurfaceInner::AnimationPattern(
+            SurfaceAnimationPattern::new(
+                id,
+                pattern_id,
+                AnimationPatternDrawMethod::Normal(method, property),
+            ),
+        ))
+    }
+
+    pub fn pattern_animation(
+        self,
+        id: AnimationIdType,
+        pattern_id: AnimationPatternIdType,
+        method: DrawMethodOnAnimation,
+    ) -> Self {
+        self.line(SurfaceInner::AnimationPattern(
+            SurfaceAnimationPattern::new(
+                id,
+                pattern_id,
+                AnimationPatternDrawMethod::Animation(method),
+            ),
+        ))
+    }
+
+    pub fn option(self, id: AnimationIdType, options: Vec<AnimationOptionKind>) -> Self {
+        self.line(SurfaceInner::AnimationOption(SurfaceAnimationOption::new(
+            id, options,
+        )))
+    }
+
+    pub fn collision(
+        self,
+        id: CollisionIdType,
+        start_x: CoordinateType,
+        start_y: CoordinateType,
+        end_x: CoordinateType,
+        end_y: CoordinateType,
+        target_id: &str,
+    ) -> Self {
+        self.line(SurfaceInner::Collision(SurfaceCollision::new(
+            id,
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            target_id.to_string(),
+        )))
+    }
+
+    pub fn collision_ex(self, id: CollisionIdType, target_id: &str, kind: CollisionExKind) -> Self {
+        self.line(SurfaceInner::CollisionEx(SurfaceCollisionEx::new(
+            id,
+            target_id.to_string(),
+            kind,
+        )))
+    }
+
+    pub fn animation_collision(self, id: AnimationIdType, collision: SurfaceCollision) -> Self {
+        self.line(SurfaceInner::AnimationCollision(
+            SurfaceAnimationCollision::new(id, collision),
+        ))
+    }
+
+    pub fn animation_collision_ex(
+        self,
+        id: AnimationIdType,
+        collision: SurfaceCollisionEx,
+    ) -> Self {
+        self.line(SurfaceInner::AnimationCollisionEx(
+            SurfaceAnimationCollisionEx::new(id, collision),
+        ))
+    }
+
+    pub fn sakura_balloon_offset(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::SakuraBalloonOffsetX(x))
+            .line(SurfaceInner::SakuraBalloonOffsetY(y))
+    }
+
+    pub fn kero_balloon_offset(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::KeroBalloonOffsetX(x))
+            .line(SurfaceInner::KeroBalloonOffsetY(y))
+    }
+
+    pub fn character_balloon_offset(
+        self,
+        id: SurfaceTargetCharacterId,
+        x: CoordinateType,
+        y: CoordinateType,
+    ) -> Self {
+        self.line(SurfaceInner::character_balloon_offset_x(id.clone(), x))
+            .line(SurfaceInner::character_balloon_offset_y(id, y))
+    }
+
+    pub fn balloon_offset(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::BalloonOffsetX(x))
+            .line(SurfaceInner::BalloonOffsetY(y))
+    }
+
+    pub fn point_center(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::PointCenterX(x))
+            .line(SurfaceInner::PointCenterY(y))
+    }
+
+    pub fn point_kinoko_center(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::PointKinokoCenterX(x))
+            .line(SurfaceInner::PointKinokoCenterY(y))
+    }
+
+    pub fn point_basepos(self, x: CoordinateType, y: CoordinateType) -> Self {
+        self.line(SurfaceInner::PointBaseposX(x))
+            .line(SurfaceInner::PointBaseposY(y))
+    }
+}
+
+impl LinesBuilder<SurfaceAliasInner> {
+    pub fn alias(self, target: &str, surfaces: Vec<SurfaceIdType>) -> Self {
+        self.line(SurfaceAliasInner::new(AliasTarget::from(target), surfaces))
+    }
+}
+
+impl LinesBuilder<ShellSurfacesCursorGesture> {
+    pub fn gesture(
+        self,
+        kind: GestureKind,
+        id: CursorMouseIdType,
+        target_collision: &str,
+        filename: &str,
+    ) -> Self {
+        self.line(ShellSurfacesCursorGesture::new(
+            kind,
+            id,
+            target_collision.to_string(),
+            filename.to_string(),
+        ))
+    }
+}
+
+impl LinesBuilder<TooltipInner> {
+    pub fn tooltip(self, collision: &str, description: &str) -> Self {
+        self.line(TooltipInner::new(
+            collision.to_string(),
+            description.to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod shell_surfaces_builder {
+        use super::*;
+
+        #[test]
+        fn success_when_building_every_brace() {
+            let result = ShellSurfacesBuilder::new(Charset::ShiftJIS)
+                .header_comment("// generated")
+                .descript(|d| d.version(1).max_width(320))
+                .comment("")
+                .surface(0, |s| {
+                    s.element(0, DrawMethod::Overlay, "body0.png", 0, 0)
+                        .interval(0, vec![AnimationInterval::Sometimes])
+                        .pattern(0, 0, DrawMethod::Overlay, 101, 100, 168, 67)
+                        .pattern_animation(0, 1, DrawMethodOnAnimation::Insert(2))
+                        .comment("// eyes")
+                        .collision(0, 40, 56, 95, 90, "Head")
+                        .sakura_balloon_offset(80, -100)
+                })
+                .surface_append(vec![SurfaceId::Range(0, 9)], |s| {
+                    s.collision(1, 190, 92, 236, 118, "Face")
+                })
+                .surface_alias(SurfaceTargetCharacterId::Sakura, |a| {
+                    a.alias("素", vec![0]).alias("照れ", vec![1, 101])
+                })
+                .cursor(SurfaceTargetCharacterId::Sakura, |c| {
+                    c.gesture(GestureKind::MouseUp, 0, "Head", "system:hand")
+                })
+                .tooltip(SurfaceTargetCharacterId::Kero, |t| {
+                    t.tooltip("Bust", "怒ります。")
+                })
+                .comment("// end")
+                .build();
+
+            let case = r#"// generated
+charset,Shift_JIS
+descript
+{
+version,1
+maxwidth,320
+}
+
+surface0
+{
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes
+animation0.pattern0,overlay,101,100,168,67
+animation0.pattern1,insert,2
+// eyes
+collision0,40,56,95,90,Head
+sakura.balloon.offsetx,80
+sakura.balloon.offsety,-100
+}
+surface.append0-9
+{
+collision1,190,92,236,118,Face
+}
+sakura.surface.alias
+{
+素,[0]
+照れ,[1,101]
+}
+sakura.cursor
+{
+mouseup0,Head,system:hand
+}
+kero.tooltips
+{
+Bust,怒ります。
+}
+// end
+"#;
+            assert_eq!(result, parse(case).unwrap());
+            assert_eq!(result.to_string(), case.replace('\n', "\r\n"));
+        }
+
+        #[test]
+        fn success_when_empty() {
+            let result = ShellSurfacesBuilder::new(Charset::UTF8).build();
+            assert!(result.braces().is_empty());
+            assert_eq!(result.to_string(), "charset,UTF-8\r\n");
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Per-character braces (`.surface.alias`, `.cursor` and `.tooltips`).
+//!
+//! `sakura` and `kero` are the same characters as `char0` and `char1`, so
+//! `sakura.surface.alias` and `char0.surface.alias` configure one character.
+
+use std::collections::HashMap;
+
+use crate::ast::{
+    Brace, BraceContainer, CommentLine, LineContainer, ShellSurfaces, ShellSurfacesCursorGesture,
+    SurfaceAliasInner, SurfaceTargetCharacterId, TooltipInner,
+};
+
+/// Alias, cursor and tooltip lines of one character, in the order of braces.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CharacterView {
+    id: SurfaceTargetCharacterId,
+    aliases: Vec<SurfaceAliasInner>,
+    cursors: Vec<ShellSurfacesCursorGesture>,
+    tooltips: Vec<TooltipInner>,
+}
+
+impl CharacterView {
+    /// The character as `charN`.
+    pub fn id(&self) -> &SurfaceTargetCharacterId {
+        &self.id
+    }
+    pub fn aliases(&self) -> &Vec<SurfaceAliasInner> {
+        &self.aliases
+    }
+    pub fn cursors(&self) -> &Vec<ShellSurfacesCursorGesture> {
+        &self.cursors
+    }
+    pub fn tooltips(&self) -> &Vec<TooltipInner> {
+        &self.tooltips
+    }
+}
+
+/// Returns the view of `character`, or `None` if no brace configures it.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{characters::character_view, parse, SurfaceTargetCharacterId};
+///
+/// let case = "charset,UTF-8\r\nsakura.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nchar0.tooltips\r\n{\r\nHead,頭\r\n}\r\n";
+/// let shell_surfaces = parse(case).unwrap();
+///
+/// let view = character_view(&shell_surfaces, &SurfaceTargetCharacterId::Char(0)).unwrap();
+/// assert_eq!(view.id(), &SurfaceTargetCharacterId::Char(0));
+/// assert_eq!(view.aliases().len(), 1);
+/// assert_eq!(view.tooltips().len(), 1);
+/// ```
+pub fn character_view(
+    shell_surfaces: &ShellSurfaces,
+    character: &SurfaceTargetCharacterId,
+) -> Option<CharacterView> {
+    fn bodies<T: Clone>(lines: &[LineContainer<T>]) -> impl Iterator<Item = T> + '_ {
+        lines.iter().filter_map(LineContainer::body).cloned()
+    }
+
+    let mut configured = false;
+    let mut view = CharacterView {
+        id: character.normalized(),
+        aliases: Vec::new(),
+        cursors: Vec::new(),
+        tooltips: Vec::new(),
+    };
+    for brace in shell_surfaces.braces() {
+        match brace.body() {
+            Brace::SurfaceAlias(v) if v.id().index() == character.index() => {
+                view.aliases.extend(bodies(v.lines()));
+            }
+            Brace::Cursor(v) if v.id().index() == character.index() => {
+                view.cursors.extend(bodies(v.lines()));
+            }
+            Brace::Tooltip(v) if v.id().index() == character.index() => {
+                view.tooltips.extend(bodies(v.lines()));
+            }
+            _ => continue,
+        }
+        configured = true;
+    }
+
+    configured.then_some(view)
+}
+
+/// Returns the views of all characters configured in `shell_surfaces`, sorted by character.
+pub fn character_views(shell_surfaces: &ShellSurfaces) -> Vec<CharacterView> {
+    shell_surfaces
+        .characters()
+        .iter()
+        .filter_map(|v| character_view(shell_surfaces, v))
+        .collect()
+}
+
+/// Writes every character as `charN`, and merges braces of the same kind and character into the
+/// first one.
+///
+/// Lines of a merged brace are appended to the first one, and its header comments are kept as
+/// comment lines before them.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{characters::normalize_characters, parse};
+///
+/// let case = "charset,UTF-8\r\nsakura.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nchar0.surface.alias\r\n{\r\n笑顔,[1]\r\n}\r\n";
+/// let mut shell_surfaces = parse(case).unwrap();
+///
+/// normalize_characters(&mut shell_surfaces);
+/// assert_eq!(
+///     shell_surfaces.to_string(),
+///     "charset,UTF-8\r\nchar0.surface.alias\r\n{\r\n通常,[0]\r\n笑顔,[1]\r\n}\r\n"
+/// );
+/// ```
+pub fn normalize_characters(shell_surfaces: &mut ShellSurfaces) {
+    let mut firsts: HashMap<(u8, u32), usize> = HashMap::new();
+    let mut result: Vec<BraceContainer> = Vec::new();
+
+    for mut brace in std::mem::take(shell_surfaces.braces_mut()) {
+        let key = match brace.body_mut() {
+            Brace::SurfaceAlias(v) => Some((0, normalize_id(v.id_mut()))),
+            Brace::Cursor(v) => Some((1, normalize_id(v.id_mut()))),
+            Brace::Tooltip(v) => Some((2, normalize_id(v.id_mut()))),
+            _ => None,
+        };
+        match key.and_then(|v| firsts.get(&v)) {
+            Some(i) => merge(&mut result[*i], brace),
+            None => {
+                if let Some(key) = key {
+                    firsts.insert(key, result.len());
+                }
+                result.push(brace);
+            }
+        }
+    }
+
+    *shell_surfaces.braces_mut() = result;
+}
+
+fn normalize_id(id: &mut SurfaceTargetCharacterId) -> u32 {
+    *id = id.normalized();
+    id.index()
+}
+
+fn merge(into: &mut BraceContainer, from: BraceContainer) {
+    fn append<T: Clone>(
+        into: &mut Vec<LineContainer<T>>,
+        header_comments: &[CommentLine],
+        lines: &[LineContainer<T>],
+    ) {
+        into.extend(header_comments.iter().cloned().map(LineContainer::Comment));
+        into.extend(lines.iter().cloned());
+    }
+
+    let header_comments = from.header_comments();
+    match (into.body_mut(), from.body()) {
+        (Brace::SurfaceAlias(a), Brace::SurfaceAlias(b)) => {
+            append(a.lines_mut(), header_comments, b.lines())
+        }
+        (Brace::Cursor(a), Brace::Cursor(b)) => append(a.lines_mut(), header_comments, b.lines()),
+        (Brace::Tooltip(a), Brace::Tooltip(b)) => append(a.lines_mut(), header_comments, b.lines()),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::parse::parse;
+
+    const CASE: &str = r#"charset,UTF-8
+sakura.surface.alias
+{
+通常,[0]
+}
+kero.cursor
+{
+mouseup0,Head,system:hand
+}
+char0.surface.alias
+{
+笑顔,[1]
+}
+// tooltips of kero
+char1.tooltips
+{
+Head,頭
+}
+char2.surface.alias
+{
+通常,[200]
+}
+"#;
+
+    mod character_view {
+        use super::*;
+
+        #[test]
+        fn success_when_configured_character() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result =
+                character_view(&shell_surfaces, &SurfaceTargetCharacterId::Sakura).unwrap();
+            assert_eq!(result.id(), &SurfaceTargetCharacterId::Char(0));
+            assert_eq!(
+                result
+                    .aliases()
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>(),
+                vec!["通常,[0]", "笑顔,[1]"]
+            );
+            assert!(result.cursors().is_empty());
+
+            let result =
+                character_view(&shell_surfaces, &SurfaceTargetCharacterId::Char(1)).unwrap();
+            assert_eq!(result.cursors().len(), 1);
+            assert_eq!(result.tooltips().len(), 1);
+        }
+
+        #[test]
+        fn failed_when_unconfigured_character() {
+            let shell_surfaces = parse(CASE).unwrap();
+            assert!(character_view(&shell_surfaces, &SurfaceTargetCharacterId::Char(3)).is_none());
+        }
+    }
+
+    mod character_views {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result: Vec<_> = character_views(&shell_surfaces)
+                .iter()
+                .map(|v| v.id().clone())
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    SurfaceTargetCharacterId::Char(0),
+                    SurfaceTargetCharacterId::Char(1),
+                    SurfaceTargetCharacterId::Char(2),
+                ]
+            );
+            assert_eq!(
+                shell_surfaces.characters(),
+                result.into_iter().collect::<BTreeSet<_>>()
+            );
+        }
+    }
+
+    mod normalize_characters {
+        use super::*;
+
+        #[test]
+        fn success_when_duplicate_braces() {
+            let mut shell_surfaces = parse(CASE).unwrap();
+            normalize_characters(&mut shell_surfaces);
+            assert_eq!(
+                shell_surfaces.to_string(),
+                r#"charset,UTF-8
+char0.surface.alias
+{
+通常,[0]
+笑顔,[1]
+}
+char1.cursor
+{
+mouseup0,Head,system:hand
+}
+// tooltips of kero
+char1.tooltips
+{
+Head,頭
+}
+char2.surface.alias
+{
+通常,[200]
+}
+"#
+                .replace('\n', "\r\n")
+            );
+        }
+
+        #[test]
+        fn success_when_header_comments_merged() {
+            let case = "charset,UTF-8\r\nkero.tooltips\r\n{\r\nHead,頭\r\n}\r\n// face\r\nchar1.tooltips\r\n{\r\nFace,顔\r\n}\r\n";
+            let mut shell_surfaces = parse(case).unwrap();
+            normalize_characters(&mut shell_surfaces);
+            assert_eq!(
+                shell_surfaces.to_string(),
+                "charset,UTF-8\r\nchar1.tooltips\r\n{\r\nHead,頭\r\n// face\r\nFace,顔\r\n}\r\n"
+            );
+        }
+    }
+}
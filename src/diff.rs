@@ -0,0 +1,584 @@
+//! Semantic diff between two [`ShellSurfaces`].
+//!
+//! Surfaces are compared after resolving appends (see [`resolve`](crate::resolve)),
+//! and values are compared in the canonical text of `surfaces.txt`,
+//! so comments, brace order, layout and legacy syntax are ignored.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use crate::{
+    ast::{
+        AnimationIdType, Brace, CollisionIdType, CursorMouseIdType, DescriptInner, ElementIdType,
+        GestureKind, LineContainer, ShellSurfaces, SurfaceIdType, SurfaceTargetCharacterId,
+    },
+    resolve::{bounds, resolved_ranges, OffsetKey, ResolvedAnimation, ResolvedSurface},
+};
+
+/// Kind of a [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// What a [`Change`] is about.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DiffTarget {
+    /// A line of `descript`, keyed by its name (e.g. `version`).
+    Descript(String),
+    Surface(SurfaceIdType),
+    Element(SurfaceIdType, ElementIdType),
+    Collision(SurfaceIdType, CollisionIdType),
+    CollisionEx(SurfaceIdType, CollisionIdType),
+    Animation(SurfaceIdType, AnimationIdType),
+    AnimationInterval(SurfaceIdType, AnimationIdType),
+    AnimationOption(SurfaceIdType, AnimationIdType),
+    AnimationPattern(SurfaceIdType, AnimationIdType, AnimationIdType),
+    AnimationCollision(SurfaceIdType, AnimationIdType, CollisionIdType),
+    AnimationCollisionEx(SurfaceIdType, AnimationIdType, CollisionIdType),
+    /// A balloon offset or a point.
+    Offset(SurfaceIdType, OffsetKey),
+    /// An alias line. Characters are written as `charN`, so `sakura` and `char0` are the same.
+    Alias(SurfaceTargetCharacterId, String),
+    /// A cursor line, with the character as `charN`.
+    Cursor(SurfaceTargetCharacterId, GestureKind, CursorMouseIdType),
+    /// A tooltip line, with the character as `charN`.
+    Tooltip(SurfaceTargetCharacterId, String),
+}
+
+impl Display for DiffTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffTarget::Descript(key) => write!(f, "descript {}", key),
+            DiffTarget::Surface(s) => write!(f, "surface{}", s),
+            DiffTarget::Element(s, id) => write!(f, "surface{} element{}", s, id),
+            DiffTarget::Collision(s, id) => write!(f, "surface{} collision{}", s, id),
+            DiffTarget::CollisionEx(s, id) => write!(f, "surface{} collisionex{}", s, id),
+            DiffTarget::Animation(s, id) => write!(f, "surface{} animation{}", s, id),
+            DiffTarget::AnimationInterval(s, id) => {
+                write!(f, "surface{} animation{}.interval", s, id)
+            }
+            DiffTarget::AnimationOption(s, id) => write!(f, "surface{} animation{}.option", s, id),
+            DiffTarget::AnimationPattern(s, a, id) => {
+                write!(f, "surface{} animation{}.pattern{}", s, a, id)
+            }
+            DiffTarget::AnimationCollision(s, a, id) => {
+                write!(f, "surface{} animation{}.collision{}", s, a, id)
+            }
+            DiffTarget::AnimationCollisionEx(s, a, id) => {
+                write!(f, "surface{} animation{}.collisionex{}", s, a, id)
+            }
+            DiffTarget::Offset(s, key) => write!(f, "surface{} {}", s, key),
+            DiffTarget::Alias(c, target) => write!(f, "{}.surface.alias {}", c, target),
+            DiffTarget::Cursor(c, kind, id) => write!(f, "{}.cursor {}{}", c, kind, id),
+            DiffTarget::Tooltip(c, collision) => write!(f, "{}.tooltips {}", c, collision),
+        }
+    }
+}
+
+/// A semantic difference.
+///
+/// `old` and `new` are the lines in `surfaces.txt`, and `None` when the target is absent
+/// or is a whole surface or animation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Change {
+    kind: ChangeKind,
+    target: DiffTarget,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+impl Change {
+    pub fn new(
+        kind: ChangeKind,
+        target: DiffTarget,
+        old: Option<String>,
+        new: Option<String>,
+    ) -> Change {
+        Change {
+            kind,
+            target,
+            old,
+            new,
+        }
+    }
+
+    pub fn kind(&self) -> &ChangeKind {
+        &self.kind
+    }
+    pub fn target(&self) -> &DiffTarget {
+        &self.target
+    }
+    pub fn old(&self) -> &Option<String> {
+        &self.old
+    }
+    pub fn new_value(&self) -> &Option<String> {
+        &self.new
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.kind {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+        };
+        write!(f, "{} {}", mark, self.target)?;
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, ": {} -> {}", old, new),
+            (Some(v), None) | (None, Some(v)) => write!(f, ": {}", v),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Compares `old` and `new` semantically.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{
+///     diff::{diff, ChangeKind, DiffTarget},
+///     parse,
+/// };
+///
+/// let old = parse("charset,UTF-8\r\nsurface0\r\n{\r\ncollision0,0,0,10,10,Head\r\n}\r\n").unwrap();
+/// let new = parse("charset,UTF-8\r\n// moved\r\nsurface0\r\n{\r\ncollision0,5,5,15,15,Head\r\n}\r\n").unwrap();
+///
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes.len(), 1);
+/// assert_eq!(changes[0].kind(), &ChangeKind::Changed);
+/// assert_eq!(changes[0].target(), &DiffTarget::Collision(0, 0));
+/// assert_eq!(
+///     changes[0].to_string(),
+///     "~ surface0 collision0: collision0,0,0,10,10,Head -> collision0,5,5,15,15,Head"
+/// );
+/// ```
+pub fn diff(old: &ShellSurfaces, new: &ShellSurfaces) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    diff_map(&mut changes, &descript_map(old), &descript_map(new), |k| {
+        DiffTarget::Descript(k.clone())
+    });
+
+    // Every id in a range resolves alike on each side, so a range is skipped when its first id
+    // has no change.
+    let mut ids = bounds(old);
+    ids.extend(bounds(new));
+    let mut added = Vec::new();
+    for ((range, o), (_, n)) in resolved_ranges(old, ids.clone()).zip(resolved_ranges(new, ids)) {
+        match (o, n) {
+            (Some(o), Some(n)) => {
+                let len = changes.len();
+                diff_surface(&mut changes, &o, &n);
+                if changes.len() > len {
+                    for id in range.skip(1) {
+                        diff_surface(&mut changes, &o.with_id(id), &n.with_id(id));
+                    }
+                }
+            }
+            (Some(_), None) => {
+                changes.extend(range.map(|id| {
+                    Change::new(ChangeKind::Removed, DiffTarget::Surface(id), None, None)
+                }))
+            }
+            (None, Some(_)) => added.push(range),
+            (None, None) => {}
+        }
+    }
+    changes.extend(
+        added
+            .into_iter()
+            .flatten()
+            .map(|id| Change::new(ChangeKind::Added, DiffTarget::Surface(id), None, None)),
+    );
+
+    diff_map(&mut changes, &alias_map(old), &alias_map(new), |(c, t)| {
+        DiffTarget::Alias(c.clone(), t.clone())
+    });
+    diff_map(
+        &mut changes,
+        &cursor_map(old),
+        &cursor_map(new),
+        |(c, kind, id)| DiffTarget::Cursor(c.clone(), kind.clone(), *id),
+    );
+    diff_map(
+        &mut changes,
+        &tooltip_map(old),
+        &tooltip_map(new),
+        |(c, t)| DiffTarget::Tooltip(c.clone(), t.clone()),
+    );
+
+    changes
+}
+
+fn diff_map<K, F>(
+    changes: &mut Vec<Change>,
+    old: &BTreeMap<K, String>,
+    new: &BTreeMap<K, String>,
+    target: F,
+) where
+    K: Ord,
+    F: Fn(&K) -> DiffTarget,
+{
+    for (k, o) in old.iter() {
+        match new.get(k) {
+            Some(n) if n != o => changes.push(Change::new(
+                ChangeKind::Changed,
+                target(k),
+                Some(o.clone()),
+                Some(n.clone()),
+            )),
+            Some(_) => {}
+            None => changes.push(Change::new(
+                ChangeKind::Removed,
+                target(k),
+                Some(o.clone()),
+                None,
+            )),
+        }
+    }
+    for (k, n) in new.iter() {
+        if !old.contains_key(k) {
+            changes.push(Change::new(
+                ChangeKind::Added,
+                target(k),
+                None,
+                Some(n.clone()),
+            ));
+        }
+    }
+}
+
+fn to_map<K, V, I, F, G>(values: I, key: F, value: G) -> BTreeMap<K, String>
+where
+    K: Ord,
+    I: IntoIterator<Item = V>,
+    F: Fn(&V) -> K,
+    G: Fn(&V) -> String,
+{
+    values.into_iter().map(|v| (key(&v), value(&v))).collect()
+}
+
+fn diff_surface(changes: &mut Vec<Change>, old: &ResolvedSurface, new: &ResolvedSurface) {
+    let s = *old.id();
+    diff_map(
+        changes,
+        &to_map(old.elements(), |v| *v.id(), |v| v.to_string()),
+        &to_map(new.elements(), |v| *v.id(), |v| v.to_string()),
+        |id| DiffTarget::Element(s, *id),
+    );
+
+    let old_animations: BTreeMap<_, _> = old.animations().iter().map(|v| (*v.id(), v)).collect();
+    let new_animations: BTreeMap<_, _> = new.animations().iter().map(|v| (*v.id(), v)).collect();
+    for (id, o) in old_animations.iter() {
+        match new_animations.get(id) {
+            Some(n) => diff_animation(changes, s, o, n),
+            None => changes.push(Change::new(
+                ChangeKind::Removed,
+                DiffTarget::Animation(s, *id),
+                None,
+                None,
+            )),
+        }
+    }
+    for id in new_animations.keys() {
+        if !old_animations.contains_key(id) {
+            changes.push(Change::new(
+                ChangeKind::Added,
+                DiffTarget::Animation(s, *id),
+                None,
+                None,
+            ));
+        }
+    }
+
+    diff_map(
+        changes,
+        &to_map(old.collisions(), |v| *v.id(), |v| v.to_string()),
+        &to_map(new.collisions(), |v| *v.id(), |v| v.to_string()),
+        |id| DiffTarget::Collision(s, *id),
+    );
+    diff_map(
+        changes,
+        &to_map(old.collisions_ex(), |v| *v.id(), |v| v.to_string()),
+        &to_map(new.collisions_ex(), |v| *v.id(), |v| v.to_string()),
+        |id| DiffTarget::CollisionEx(s, *id),
+    );
+    diff_map(
+        changes,
+        &to_map(old.offsets(), |(k, _)| **k, |(k, v)| format!("{},{}", k, v)),
+        &to_map(new.offsets(), |(k, _)| **k, |(k, v)| format!("{},{}", k, v)),
+        |key| DiffTarget::Offset(s, *key),
+    );
+}
+
+fn diff_animation(
+    changes: &mut Vec<Change>,
+    s: SurfaceIdType,
+    old: &ResolvedAnimation,
+    new: &ResolvedAnimation,
+) {
+    let a = *old.id();
+    let interval = |v: &ResolvedAnimation| {
+        let intervals: Vec<String> = v.intervals().iter().map(|i| i.to_string()).collect();
+        to_map(
+            Some(intervals).filter(|i| !i.is_empty()),
+            |_| (),
+            |i| format!("animation{}.interval,{}", a, i.join("+")),
+        )
+    };
+    diff_map(changes, &interval(old), &interval(new), |_| {
+        DiffTarget::AnimationInterval(s, a)
+    });
+
+    let option = |v: &ResolvedAnimation| {
+        let options: Vec<String> = v.options().iter().map(|o| o.to_string()).collect();
+        to_map(
+            Some(options).filter(|o| !o.is_empty()),
+            |_| (),
+            |o| format!("animation{}.option,{}", a, o.join("+")),
+        )
+    };
+    diff_map(changes, &option(old), &option(new), |_| {
+        DiffTarget::AnimationOption(s, a)
+    });
+
+    diff_map(
+        changes,
+        &to_map(old.patterns(), |v| *v.pattern_id(), |v| v.to_string()),
+        &to_map(new.patterns(), |v| *v.pattern_id(), |v| v.to_string()),
+        |id| DiffTarget::AnimationPattern(s, a, *id),
+    );
+    diff_map(
+        changes,
+        &to_map(old.collisions(), |v| *v.id(), |v| v.to_string()),
+        &to_map(new.collisions(), |v| *v.id(), |v| v.to_string()),
+        |id| DiffTarget::AnimationCollision(s, a, *id),
+    );
+    diff_map(
+        changes,
+        &to_map(old.collisions_ex(), |v| *v.id(), |v| v.to_string()),
+        &to_map(new.collisions_ex(), |v| *v.id(), |v| v.to_string()),
+        |id| DiffTarget::AnimationCollisionEx(s, a, *id),
+    );
+}
+
+fn descript_map(shell_surfaces: &ShellSurfaces) -> BTreeMap<String, String> {
+    shell_surfaces
+        .braces()
+        .iter()
+        .filter_map(|v| match v.body() {
+            Brace::Descript(d) => Some(d.lines()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(LineContainer::body)
+        .map(|v: &DescriptInner| {
+            let text = v.to_string();
+            let key = text.split(',').next().unwrap_or_default().to_string();
+            (key, text)
+        })
+        .collect()
+}
+
+fn alias_map(
+    shell_surfaces: &ShellSurfaces,
+) -> BTreeMap<(SurfaceTargetCharacterId, String), String> {
+    let mut result = BTreeMap::new();
+    for brace in shell_surfaces.braces() {
+        if let Brace::SurfaceAlias(v) = brace.body() {
+            for inner in v.lines().iter().filter_map(LineContainer::body) {
+                result.insert(
+                    (v.id().normalized(), inner.target().to_string()),
+                    inner.to_string(),
+                );
+            }
+        }
+    }
+    result
+}
+
+fn cursor_map(
+    shell_surfaces: &ShellSurfaces,
+) -> BTreeMap<(SurfaceTargetCharacterId, GestureKind, CursorMouseIdType), String> {
+    let mut result = BTreeMap::new();
+    for brace in shell_surfaces.braces() {
+        if let Brace::Cursor(v) = brace.body() {
+            for inner in v.lines().iter().filter_map(LineContainer::body) {
+                result.insert(
+                    (v.id().normalized(), inner.kind().clone(), *inner.id()),
+                    inner.to_string(),
+                );
+            }
+        }
+    }
+    result
+}
+
+fn tooltip_map(
+    shell_surfaces: &ShellSurfaces,
+) -> BTreeMap<(SurfaceTargetCharacterId, String), String> {
+    let mut result = BTreeMap::new();
+    for brace in shell_surfaces.braces() {
+        if let Brace::Tooltip(v) = brace.body() {
+            for inner in v.lines().iter().filter_map(LineContainer::body) {
+                result.insert(
+                    (v.id().normalized(), inner.collision().clone()),
+                    inner.to_string(),
+                );
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod diff {
+        use super::*;
+
+        #[test]
+        fn success_when_same_semantics() {
+            let old = r#"charset,UTF-8
+surface0
+{
+0interval,sometimes
+0pattern0,100,5,overlay,0,0
+element0,overlay,body0.png,0,0
+}
+surface1
+{
+}
+"#;
+            let new = r#"charset,UTF-8
+// reordered
+surface1
+{
+}
+
+surface0
+{
+	element0,overlay,body0.png,0,0
+	animation0.interval,sometimes
+	animation0.pattern0,overlay,100,50,0,0
+}
+"#;
+            assert!(diff(&parse(old).unwrap(), &parse(new).unwrap()).is_empty());
+        }
+
+        #[test]
+        fn success_when_large_range() {
+            let old = "charset,UTF-8\r\nsurface0-4294967295\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n";
+            let new = "charset,UTF-8\r\nsurface0-4294967295\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\nsurface.append5\r\n{\r\nelement1,overlay,face.png,0,0\r\n}\r\n";
+            let result = diff(&parse(old).unwrap(), &parse(new).unwrap());
+            assert_eq!(
+                result,
+                vec![Change::new(
+                    ChangeKind::Added,
+                    DiffTarget::Element(5, 1),
+                    None,
+                    Some("element1,overlay,face.png,0,0".to_string())
+                )]
+            );
+        }
+
+        #[test]
+        fn success_when_changed() {
+            let old = r#"charset,UTF-8
+descript
+{
+version,0
+}
+surface0
+{
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes
+animation0.pattern0,overlay,100,50,0,0
+animation1.interval,always
+collision0,0,0,10,10,Head
+}
+surface1
+{
+}
+sakura.surface.alias
+{
+通常,[0]
+}
+sakura.tooltips
+{
+Head,なでる
+}
+"#;
+            let new = r#"charset,UTF-8
+descript
+{
+version,1
+}
+surface0
+{
+element0,overlay,body0.png,0,0
+element1,overlay,face0.png,0,0
+animation0.interval,rarely
+animation0.pattern0,overlay,100,50,0,0
+collision0,5,5,15,15,Head
+sakura.balloon.offsetx,10
+}
+surface2
+{
+}
+sakura.surface.alias
+{
+通常,[0,2]
+}
+sakura.cursor
+{
+mouseup0,Head,hand.cur
+}
+sakura.tooltips
+{
+Head,なでなで
+}
+"#;
+            let result: Vec<String> = diff(&parse(old).unwrap(), &parse(new).unwrap())
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    "~ descript version: version,0 -> version,1",
+                    "+ surface0 element1: element1,overlay,face0.png,0,0",
+                    "~ surface0 animation0.interval: animation0.interval,sometimes -> animation0.interval,rarely",
+                    "- surface0 animation1",
+                    "~ surface0 collision0: collision0,0,0,10,10,Head -> collision0,5,5,15,15,Head",
+                    "+ surface0 sakura.balloon.offsetx: sakura.balloon.offsetx,10",
+                    "- surface1",
+                    "+ surface2",
+                    "~ char0.surface.alias 通常: 通常,[0] -> 通常,[0,2]",
+                    "+ char0.cursor mouseup0: mouseup0,Head,hand.cur",
+                    "~ char0.tooltips Head: Head,なでる -> Head,なでなで",
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_same_character_renamed() {
+            let old = "charset,UTF-8\r\nsakura.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nkero.tooltips\r\n{\r\nHead,なでる\r\n}\r\n";
+            let new = "charset,UTF-8\r\nchar0.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nchar1.tooltips\r\n{\r\nHead,なでる\r\n}\r\n";
+            assert!(diff(&parse(old).unwrap(), &parse(new).unwrap()).is_empty());
+        }
+    }
+}
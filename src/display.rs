@@ -0,0 +1,768 @@
+//! Writes AST back to the text of `surfaces.txt`.
+
+use std::fmt::{self, Display};
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AliasTarget, AnimationInterval, AnimationOptionKind, AnimationPatternDrawMethod,
+    AnimationPatternProperty, BlockComment, Brace, BraceContainer, BraceLayout, CollisionExKind,
+    CommentLine, Descript, DescriptInner, DrawMethod, DrawMethodOnAnimation, GestureKind,
+    LineContainer, ShellSurfaces, ShellSurfacesCursor, ShellSurfacesCursorGesture, SortOrder,
+    Surface, SurfaceAlias, SurfaceAliasInner, SurfaceAnimationCollision,
+    SurfaceAnimationCollisionEx, SurfaceAnimationInterval, SurfaceAnimationOption,
+    SurfaceAnimationPattern, SurfaceAppend, SurfaceCollision, SurfaceCollisionEx, SurfaceElement,
+    SurfaceId, SurfaceInner, SurfaceTargetCharacterId, Tooltip, TooltipInner,
+};
+
+pub(crate) const NEWLINE: &str = "\r\n";
+
+/// Every [`Charset`], whose names are matched by the parser and serde.
+pub(crate) const CHARSETS: [Charset; 6] = [
+    Charset::ASCII,
+    Charset::ShiftJIS,
+    Charset::ISO2022JP,
+    Charset::EUCJP,
+    Charset::UTF8,
+    Charset::Default,
+];
+
+/// Returns the name of `charset` as written after `charset,`.
+pub(crate) fn charset_name(charset: &Charset) -> &'static str {
+    match charset {
+        Charset::ASCII => "ASCII",
+        Charset::ShiftJIS => "Shift_JIS",
+        Charset::ISO2022JP => "ISO-2022-JP",
+        Charset::EUCJP => "EUC-JP",
+        Charset::UTF8 => "UTF-8",
+        Charset::Default => "default",
+    }
+}
+
+fn write_joined<T: Display>(f: &mut fmt::Formatter<'_>, values: &[T], sep: &str) -> fmt::Result {
+    for (i, v) in values.iter().enumerate() {
+        if i != 0 {
+            write!(f, "{}", sep)?;
+        }
+        write!(f, "{}", v)?;
+    }
+    Ok(())
+}
+
+fn write_comments(f: &mut fmt::Formatter<'_>, comments: &[CommentLine]) -> fmt::Result {
+    for c in comments {
+        write!(f, "{}{}", c, NEWLINE)?;
+    }
+    Ok(())
+}
+
+/// Whether `lines` can be written as [`BraceLayout::OneLine`], which holds at most one body line
+/// without a trailing comment.
+pub(crate) fn fits_one_line<T, S>(lines: &[LineContainer<T, S>]) -> bool {
+    match lines {
+        [] => true,
+        [LineContainer::Body(_, format)] => format.comment().is_none(),
+        _ => false,
+    }
+}
+
+/// Writes a brace with `layout`, or with [`BraceLayout::SameLine`] when `lines` do not fit on one
+/// line.
+fn write_brace<T: Display>(
+    f: &mut fmt::Formatter<'_>,
+    name: &dyn Display,
+    lines: &[LineContainer<T>],
+    layout: &BraceLayout,
+) -> fmt::Result {
+    match layout {
+        BraceLayout::OneLine if fits_one_line(lines) => {
+            return match lines.first() {
+                Some(line) => write!(f, "{} {{ {} }}{}", name, line, NEWLINE),
+                None => write!(f, "{} {{}}{}", name, NEWLINE),
+            };
+        }
+        BraceLayout::OwnLines => write!(f, "{}{}{{{}", name, NEWLINE, NEWLINE)?,
+        BraceLayout::SameLine | BraceLayout::OneLine => write!(f, "{} {{{}", name, NEWLINE)?,
+    }
+    for line in lines {
+        write!(f, "{}{}", line, NEWLINE)?;
+    }
+    write!(f, "}}{}", NEWLINE)
+}
+
+impl Display for ShellSurfaces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_comments(f, self.header_comments())?;
+        if *self.charset_declared() {
+            write!(f, "charset,{}{}", charset_name(self.charset()), NEWLINE)?;
+        }
+        for brace in self.braces() {
+            write!(f, "{}", brace)?;
+        }
+        write_comments(f, self.footer_comments())
+    }
+}
+
+impl Display for CommentLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.body())
+    }
+}
+
+impl Display for BlockComment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/*{}*/", self.body())
+    }
+}
+
+impl<T: Display> Display for LineContainer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineContainer::Comment(v) => write!(f, "{}", v),
+            LineContainer::BlockComment(v) => write!(f, "{}", v),
+            LineContainer::Body(v, format) => {
+                match format.raw() {
+                    Some(raw) => write!(f, "{}", raw)?,
+                    None => write!(f, "{}", v)?,
+                }
+                match format.comment() {
+                    Some(comment) => write!(f, " {}", comment),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Display for BraceContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_comments(f, self.header_comments())?;
+        self.body().write_with_layout(f, self.layout())
+    }
+}
+
+impl Display for Brace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with_layout(f, &BraceLayout::default())
+    }
+}
+
+impl Brace {
+    fn write_with_layout(&self, f: &mut fmt::Formatter<'_>, layout: &BraceLayout) -> fmt::Result {
+        match self {
+            Brace::Descript(v) => write_brace(f, &"descript", v.lines(), layout),
+            Brace::Surface(v) => write_brace(f, &SurfaceIds("surface", v.ids()), v.lines(), layout),
+            Brace::SurfaceAppend(v) => {
+                write_brace(f, &SurfaceIds("surface.append", v.ids()), v.lines(), layout)
+            }
+            Brace::SurfaceAlias(v) => {
+                write_brace(f, &format!("{}.surface.alias", v.id()), v.lines(), layout)
+            }
+            Brace::Cursor(v) => write_brace(f, &format!("{}.cursor", v.id()), v.lines(), layout),
+            Brace::Tooltip(v) => write_brace(f, &format!("{}.tooltips", v.id()), v.lines(), layout),
+            Brace::BlockComment(v) => write!(f, "{}{}", v, NEWLINE),
+        }
+    }
+}
+
+impl Display for Descript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(f, &"descript", self.lines(), &BraceLayout::default())
+    }
+}
+
+impl Display for DescriptInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescriptInner::Version(v) => write!(f, "version,{}", v),
+            DescriptInner::MaxWidth(v) => write!(f, "maxwidth,{}", v),
+            DescriptInner::CollistionSort(v) => write!(f, "collision-sort,{}", v),
+            DescriptInner::AnimationSort(v) => write!(f, "animation-sort,{}", v),
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::Ascend => write!(f, "ascend"),
+            SortOrder::Descend => write!(f, "descend"),
+        }
+    }
+}
+
+struct SurfaceIds<'a>(&'a str, &'a [SurfaceId]);
+
+impl<'a> Display for SurfaceIds<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        write_joined(f, self.1, ",")
+    }
+}
+
+impl Display for Surface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(
+            f,
+            &SurfaceIds("surface", self.ids()),
+            self.lines(),
+            &BraceLayout::default(),
+        )
+    }
+}
+
+impl Display for SurfaceAppend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(
+            f,
+            &SurfaceIds("surface.append", self.ids()),
+            self.lines(),
+            &BraceLayout::default(),
+        )
+    }
+}
+
+impl Display for SurfaceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurfaceId::Unit(v) => write!(f, "{}", v),
+            SurfaceId::Range(start, end) => write!(f, "{}-{}", start, end),
+            SurfaceId::RangeFrom(start) => write!(f, "{}-", start),
+            SurfaceId::All => write!(f, "*"),
+            SurfaceId::Not(v) => write!(f, "!{}", v),
+        }
+    }
+}
+
+impl Display for SurfaceInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurfaceInner::Element(v) => write!(f, "{}", v),
+            SurfaceInner::AnimationInterval(v) => write!(f, "{}", v),
+            SurfaceInner::AnimationPattern(v) => write!(f, "{}", v),
+            SurfaceInner::AnimationOption(v) => write!(f, "{}", v),
+            SurfaceInner::AnimationCollision(v) => write!(f, "{}", v),
+            SurfaceInner::AnimationCollisionEx(v) => write!(f, "{}", v),
+            SurfaceInner::Collision(v) => write!(f, "{}", v),
+            SurfaceInner::CollisionEx(v) => write!(f, "{}", v),
+            SurfaceInner::SakuraBalloonOffsetX(v) => write!(f, "sakura.balloon.offsetx,{}", v),
+            SurfaceInner::SakuraBalloonOffsetY(v) => write!(f, "sakura.balloon.offsety,{}", v),
+            SurfaceInner::KeroBalloonOffsetX(v) => write!(f, "kero.balloon.offsetx,{}", v),
+            SurfaceInner::KeroBalloonOffsetY(v) => write!(f, "kero.balloon.offsety,{}", v),
+            SurfaceInner::BalloonOffsetX(v) => write!(f, "balloon.offsetx,{}", v),
+            SurfaceInner::BalloonOffsetY(v) => write!(f, "balloon.offsety,{}", v),
+            SurfaceInner::CharacterBalloonOffsetX(id, v) => {
+                write!(f, "{}.balloon.offsetx,{}", id, v)
+            }
+            SurfaceInner::CharacterBalloonOffsetY(id, v) => {
+                write!(f, "{}.balloon.offsety,{}", id, v)
+            }
+            SurfaceInner::PointCenterX(v) => write!(f, "point.centerx,{}", v),
+            SurfaceInner::PointCenterY(v) => write!(f, "point.centery,{}", v),
+            SurfaceInner::PointKinokoCenterX(v) => write!(f, "point.kinoko.centerx,{}", v),
+            SurfaceInner::PointKinokoCenterY(v) => write!(f, "point.kinoko.centery,{}", v),
+            SurfaceInner::PointBaseposX(v) => write!(f, "point.basepos.x,{}", v),
+            SurfaceInner::PointBaseposY(v) => write!(f, "point.basepos.y,{}", v),
+        }
+    }
+}
+
+impl Display for SurfaceElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "element{},{},{},{},{}",
+            self.id(),
+            self.method(),
+            self.filename(),
+            self.x(),
+            self.y()
+        )
+    }
+}
+
+impl Display for DrawMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = match self {
+            DrawMethod::Base => "base",
+            DrawMethod::Overlay => "overlay",
+            DrawMethod::Overlayfast => "overlayfast",
+            DrawMethod::Overlaymultiply => "overlaymultiply",
+            DrawMethod::Replace => "replace",
+            DrawMethod::Interpolate => "interpolate",
+            DrawMethod::Asis => "asis",
+            DrawMethod::Move => "move",
+            DrawMethod::Bind => "bind",
+            DrawMethod::Add => "add",
+            DrawMethod::Reduce => "reduce",
+        };
+        write!(f, "{}", v)
+    }
+}
+
+impl Display for DrawMethodOnAnimation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, ids) = match self {
+            DrawMethodOnAnimation::Insert(v) => return write!(f, "insert,{}", v),
+            DrawMethodOnAnimation::Start(v) => return write!(f, "start,{}", v),
+            DrawMethodOnAnimation::Stop(v) => return write!(f, "stop,{}", v),
+            DrawMethodOnAnimation::Alternativestart(v) => ("alternativestart", v),
+            DrawMethodOnAnimation::Alternativestop(v) => ("alternativestop", v),
+            DrawMethodOnAnimation::Parallelstart(v) => ("parallelstart", v),
+            DrawMethodOnAnimation::Parallelstop(v) => ("parallelstop", v),
+        };
+        write!(f, "{},(", name)?;
+        write_joined(f, ids, ",")?;
+        write!(f, ")")
+    }
+}
+
+impl Display for SurfaceAnimationInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "animation{}.interval,", self.id())?;
+        write_joined(f, self.intervals(), "+")
+    }
+}
+
+impl Display for AnimationInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationInterval::Sometimes => write!(f, "sometimes"),
+            AnimationInterval::Rarely => write!(f, "rarely"),
+            AnimationInterval::Random(v) => write!(f, "random,{}", v),
+            AnimationInterval::Periodic(v) => write!(f, "periodic,{}", v),
+            AnimationInterval::Always => write!(f, "always"),
+            AnimationInterval::Runonce => write!(f, "runonce"),
+            AnimationInterval::Never => write!(f, "never"),
+            AnimationInterval::YenE => write!(f, "yen-e"),
+            AnimationInterval::Talk(v) => write!(f, "talk,{}", v),
+            AnimationInterval::Bind => write!(f, "bind"),
+        }
+    }
+}
+
+impl Display for SurfaceAnimationPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "animation{}.pattern{},{}",
+            self.id(),
+            self.pattern_id(),
+            self.method()
+        )
+    }
+}
+
+impl Display for AnimationPatternDrawMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationPatternDrawMethod::Normal(method, property) => {
+                write!(f, "{},{}", method, property)
+            }
+            AnimationPatternDrawMethod::Animation(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl Display for AnimationPatternProperty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.surface_id(),
+            self.weight(),
+            self.x(),
+            self.y()
+        )
+    }
+}
+
+impl Display for SurfaceAnimationOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "animation{}.option,", self.id())?;
+        write_joined(f, self.options(), "+")?;
+        let ids = self.options().iter().find_map(|v| match v {
+            AnimationOptionKind::Exclusive(Some(ids)) => Some(ids),
+            _ => None,
+        });
+        if let Some(ids) = ids {
+            write!(f, ",(")?;
+            write_joined(f, ids, ",")?;
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AnimationOptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationOptionKind::Exclusive(_) => write!(f, "exclusive"),
+            AnimationOptionKind::Background => write!(f, "background"),
+            AnimationOptionKind::SharedIndex => write!(f, "shared-index"),
+        }
+    }
+}
+
+impl Display for SurfaceAnimationCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "animation{}.{}", self.id(), self.collision())
+    }
+}
+
+impl Display for SurfaceAnimationCollisionEx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "animation{}.{}", self.id(), self.collision())
+    }
+}
+
+impl Display for SurfaceCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "collision{},{},{},{},{},{}",
+            self.id(),
+            self.start_x(),
+            self.start_y(),
+            self.end_x(),
+            self.end_y(),
+            self.target_id()
+        )
+    }
+}
+
+impl Display for SurfaceCollisionEx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "collisionex{},{},{}",
+            self.id(),
+            self.target_id(),
+            self.kind()
+        )
+    }
+}
+
+impl Display for CollisionExKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollisionExKind::Rect(sx, sy, ex, ey) => write!(f, "rect,{},{},{},{}", sx, sy, ex, ey),
+            CollisionExKind::Ellipse(sx, sy, ex, ey) => {
+                write!(f, "ellipse,{},{},{},{}", sx, sy, ex, ey)
+            }
+            CollisionExKind::Circle(x, y, r) => write!(f, "circle,{},{},{}", x, y, r),
+            CollisionExKind::Polygon(v) => {
+                write!(f, "polygon,")?;
+                write_joined(f, v, ",")
+            }
+            CollisionExKind::Region(filename, r, g, b, flag) => {
+                write!(f, "region,{},{},{},{}", filename, r, g, b)?;
+                match flag {
+                    Some(v) => write!(f, ",{}", v),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl Display for SurfaceTargetCharacterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurfaceTargetCharacterId::Sakura => write!(f, "sakura"),
+            SurfaceTargetCharacterId::Kero => write!(f, "kero"),
+            SurfaceTargetCharacterId::Char(v) => write!(f, "char{}", v),
+        }
+    }
+}
+
+impl Display for SurfaceAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(
+            f,
+            &format!("{}.surface.alias", self.id()),
+            self.lines(),
+            &BraceLayout::default(),
+        )
+    }
+}
+
+impl Display for AliasTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AliasTarget::Id(v) => write!(f, "{}", v),
+            AliasTarget::Name(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl Display for SurfaceAliasInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},[", self.target())?;
+        write_joined(f, self.surfaces(), ",")?;
+        write!(f, "]")
+    }
+}
+
+impl Display for ShellSurfacesCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(
+            f,
+            &format!("{}.cursor", self.id()),
+            self.lines(),
+            &BraceLayout::default(),
+        )
+    }
+}
+
+impl Display for ShellSurfacesCursorGesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{},{},{}",
+            self.kind(),
+            self.id(),
+            self.target_collistion(),
+            self.filename()
+        )
+    }
+}
+
+impl Display for GestureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = match self {
+            GestureKind::MouseUp => "mouseup",
+            GestureKind::MouseDown => "mousedown",
+            GestureKind::MouseRightDown => "mouserightdown",
+            GestureKind::MouseWheel => "mousewheel",
+            GestureKind::MouseHover => "mousehover",
+        };
+        write!(f, "{}", v)
+    }
+}
+
+impl Display for Tooltip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_brace(
+            f,
+            &format!("{}.tooltips", self.id()),
+            self.lines(),
+            &BraceLayout::default(),
+        )
+    }
+}
+
+impl Display for TooltipInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.collision(), self.description())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod shell_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_round_trip() {
+            let case = r#"// header
+charset,Shift_JIS
+
+descript
+{
+version,1
+maxwidth,320
+collision-sort,ascend
+animation-sort,descend
+}
+
+surface0,3-5,!4
+{
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes+random,5
+animation0.pattern0,overlay,101,100,168,67
+animation0.pattern1,alternativestart,(1,2)
+animation0.pattern2,insert,3
+animation0.option,exclusive+background,(1,3)
+animation0.collision0,10,10,200,100,Head
+animation0.collisionex1,Face,circle,100,200,20
+collision0,40,56,95,90,Head
+collisionex1,Bust,polygon,100,100,200,300,50,200
+collisionex2,Skirt,region,atari.png,0,255,0,true
+// comment
+sakura.balloon.offsetx,80
+char2.balloon.offsety,-20
+point.basepos.y,-10
+}
+
+surface.append0-9
+{
+collision0,188,25,252,63,Head
+}
+
+sakura.surface.alias
+{
+素,[0]
+照れ,[1,101,201]
+}
+
+char2.cursor
+{
+mouseup0,Head,system:hand
+}
+
+kero.tooltips
+{
+Bust,怒ります。
+}
+// footer
+"#
+            .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(result.to_string(), case);
+            assert_eq!(parse(&result.to_string()).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_round_trip_trailing_comments() {
+            let case = r#"charset,UTF-8
+surface0
+{
+collision0,40,56,95,90,Head // forehead
+element0, overlay, face.png, 0, 0 // spaced
+sakura.balloon.offsetx,80 //
+}
+char2.cursor
+{
+mouseup0,Head,system:hand // hand
+}
+kero.tooltips
+{
+Bust,http://example.com/ // link
+}
+"#
+            .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(result.to_string(), case);
+            assert_eq!(parse(&result.to_string()).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_round_trip_block_comments() {
+            let case = r#"charset,UTF-8
+/* old face
+surface1
+{
+element0,overlay,face1.png,0,0
+}
+*/
+surface0
+{
+/* one line */
+element0,overlay,face0.png,0,0
+/*
+collision0,40,56,95,90,Head
+*/
+}
+"#
+            .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(result.to_string(), case);
+            assert_eq!(parse(&result.to_string()).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_round_trip_brace_layouts() {
+            let case = r#"charset,UTF-8
+descript {
+version,1
+}
+surface5 { element0,base,a.png,0,0 }
+surface6 {}
+sakura.tooltips { Head, head }
+"#
+            .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(result.to_string(), case);
+            assert_eq!(parse(&result.to_string()).unwrap(), result);
+
+            let result = parse(&"surface0{\n}\n".replace('\n', NEWLINE)).unwrap();
+            assert_eq!(result.to_string(), "surface0 {\n}\n".replace('\n', NEWLINE));
+        }
+
+        #[test]
+        fn success_when_one_line_brace_does_not_fit() {
+            let mut result = parse(&"surface5 {}\n".replace('\n', NEWLINE)).unwrap();
+            if let Brace::Surface(v) = result.braces_mut()[0].body_mut() {
+                v.push_line(LineContainer::Comment(CommentLine::new("// a".to_string())));
+            }
+            assert_eq!(
+                result.to_string(),
+                "surface5 {\n// a\n}\n".replace('\n', NEWLINE)
+            );
+        }
+
+        #[test]
+        fn success_when_round_trip_open_ids() {
+            let case = "charset,UTF-8\nsurface10-,!20-\n{\n}\nsurface.append*,!0-3\n{\n}\n"
+                .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(result.to_string(), case);
+        }
+
+        #[test]
+        fn success_when_round_trip_spaced_commas() {
+            let case = r#"charset, UTF-8
+surface0, 3
+{
+element0, overlay, face.png, 0, 0
+collision0,40, 56,95,90,Head
+animation0.pattern0 ,alternativestart, (1, 2)
+}
+sakura.surface.alias
+{
+照れ , [1, 101]
+}
+"#
+            .replace('\n', NEWLINE);
+            let result = parse(&case).unwrap();
+            assert_eq!(
+                result.to_string(),
+                case.replacen("charset, UTF-8", "charset,UTF-8", 1)
+                    .replacen("surface0, 3", "surface0,3", 1)
+            );
+            assert_eq!(parse(&result.to_string()).unwrap(), result);
+        }
+    }
+
+    mod charset_name {
+        use super::*;
+
+        #[test]
+        fn success_when_known_charset() {
+            assert_eq!(charset_name(&Charset::ShiftJIS), "Shift_JIS");
+            assert_eq!(charset_name(&Charset::UTF8), "UTF-8");
+            assert_eq!(charset_name(&Charset::ASCII), "ASCII");
+            assert_eq!(charset_name(&Charset::ISO2022JP), "ISO-2022-JP");
+            assert_eq!(charset_name(&Charset::EUCJP), "EUC-JP");
+            assert_eq!(charset_name(&Charset::Default), "default");
+        }
+    }
+
+    mod surface_inner {
+        use super::*;
+
+        #[test]
+        fn success_when_v0_syntax() {
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n0interval,never\r\n0pattern0,-1,7,overlay,0,0\r\n}\r\n";
+            let result = parse(case).unwrap().to_string();
+            assert_eq!(
+                result,
+                "charset,UTF-8\r\nsurface0\r\n{\r\nanimation0.interval,never\r\nanimation0.pattern0,overlay,-1,70,0,0\r\n}\r\n"
+            );
+        }
+    }
+}
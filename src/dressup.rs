@@ -0,0 +1,561 @@
+//! Dressup model joining bind animations in `surfaces.txt` and bind groups in `descript.txt`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::RangeInclusive,
+};
+
+use crate::ast::{
+    AnimationIdType, AnimationInterval, AnimationPatternDrawMethod, Brace, DrawMethod,
+    LineContainer, ShellSurfaces, SurfaceAnimationPattern, SurfaceId, SurfaceIdType, SurfaceInner,
+    SurfaceTargetCharacterId,
+};
+
+/// Bind group definitions read from `descript.txt`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindDescript {
+    groups: Vec<BindGroup>,
+    options: Vec<BindOption>,
+}
+
+/// `{character}.bindgroupN.name` and `{character}.bindgroupN.default`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindGroup {
+    character: SurfaceTargetCharacterId,
+    id: AnimationIdType,
+    category: String,
+    part: String,
+    thumbnail: Option<String>,
+    default: bool,
+}
+
+/// `{character}.bindoptionN.group`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindOption {
+    character: SurfaceTargetCharacterId,
+    category: String,
+    kinds: Vec<BindOptionKind>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindOptionKind {
+    Multiple,
+    MustSelect,
+}
+
+/// Animation marked as a dressup part in a surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindAnimation {
+    id: AnimationIdType,
+    intervals: Vec<AnimationInterval>,
+    patterns: Vec<SurfaceAnimationPattern>,
+}
+
+/// Bind groups and the bind animations of every defined surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dressup {
+    groups: Vec<BindGroup>,
+    options: Vec<BindOption>,
+    surfaces: Vec<(RangeInclusive<SurfaceIdType>, Vec<BindAnimation>)>,
+}
+
+/// Chosen on/off state of bind groups. Groups not set here use their `default`.
+///
+/// States are kept per character as `charN`, so `sakura` and `char0` share them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DressupConfig {
+    states: HashMap<(SurfaceTargetCharacterId, AnimationIdType), bool>,
+}
+
+impl BindDescript {
+    pub fn new(groups: Vec<BindGroup>, options: Vec<BindOption>) -> BindDescript {
+        BindDescript { groups, options }
+    }
+
+    pub fn groups(&self) -> &Vec<BindGroup> {
+        &self.groups
+    }
+    pub fn options(&self) -> &Vec<BindOption> {
+        &self.options
+    }
+}
+
+impl BindGroup {
+    pub fn new(
+        character: SurfaceTargetCharacterId,
+        id: AnimationIdType,
+        category: String,
+        part: String,
+        thumbnail: Option<String>,
+        default: bool,
+    ) -> BindGroup {
+        BindGroup {
+            character,
+            id,
+            category,
+            part,
+            thumbnail,
+            default,
+        }
+    }
+
+    pub fn character(&self) -> &SurfaceTargetCharacterId {
+        &self.character
+    }
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    pub fn category(&self) -> &String {
+        &self.category
+    }
+    pub fn part(&self) -> &String {
+        &self.part
+    }
+    pub fn thumbnail(&self) -> &Option<String> {
+        &self.thumbnail
+    }
+    pub fn default(&self) -> &bool {
+        &self.default
+    }
+    pub fn default_mut(&mut self) -> &mut bool {
+        &mut self.default
+    }
+}
+
+impl BindOption {
+    pub fn new(
+        character: SurfaceTargetCharacterId,
+        category: String,
+        kinds: Vec<BindOptionKind>,
+    ) -> BindOption {
+        BindOption {
+            character,
+            category,
+            kinds,
+        }
+    }
+
+    pub fn character(&self) -> &SurfaceTargetCharacterId {
+        &self.character
+    }
+    pub fn category(&self) -> &String {
+        &self.category
+    }
+    pub fn kinds(&self) -> &Vec<BindOptionKind> {
+        &self.kinds
+    }
+}
+
+impl BindAnimation {
+    pub fn new(
+        id: AnimationIdType,
+        intervals: Vec<AnimationInterval>,
+        patterns: Vec<SurfaceAnimationPattern>,
+    ) -> BindAnimation {
+        BindAnimation {
+            id,
+            intervals,
+            patterns,
+        }
+    }
+
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    pub fn intervals(&self) -> &Vec<AnimationInterval> {
+        &self.intervals
+    }
+    pub fn patterns(&self) -> &Vec<SurfaceAnimationPattern> {
+        &self.patterns
+    }
+}
+
+impl Dressup {
+    /// Collects animations which have `bind` interval or `bind` pattern in every surface,
+    /// including lines added by `surface.append`.
+    ///
+    /// Only braces with animation lines are scanned, once for each range of surface ids they
+    /// apply to alike, so no range such as `surface0-4294967295` is expanded.
+    pub fn new(shell_surfaces: &ShellSurfaces, descript: &BindDescript) -> Dressup {
+        let mut bounds = BTreeSet::new();
+        let mut braces = Vec::new();
+        for brace in shell_surfaces.braces() {
+            let (ids, lines) = match brace.body() {
+                Brace::Surface(v) => {
+                    bounds.extend(v.ids().iter().flat_map(SurfaceId::bounds));
+                    (v.ids(), v.lines())
+                }
+                Brace::SurfaceAppend(v) => (v.ids(), v.lines()),
+                _ => continue,
+            };
+            let has_animation = lines.iter().any(|v| {
+                matches!(
+                    v.body(),
+                    Some(SurfaceInner::AnimationInterval(_) | SurfaceInner::AnimationPattern(_))
+                )
+            });
+            if has_animation {
+                bounds.extend(ids.iter().flat_map(SurfaceId::bounds));
+                braces.push((ids.as_slice(), lines.as_slice()));
+            }
+        }
+
+        let mut surfaces: Vec<(RangeInclusive<SurfaceIdType>, Vec<BindAnimation>)> = Vec::new();
+        for range in SurfaceId::split(bounds).filter(|v| shell_surfaces.defines_surface(*v.start()))
+        {
+            let animations = bind_animations(&braces, *range.start());
+            if animations.is_empty() {
+                continue;
+            }
+            match surfaces.last_mut() {
+                Some((last, v))
+                    if *v == animations && last.end().checked_add(1) == Some(*range.start()) =>
+                {
+                    *last = *last.start()..=*range.end();
+                }
+                _ => surfaces.push((range, animations)),
+            }
+        }
+
+        Dressup {
+            groups: descript.groups().clone(),
+            options: descript.options().clone(),
+            surfaces,
+        }
+    }
+
+    pub fn groups(&self) -> &Vec<BindGroup> {
+        &self.groups
+    }
+    pub fn options(&self) -> &Vec<BindOption> {
+        &self.options
+    }
+    /// Bind animations keyed by ranges of surface ids sharing them, sorted by id.
+    pub fn surfaces(&self) -> &Vec<(RangeInclusive<SurfaceIdType>, Vec<BindAnimation>)> {
+        &self.surfaces
+    }
+
+    /// Returns bind animations of surface `surface_id`, or `None` when it has none.
+    pub fn animations(&self, surface_id: SurfaceIdType) -> Option<&Vec<BindAnimation>> {
+        let i = self
+            .surfaces
+            .partition_point(|(range, _)| *range.end() < surface_id);
+        self.surfaces
+            .get(i)
+            .filter(|(range, _)| range.contains(&surface_id))
+            .map(|(_, v)| v)
+    }
+
+    pub fn group(
+        &self,
+        character: &SurfaceTargetCharacterId,
+        id: AnimationIdType,
+    ) -> Option<&BindGroup> {
+        self.groups
+            .iter()
+            .find(|v| v.character().index() == character.index() && v.id() == &id)
+    }
+
+    /// Returns bind animation ids of surface `surface_id`.
+    pub fn animation_ids(&self, surface_id: SurfaceIdType) -> Vec<AnimationIdType> {
+        self.animations(surface_id)
+            .map(|v| v.iter().map(|a| a.id).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if groups in `category` of `character` can be selected at the same time.
+    pub fn is_multiple(&self, character: &SurfaceTargetCharacterId, category: &str) -> bool {
+        self.options.iter().any(|v| {
+            v.character().index() == character.index()
+                && v.category() == category
+                && v.kinds().contains(&BindOptionKind::Multiple)
+        })
+    }
+
+    /// Turns group `id` of `character` on or off in `config`.
+    ///
+    /// Turning on a group turns off the others in the same category unless the category is `multiple`.
+    /// Turning off the last group in a `mustselect` category is ignored.
+    pub fn select(
+        &self,
+        config: &mut DressupConfig,
+        character: &SurfaceTargetCharacterId,
+        id: AnimationIdType,
+        enabled: bool,
+    ) {
+        let group = match self.group(character, id) {
+            Some(v) => v,
+            None => {
+                config.set(character.clone(), id, enabled);
+                return;
+            }
+        };
+        let others: Vec<&BindGroup> = self
+            .groups
+            .iter()
+            .filter(|v| {
+                v.character().index() == character.index()
+                    && v.category() == group.category()
+                    && v.id() != &id
+            })
+            .collect();
+
+        if enabled {
+            if !self.is_multiple(character, group.category()) {
+                for other in others {
+                    config.set(character.clone(), other.id, false);
+                }
+            }
+        } else {
+            let must_select = self.options.iter().any(|v| {
+                v.character().index() == character.index()
+                    && v.category() == group.category()
+                    && v.kinds().contains(&BindOptionKind::MustSelect)
+            });
+            if must_select && !others.iter().any(|v| config.is_enabled(v)) {
+                return;
+            }
+        }
+        config.set(character.clone(), id, enabled);
+    }
+
+    /// Returns bind animations of surface `surface_id` drawn for `character` with `config`.
+    pub fn active_overlays(
+        &self,
+        character: &SurfaceTargetCharacterId,
+        surface_id: SurfaceIdType,
+        config: &DressupConfig,
+    ) -> Vec<&BindAnimation> {
+        match self.animations(surface_id) {
+            Some(animations) => animations
+                .iter()
+                .filter(|a| match self.group(character, a.id) {
+                    Some(group) => config.is_enabled(group),
+                    None => config.state(character, a.id).unwrap_or(false),
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl DressupConfig {
+    pub fn new() -> DressupConfig {
+        DressupConfig::default()
+    }
+
+    pub fn set(&mut self, character: SurfaceTargetCharacterId, id: AnimationIdType, enabled: bool) {
+        self.states.insert((character.normalized(), id), enabled);
+    }
+
+    pub fn state(&self, character: &SurfaceTargetCharacterId, id: AnimationIdType) -> Option<bool> {
+        self.states.get(&(character.normalized(), id)).copied()
+    }
+
+    /// Returns the state of `group`, falling back to its `default`.
+    pub fn is_enabled(&self, group: &BindGroup) -> bool {
+        self.state(group.character(), group.id)
+            .unwrap_or(group.default)
+    }
+}
+
+fn bind_animations(
+    braces: &[(&[SurfaceId], &[LineContainer<SurfaceInner>])],
+    surface_id: SurfaceIdType,
+) -> Vec<BindAnimation> {
+    let mut animations: BTreeMap<AnimationIdType, BindAnimation> = BTreeMap::new();
+    let lines = braces
+        .iter()
+        .filter(|(ids, _)| SurfaceId::matches(ids, surface_id))
+        .flat_map(|(_, lines)| lines.iter());
+
+    for line in lines {
+        match line {
+            LineContainer::Body(SurfaceInner::AnimationInterval(v), _) => {
+                animations
+                    .entry(*v.id())
+                    .or_insert_with(|| BindAnimation::new(*v.id(), vec![], vec![]))
+                    .intervals = v.intervals().clone();
+            }
+            LineContainer::Body(SurfaceInner::AnimationPattern(v), _) => {
+                animations
+                    .entry(*v.id())
+                    .or_insert_with(|| BindAnimation::new(*v.id(), vec![], vec![]))
+                    .patterns
+                    .push(v.clone());
+            }
+            _ => {}
+        }
+    }
+
+    animations
+        .into_values()
+        .filter(|a| {
+            a.intervals.contains(&AnimationInterval::Bind)
+                || a.patterns.iter().any(|p| {
+                    matches!(
+                        p.method(),
+                        AnimationPatternDrawMethod::Normal(DrawMethod::Bind, _)
+                    )
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse, parse_bind_descript};
+
+    const SURFACES: &str = r#"charset,UTF-8
+
+surface0
+{
+element0,base,body.png,0,0
+animation20.interval,bind
+animation20.pattern0,overlay,1020,0,0,0
+animation21.interval,bind
+animation21.pattern0,overlay,1021,0,0,0
+animation22.interval,bind
+animation22.pattern0,overlay,1022,0,0,0
+animation1.interval,sometimes
+animation1.pattern0,overlay,100,50,0,0
+}
+
+surface1
+{
+element0,base,body.png,0,0
+}
+
+surface.append0-1
+{
+animation30.interval,never
+animation30.pattern0,bind,1030,0,0,0
+}
+"#;
+
+    const DESCRIPT: &str = r#"charset,UTF-8
+sakura.bindgroup20.name,帽子,麦わら帽子
+sakura.bindgroup20.default,1
+sakura.bindgroup21.name,帽子,リボン
+sakura.bindgroup22.name,アクセサリ,眼鏡
+sakura.bindgroup30.name,アクセサリ,腕輪
+sakura.bindgroup30.default,1
+sakura.bindoption0.group,アクセサリ,multiple
+sakura.bindoption1.group,帽子,mustselect
+"#;
+
+    fn dressup() -> Dressup {
+        Dressup::new(&parse(SURFACES).unwrap(), &parse_bind_descript(DESCRIPT))
+    }
+
+    mod dressup {
+        use super::*;
+
+        #[test]
+        fn success_when_collecting_bind_animations() {
+            let result = dressup();
+            assert_eq!(result.groups().len(), 4);
+            assert_eq!(result.animation_ids(0), vec![20, 21, 22, 30]);
+            assert_eq!(result.animation_ids(1), vec![30]);
+            assert!(result.animation_ids(2).is_empty());
+            assert_eq!(result.animations(0).unwrap()[0].patterns().len(), 1);
+        }
+
+        #[test]
+        fn success_when_large_range() {
+            let case = r#"charset,UTF-8
+surface0-4294967295
+{
+element0,base,body.png,0,0
+}
+surface.append10-
+{
+animation20.interval,bind
+animation20.pattern0,overlay,1020,0,0,0
+}
+surface.append!15,10-20
+{
+animation20.pattern1,overlay,1021,0,0,0
+}
+"#;
+            let result = Dressup::new(&parse(case).unwrap(), &parse_bind_descript(DESCRIPT));
+            assert_eq!(
+                result
+                    .surfaces()
+                    .iter()
+                    .map(|(range, v)| (range.clone(), v[0].patterns().len()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (10..=14, 2),
+                    (15..=15, 1),
+                    (16..=20, 2),
+                    (21..=SurfaceIdType::MAX, 1)
+                ]
+            );
+            assert!(result.animation_ids(9).is_empty());
+            assert_eq!(result.animation_ids(15), vec![20]);
+            assert_eq!(result.animation_ids(SurfaceIdType::MAX), vec![20]);
+        }
+
+        #[test]
+        fn success_when_default_config() {
+            let result = dressup();
+            let config = DressupConfig::new();
+            let ids: Vec<AnimationIdType> = result
+                .active_overlays(&SurfaceTargetCharacterId::Sakura, 0, &config)
+                .iter()
+                .map(|v| *v.id())
+                .collect();
+            assert_eq!(ids, vec![20, 30]);
+            assert!(result
+                .active_overlays(&SurfaceTargetCharacterId::Kero, 0, &config)
+                .is_empty());
+        }
+
+        #[test]
+        fn success_when_selecting_in_single_category() {
+            let result = dressup();
+            let mut config = DressupConfig::new();
+            let sakura = SurfaceTargetCharacterId::Sakura;
+
+            result.select(&mut config, &sakura, 21, true);
+            assert_eq!(config.state(&sakura, 20), Some(false));
+            assert_eq!(config.state(&sakura, 21), Some(true));
+
+            result.select(&mut config, &sakura, 21, false);
+            assert_eq!(config.state(&sakura, 21), Some(true));
+
+            result.select(&mut config, &sakura, 22, true);
+            assert_eq!(config.state(&sakura, 30), None);
+
+            let ids: Vec<AnimationIdType> = result
+                .active_overlays(&sakura, 0, &config)
+                .iter()
+                .map(|v| *v.id())
+                .collect();
+            assert_eq!(ids, vec![21, 22, 30]);
+        }
+
+        #[test]
+        fn success_when_character_written_as_char() {
+            let result = dressup();
+            let mut config = DressupConfig::new();
+            let char0 = SurfaceTargetCharacterId::Char(0);
+
+            result.select(&mut config, &char0, 21, true);
+            assert_eq!(
+                config.state(&SurfaceTargetCharacterId::Sakura, 20),
+                Some(false)
+            );
+            assert!(result.is_multiple(&char0, "アクセサリ"));
+
+            let ids: Vec<AnimationIdType> = result
+                .active_overlays(&char0, 0, &config)
+                .iter()
+                .map(|v| *v.id())
+                .collect();
+            assert_eq!(ids, vec![21, 30]);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Detects the encoding of `surfaces.txt` from its bytes.
+//!
+//! The `charset` line is found without decoding, since it and the comments before it are ASCII
+//! in every charset `surfaces.txt` can declare. A UTF-8 BOM is skipped and decides the encoding.
+//! When no charset is declared, or the bytes contradict the declared one, the encoding is sniffed
+//! between UTF-8 and Shift_JIS.
+
+use std::borrow::Cow;
+
+use encoding_rs::SHIFT_JIS;
+use shell_parser_common_rs::charset::Charset;
+
+use crate::{display::charset_name, parse::declared_charset};
+
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Encoding of bytes decoded by [`decode_detected`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedEncoding {
+    charset: Charset,
+    declared: Option<Charset>,
+    bom: bool,
+}
+
+impl DetectedEncoding {
+    pub fn new(charset: Charset, declared: Option<Charset>, bom: bool) -> DetectedEncoding {
+        DetectedEncoding {
+            charset,
+            declared,
+            bom,
+        }
+    }
+
+    /// Charset the bytes were decoded with.
+    pub fn charset(&self) -> &Charset {
+        &self.charset
+    }
+    /// Charset of the `charset` line, if any.
+    pub fn declared(&self) -> &Option<Charset> {
+        &self.declared
+    }
+    /// Whether the bytes start with a UTF-8 BOM.
+    pub fn bom(&self) -> &bool {
+        &self.bom
+    }
+
+    /// Whether the bytes were decoded with another charset than the declared one.
+    pub fn is_mismatch(&self) -> bool {
+        match &self.declared {
+            Some(v) => normalized(v) != normalized(&self.charset),
+            None => false,
+        }
+    }
+}
+
+/// Decodes `input`, detecting its encoding.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::encoding::decode_detected;
+///
+/// let case = "charset,Shift_JIS\r\n// 通常\r\n".as_bytes();
+/// let (content, encoding) = decode_detected(case).unwrap();
+/// assert_eq!(content, "charset,Shift_JIS\r\n// 通常\r\n");
+/// assert_eq!(encoding.charset(), &Charset::UTF8);
+/// assert_eq!(encoding.declared(), &Some(Charset::ShiftJIS));
+/// assert!(encoding.is_mismatch());
+/// ```
+pub fn decode_detected(input: &[u8]) -> Result<(Cow<'_, str>, DetectedEncoding), String> {
+    let (bom, body) = match input.strip_prefix(UTF8_BOM) {
+        Some(v) => (true, v),
+        None => (false, input),
+    };
+    let declared = declared_charset(body);
+
+    let charset = match &declared {
+        _ if bom => Charset::UTF8,
+        Some(v) if !contradicts(v, body) => v.clone(),
+        _ => match sniff(body) {
+            Some(v) => v,
+            None => {
+                let name = match &declared {
+                    Some(v) => charset_name(v),
+                    None => "UTF-8 or Shift_JIS",
+                };
+                return Err(format!("Encoding failed: to {}", name));
+            }
+        },
+    };
+
+    match charset.decode(body) {
+        Ok(v) => Ok((v, DetectedEncoding::new(charset, declared, bom))),
+        Err(_) => Err(format!("Encoding failed: to {}", charset_name(&charset))),
+    }
+}
+
+/// Whether `input` cannot be in `declared`.
+fn contradicts(declared: &Charset, input: &[u8]) -> bool {
+    declared.decode(input).is_err() || (declared != &Charset::UTF8 && is_multibyte_utf8(input))
+}
+
+/// Guesses the encoding of `input` between UTF-8 and Shift_JIS.
+///
+/// ASCII-only input is Shift_JIS, which the baseware assumes.
+fn sniff(input: &[u8]) -> Option<Charset> {
+    if is_multibyte_utf8(input) {
+        Some(Charset::UTF8)
+    } else if SHIFT_JIS
+        .decode_without_bom_handling_and_without_replacement(input)
+        .is_some()
+    {
+        Some(Charset::ShiftJIS)
+    } else {
+        None
+    }
+}
+
+/// Whether `input` is valid UTF-8 with non-ASCII characters, which is unlikely by chance.
+fn is_multibyte_utf8(input: &[u8]) -> bool {
+    !input.is_ascii() && std::str::from_utf8(input).is_ok()
+}
+
+fn normalized(charset: &Charset) -> &Charset {
+    match charset {
+        Charset::Default => &Charset::ShiftJIS,
+        _ => charset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod decode_detected {
+        use super::*;
+
+        #[test]
+        fn success_when_declared() {
+            let (case, _, _) = SHIFT_JIS.encode("charset,Shift_JIS\r\n// 通常\r\n");
+            let (content, encoding) = decode_detected(&case).unwrap();
+            assert_eq!(content, "charset,Shift_JIS\r\n// 通常\r\n");
+            assert_eq!(
+                encoding,
+                DetectedEncoding::new(Charset::ShiftJIS, Some(Charset::ShiftJIS), false)
+            );
+            assert!(!encoding.is_mismatch());
+
+            let (case, _, _) = SHIFT_JIS.encode("// 通常\r\ncharset,default\r\n");
+            let (_, encoding) = decode_detected(&case).unwrap();
+            assert_eq!(encoding.charset(), &Charset::Default);
+            assert!(!encoding.is_mismatch());
+        }
+
+        #[test]
+        fn success_when_bom() {
+            let case = "\u{FEFF}charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n".as_bytes();
+            let (content, encoding) = decode_detected(case).unwrap();
+            assert_eq!(content, "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n");
+            assert_eq!(
+                encoding,
+                DetectedEncoding::new(Charset::UTF8, Some(Charset::UTF8), true)
+            );
+
+            let case = "\u{FEFF}charset,Shift_JIS\r\n".as_bytes();
+            let (_, encoding) = decode_detected(case).unwrap();
+            assert_eq!(encoding.charset(), &Charset::UTF8);
+            assert!(encoding.is_mismatch());
+        }
+
+        #[test]
+        fn success_when_not_declared() {
+            let case = "// 通常\r\nsurface0\r\n{\r\n}\r\n";
+            let (content, encoding) = decode_detected(case.as_bytes()).unwrap();
+            assert_eq!(content, case);
+            assert_eq!(encoding, DetectedEncoding::new(Charset::UTF8, None, false));
+
+            let (bytes, _, _) = SHIFT_JIS.encode(case);
+            let (content, encoding) = decode_detected(&bytes).unwrap();
+            assert_eq!(content, case);
+            assert_eq!(
+                encoding,
+                DetectedEncoding::new(Charset::ShiftJIS, None, false)
+            );
+
+            let (_, encoding) = decode_detected(b"surface0\r\n{\r\n}\r\n").unwrap();
+            assert_eq!(encoding.charset(), &Charset::ShiftJIS);
+        }
+
+        #[test]
+        fn success_when_mismatch() {
+            let (case, _, _) = SHIFT_JIS.encode("charset,UTF-8\r\n// 通常\r\n");
+            let (content, encoding) = decode_detected(&case).unwrap();
+            assert_eq!(content, "charset,UTF-8\r\n// 通常\r\n");
+            assert_eq!(
+                encoding,
+                DetectedEncoding::new(Charset::ShiftJIS, Some(Charset::UTF8), false)
+            );
+            assert!(encoding.is_mismatch());
+        }
+
+        #[test]
+        fn failed_when_invalid_bytes() {
+            assert!(decode_detected(b"charset,UTF-8\r\n// \xff\xff\r\n").is_err());
+            assert!(decode_detected(b"// \xff\xff\r\n").is_err());
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Finds and reads `surfaces*.txt` of a shell.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use encoding_rs::{Encoding, SHIFT_JIS};
+use shell_parser_common_rs::charset::Charset;
+
+use crate::{
+    ast::ShellSurfaces,
+    display::charset_name,
+    encoding::{decode_detected, DetectedEncoding, UTF8_BOM},
+    parse::parse_bytes,
+};
+
+/// Returns `surfaces*.txt` in `path` sorted by name, or `path` itself when it is a file.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use shell_parser_surfaces_rs::files::surfaces_paths;
+///
+/// let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/surfaces");
+/// let paths = surfaces_paths(&dir).unwrap();
+/// assert_eq!(paths, vec![dir.join("surfaces01.txt")]);
+/// ```
+pub fn surfaces_paths(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        fs::metadata(path)?;
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_file() && is_surfaces_file_name(&entry_path) {
+            paths.push(entry_path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads `path` and decodes it, detecting its encoding by [`decode_detected`].
+///
+/// Write the content back with [`write_surfaces_file`] and the returned encoding to keep the
+/// bytes as they were read.
+pub fn read_surfaces_file(path: &Path) -> Result<(String, DetectedEncoding), String> {
+    let buffer = fs::read(path).map_err(|e| e.to_string())?;
+    decode_detected(&buffer).map(|(v, encoding)| (v.into_owned(), encoding))
+}
+
+/// Reads and parses all `surfaces*.txt` in `path` as one shell.
+///
+/// Braces of later files are appended to the first file.
+pub fn read_shell(path: &Path) -> Result<ShellSurfaces, String> {
+    let files = surfaces_paths(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut merged: Option<ShellSurfaces> = None;
+    for file in files {
+        let buffer = fs::read(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        let (shell_surfaces, _) =
+            parse_bytes(&buffer).map_err(|e| format!("{}: {}", file.display(), e))?;
+        match merged.as_mut() {
+            Some(v) => v
+                .braces_mut()
+                .extend(shell_surfaces.braces().iter().cloned()),
+            None => merged = Some(shell_surfaces),
+        }
+    }
+    merged.ok_or_else(|| format!("{}: no surfaces*.txt is found", path.display()))
+}
+
+/// Encodes `content` with `charset`.
+///
+/// `default` charset is encoded as Shift_JIS.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::files::encode_surfaces;
+///
+/// let result = encode_surfaces("通常", &Charset::ShiftJIS).unwrap();
+/// assert_eq!(result, vec![0x92, 0xca, 0x8f, 0xed]);
+/// ```
+pub fn encode_surfaces(content: &str, charset: &Charset) -> Result<Vec<u8>, String> {
+    let encoding = Encoding::for_label(charset_name(charset).as_bytes()).unwrap_or(SHIFT_JIS);
+    let (result, _, had_errors) = encoding.encode(content);
+    if had_errors {
+        return Err(format!("Encoding failed: to {}", charset_name(charset)));
+    }
+    Ok(result.into_owned())
+}
+
+/// Encodes `content` with the charset of `encoding`, after a UTF-8 BOM if the decoded bytes
+/// had one.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{encoding::decode_detected, files::encode_detected};
+///
+/// let case = "\u{FEFF}// 通常\r\nsurface0\r\n{\r\n}\r\n".as_bytes();
+/// let (content, encoding) = decode_detected(case).unwrap();
+/// assert_eq!(encode_detected(&content, &encoding).unwrap(), case);
+/// ```
+pub fn encode_detected(content: &str, encoding: &DetectedEncoding) -> Result<Vec<u8>, String> {
+    let mut result = Vec::new();
+    if *encoding.bom() {
+        result.extend_from_slice(UTF8_BOM);
+    }
+    result.extend(encode_surfaces(content, encoding.charset())?);
+    Ok(result)
+}
+
+/// Encodes `content` as [`encode_detected`] and writes it to `path`.
+pub fn write_surfaces_file(
+    path: &Path,
+    content: &str,
+    encoding: &DetectedEncoding,
+) -> Result<(), String> {
+    let buffer = encode_detected(content, encoding)?;
+    fs::write(path, buffer).map_err(|e| e.to_string())
+}
+
+fn is_surfaces_file_name(path: &Path) -> bool {
+    match path.file_name().and_then(|v| v.to_str()) {
+        Some(name) => {
+            let name = name.to_ascii_lowercase();
+            name.starts_with("surfaces") && name.ends_with(".txt")
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod surfaces_paths {
+        use super::*;
+
+        #[test]
+        fn success_when_file() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/surfaces/surfaces01.txt");
+            assert_eq!(surfaces_paths(&path).unwrap(), vec![path]);
+        }
+
+        #[test]
+        fn failed_when_not_found() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/not_found");
+            assert!(surfaces_paths(&path).is_err());
+        }
+    }
+
+    mod is_surfaces_file_name {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_name() {
+            assert!(is_surfaces_file_name(Path::new(
+                "shell/master/surfaces.txt"
+            )));
+            assert!(is_surfaces_file_name(Path::new("Surfaces_dress.TXT")));
+        }
+
+        #[test]
+        fn failed_when_invalid_name() {
+            assert!(!is_surfaces_file_name(Path::new("descript.txt")));
+            assert!(!is_surfaces_file_name(Path::new("surfaces.txt.bak")));
+        }
+    }
+
+    mod encode_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = encode_surfaces("charset,UTF-8\r\n通常", &Charset::UTF8).unwrap();
+            assert_eq!(result, "charset,UTF-8\r\n通常".as_bytes());
+        }
+
+        #[test]
+        fn failed_when_unmappable_str() {
+            assert!(encode_surfaces("🍣", &Charset::ShiftJIS).is_err());
+        }
+    }
+
+    mod encode_detected {
+        use encoding_rs::SHIFT_JIS;
+
+        use super::*;
+
+        #[test]
+        fn success_when_bytes_kept() {
+            let (sjis, _, _) = SHIFT_JIS.encode("// 通常\r\nsurface0\r\n{\r\n}\r\n");
+            let utf8 = "// 通常\r\nsurface0\r\n{\r\n}\r\n".as_bytes();
+            let bom = "\u{FEFF}charset,UTF-8\r\n// 通常\r\n".as_bytes();
+            let mismatch = "charset,Shift_JIS\r\n// 通常\r\n".as_bytes();
+            for case in [&sjis[..], utf8, bom, mismatch] {
+                let (content, encoding) = decode_detected(case).unwrap();
+                assert_eq!(encode_detected(&content, &encoding).unwrap(), case);
+            }
+        }
+
+        #[test]
+        fn failed_when_unmappable_str() {
+            let encoding = DetectedEncoding::new(Charset::ShiftJIS, None, false);
+            assert!(encode_detected("🍣", &encoding).is_err());
+        }
+    }
+
+    mod read_surfaces_file {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_file() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/surfaces/surfaces01.txt");
+            let (result, encoding) = read_surfaces_file(&path).unwrap();
+            assert!(result.contains("charset"));
+            assert!(!encoding.is_mismatch());
+        }
+    }
+
+    mod read_shell {
+        use super::*;
+
+        #[test]
+        fn success_when_dir() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/surfaces");
+            assert!(!read_shell(&path).unwrap().braces().is_empty());
+        }
+
+        #[test]
+        fn failed_when_not_found() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/not_found");
+            assert!(read_shell(&path).is_err());
+        }
+    }
+}
@@ -0,0 +1,603 @@
+//! Transforms the AST by value.
+//!
+//! Each method of [`Fold`] calls the free function of the same name by default,
+//! which folds the children of the node and rebuilds it. Override only the methods for nodes of interest.
+//!
+//! # Examples
+//!
+//! ```
+//! use shell_parser_surfaces_rs::{fold::Fold, parse, SurfaceId};
+//!
+//! struct Offset(u32);
+//!
+//! impl Fold for Offset {
+//!     fn fold_surface_id(&mut self, node: SurfaceId) -> SurfaceId {
+//!         match node {
+//!             SurfaceId::Unit(v) => SurfaceId::Unit(v + self.0),
+//!             SurfaceId::Range(s, e) => SurfaceId::Range(s + self.0, e + self.0),
+//!             SurfaceId::RangeFrom(s) => SurfaceId::RangeFrom(s + self.0),
+//!             SurfaceId::All => SurfaceId::All,
+//!             SurfaceId::Not(v) => SurfaceId::Not(Box::new(self.fold_surface_id(*v))),
+//!         }
+//!     }
+//! }
+//!
+//! let case = r#"charset,UTF-8
+//! surface0,!1
+//! {
+//! }
+//! "#;
+//!
+//! let result = Offset(100).fold_shell_surfaces(parse(case).unwrap());
+//! assert!(result.to_string().contains("surface100,!101"));
+//! ```
+
+use std::mem::take;
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AnimationInterval, AnimationOptionKind, AnimationPatternDrawMethod, AnimationPatternProperty,
+    BlockComment, Brace, BraceContainer, CollisionExKind, CommentLine, Descript, DescriptInner,
+    DrawMethod, DrawMethodOnAnimation, GestureKind, LineContainer, ShellSurfaces,
+    ShellSurfacesCursor, ShellSurfacesCursorGesture, SortOrder, Surface, SurfaceAlias,
+    SurfaceAliasInner, SurfaceAnimationCollision, SurfaceAnimationCollisionEx,
+    SurfaceAnimationInterval, SurfaceAnimationOption, SurfaceAnimationPattern, SurfaceAppend,
+    SurfaceCollision, SurfaceCollisionEx, SurfaceElement, SurfaceId, SurfaceInner,
+    SurfaceTargetCharacterId, Tooltip, TooltipInner,
+};
+
+pub trait Fold {
+    fn fold_shell_surfaces(&mut self, node: ShellSurfaces) -> ShellSurfaces {
+        fold_shell_surfaces(self, node)
+    }
+    fn fold_comment_line(&mut self, node: CommentLine) -> CommentLine {
+        fold_comment_line(self, node)
+    }
+    fn fold_block_comment(&mut self, node: BlockComment) -> BlockComment {
+        fold_block_comment(self, node)
+    }
+    fn fold_charset(&mut self, node: Charset) -> Charset {
+        fold_charset(self, node)
+    }
+    fn fold_brace_container(&mut self, node: BraceContainer) -> BraceContainer {
+        fold_brace_container(self, node)
+    }
+    fn fold_brace(&mut self, node: Brace) -> Brace {
+        fold_brace(self, node)
+    }
+    fn fold_descript(&mut self, node: Descript) -> Descript {
+        fold_descript(self, node)
+    }
+    fn fold_descript_inner(&mut self, node: DescriptInner) -> DescriptInner {
+        fold_descript_inner(self, node)
+    }
+    fn fold_sort_order(&mut self, node: SortOrder) -> SortOrder {
+        fold_sort_order(self, node)
+    }
+    fn fold_surface(&mut self, node: Surface) -> Surface {
+        fold_surface(self, node)
+    }
+    fn fold_surface_append(&mut self, node: SurfaceAppend) -> SurfaceAppend {
+        fold_surface_append(self, node)
+    }
+    fn fold_surface_id(&mut self, node: SurfaceId) -> SurfaceId {
+        fold_surface_id(self, node)
+    }
+    fn fold_surface_inner(&mut self, node: SurfaceInner) -> SurfaceInner {
+        fold_surface_inner(self, node)
+    }
+    fn fold_surface_element(&mut self, node: SurfaceElement) -> SurfaceElement {
+        fold_surface_element(self, node)
+    }
+    fn fold_draw_method(&mut self, node: DrawMethod) -> DrawMethod {
+        fold_draw_method(self, node)
+    }
+    fn fold_draw_method_on_animation(
+        &mut self,
+        node: DrawMethodOnAnimation,
+    ) -> DrawMethodOnAnimation {
+        fold_draw_method_on_animation(self, node)
+    }
+    fn fold_surface_animation_interval(
+        &mut self,
+        node: SurfaceAnimationInterval,
+    ) -> SurfaceAnimationInterval {
+        fold_surface_animation_interval(self, node)
+    }
+    fn fold_animation_interval(&mut self, node: AnimationInterval) -> AnimationInterval {
+        fold_animation_interval(self, node)
+    }
+    fn fold_surface_animation_pattern(
+        &mut self,
+        node: SurfaceAnimationPattern,
+    ) -> SurfaceAnimationPattern {
+        fold_surface_animation_pattern(self, node)
+    }
+    fn fold_animation_pattern_draw_method(
+        &mut self,
+        node: AnimationPatternDrawMethod,
+    ) -> AnimationPatternDrawMethod {
+        fold_animation_pattern_draw_method(self, node)
+    }
+    fn fold_animation_pattern_property(
+        &mut self,
+        node: AnimationPatternProperty,
+    ) -> AnimationPatternProperty {
+        fold_animation_pattern_property(self, node)
+    }
+    fn fold_surface_animation_option(
+        &mut self,
+        node: SurfaceAnimationOption,
+    ) -> SurfaceAnimationOption {
+        fold_surface_animation_option(self, node)
+    }
+    fn fold_animation_option_kind(&mut self, node: AnimationOptionKind) -> AnimationOptionKind {
+        fold_animation_option_kind(self, node)
+    }
+    fn fold_surface_animation_collision(
+        &mut self,
+        node: SurfaceAnimationCollision,
+    ) -> SurfaceAnimationCollision {
+        fold_surface_animation_collision(self, node)
+    }
+    fn fold_surface_animation_collision_ex(
+        &mut self,
+        node: SurfaceAnimationCollisionEx,
+    ) -> SurfaceAnimationCollisionEx {
+        fold_surface_animation_collision_ex(self, node)
+    }
+    fn fold_surface_collision(&mut self, node: SurfaceCollision) -> SurfaceCollision {
+        fold_surface_collision(self, node)
+    }
+    fn fold_surface_collision_ex(&mut self, node: SurfaceCollisionEx) -> SurfaceCollisionEx {
+        fold_surface_collision_ex(self, node)
+    }
+    fn fold_collision_ex_kind(&mut self, node: CollisionExKind) -> CollisionExKind {
+        fold_collision_ex_kind(self, node)
+    }
+    fn fold_surface_alias(&mut self, node: SurfaceAlias) -> SurfaceAlias {
+        fold_surface_alias(self, node)
+    }
+    fn fold_surface_target_character_id(
+        &mut self,
+        node: SurfaceTargetCharacterId,
+    ) -> SurfaceTargetCharacterId {
+        fold_surface_target_character_id(self, node)
+    }
+    fn fold_surface_alias_inner(&mut self, node: SurfaceAliasInner) -> SurfaceAliasInner {
+        fold_surface_alias_inner(self, node)
+    }
+    fn fold_shell_surfaces_cursor(&mut self, node: ShellSurfacesCursor) -> ShellSurfacesCursor {
+        fold_shell_surfaces_cursor(self, node)
+    }
+    fn fold_shell_surfaces_cursor_gesture(
+        &mut self,
+        node: ShellSurfacesCursorGesture,
+    ) -> ShellSurfacesCursorGesture {
+        fold_shell_surfaces_cursor_gesture(self, node)
+    }
+    fn fold_gesture_kind(&mut self, node: GestureKind) -> GestureKind {
+        fold_gesture_kind(self, node)
+    }
+    fn fold_tooltip(&mut self, node: Tooltip) -> Tooltip {
+        fold_tooltip(self, node)
+    }
+    fn fold_tooltip_inner(&mut self, node: TooltipInner) -> TooltipInner {
+        fold_tooltip_inner(self, node)
+    }
+}
+
+fn fold_comments<F: Fold + ?Sized>(f: &mut F, comments: Vec<CommentLine>) -> Vec<CommentLine> {
+    comments
+        .into_iter()
+        .map(|c| f.fold_comment_line(c))
+        .collect()
+}
+
+fn fold_lines<F, T, G>(f: &mut F, lines: Vec<LineContainer<T>>, mut g: G) -> Vec<LineContainer<T>>
+where
+    F: Fold + ?Sized,
+    T: Clone + PartialEq,
+    G: FnMut(&mut F, T) -> T,
+{
+    lines
+        .into_iter()
+        .map(|line| match line {
+            LineContainer::Comment(c) => LineContainer::Comment(f.fold_comment_line(c)),
+            LineContainer::BlockComment(c) => LineContainer::BlockComment(f.fold_block_comment(c)),
+            LineContainer::Body(b, mut format) => {
+                // The original text is kept only while the body is unchanged.
+                let before = format.raw().is_some().then(|| b.clone());
+                let b = g(f, b);
+                if before.is_some_and(|v| v != b) {
+                    *format.raw_mut() = None;
+                }
+                LineContainer::Body(b, format)
+            }
+        })
+        .collect()
+}
+
+pub fn fold_shell_surfaces<F: Fold + ?Sized>(f: &mut F, mut node: ShellSurfaces) -> ShellSurfaces {
+    let header_comments = fold_comments(f, take(node.header_comments_mut()));
+    let charset = f.fold_charset(node.charset().clone());
+    let braces = take(node.braces_mut())
+        .into_iter()
+        .map(|b| f.fold_brace_container(b))
+        .collect();
+    let footer_comments = fold_comments(f, take(node.footer_comments_mut()));
+    let mut result = ShellSurfaces::new(header_comments, charset, braces, footer_comments);
+    *result.charset_declared_mut() = *node.charset_declared();
+    result
+}
+
+pub fn fold_comment_line<F: Fold + ?Sized>(_f: &mut F, node: CommentLine) -> CommentLine {
+    node
+}
+
+pub fn fold_block_comment<F: Fold + ?Sized>(_f: &mut F, node: BlockComment) -> BlockComment {
+    node
+}
+
+pub fn fold_charset<F: Fold + ?Sized>(_f: &mut F, node: Charset) -> Charset {
+    node
+}
+
+pub fn fold_brace_container<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: BraceContainer,
+) -> BraceContainer {
+    let header_comments = fold_comments(f, take(node.header_comments_mut()));
+    let body = f.fold_brace(node.body().clone());
+    let mut result = BraceContainer::new(header_comments, body);
+    *result.layout_mut() = *node.layout();
+    result
+}
+
+pub fn fold_brace<F: Fold + ?Sized>(f: &mut F, node: Brace) -> Brace {
+    match node {
+        Brace::Descript(b) => Brace::Descript(f.fold_descript(b)),
+        Brace::Surface(b) => Brace::Surface(f.fold_surface(b)),
+        Brace::SurfaceAppend(b) => Brace::SurfaceAppend(f.fold_surface_append(b)),
+        Brace::SurfaceAlias(b) => Brace::SurfaceAlias(f.fold_surface_alias(b)),
+        Brace::Cursor(b) => Brace::Cursor(f.fold_shell_surfaces_cursor(b)),
+        Brace::Tooltip(b) => Brace::Tooltip(f.fold_tooltip(b)),
+        Brace::BlockComment(b) => Brace::BlockComment(f.fold_block_comment(b)),
+    }
+}
+
+pub fn fold_descript<F: Fold + ?Sized>(f: &mut F, mut node: Descript) -> Descript {
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| f.fold_descript_inner(b));
+    Descript::new(lines)
+}
+
+pub fn fold_descript_inner<F: Fold + ?Sized>(f: &mut F, node: DescriptInner) -> DescriptInner {
+    match node {
+        DescriptInner::CollistionSort(o) => DescriptInner::CollistionSort(f.fold_sort_order(o)),
+        DescriptInner::AnimationSort(o) => DescriptInner::AnimationSort(f.fold_sort_order(o)),
+        v => v,
+    }
+}
+
+pub fn fold_sort_order<F: Fold + ?Sized>(_f: &mut F, node: SortOrder) -> SortOrder {
+    node
+}
+
+pub fn fold_surface<F: Fold + ?Sized>(f: &mut F, mut node: Surface) -> Surface {
+    let ids = take(node.ids_mut())
+        .into_iter()
+        .map(|id| f.fold_surface_id(id))
+        .collect();
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| f.fold_surface_inner(b));
+    Surface::new(ids, lines)
+}
+
+pub fn fold_surface_append<F: Fold + ?Sized>(f: &mut F, mut node: SurfaceAppend) -> SurfaceAppend {
+    let ids = take(node.ids_mut())
+        .into_iter()
+        .map(|id| f.fold_surface_id(id))
+        .collect();
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| f.fold_surface_inner(b));
+    SurfaceAppend::new(ids, lines)
+}
+
+pub fn fold_surface_id<F: Fold + ?Sized>(f: &mut F, node: SurfaceId) -> SurfaceId {
+    match node {
+        SurfaceId::Not(inner) => SurfaceId::Not(Box::new(f.fold_surface_id(*inner))),
+        v => v,
+    }
+}
+
+pub fn fold_surface_inner<F: Fold + ?Sized>(f: &mut F, node: SurfaceInner) -> SurfaceInner {
+    match node {
+        SurfaceInner::Element(b) => SurfaceInner::Element(f.fold_surface_element(b)),
+        SurfaceInner::AnimationInterval(b) => {
+            SurfaceInner::AnimationInterval(f.fold_surface_animation_interval(b))
+        }
+        SurfaceInner::AnimationPattern(b) => {
+            SurfaceInner::AnimationPattern(f.fold_surface_animation_pattern(b))
+        }
+        SurfaceInner::AnimationOption(b) => {
+            SurfaceInner::AnimationOption(f.fold_surface_animation_option(b))
+        }
+        SurfaceInner::AnimationCollision(b) => {
+            SurfaceInner::AnimationCollision(f.fold_surface_animation_collision(b))
+        }
+        SurfaceInner::AnimationCollisionEx(b) => {
+            SurfaceInner::AnimationCollisionEx(f.fold_surface_animation_collision_ex(b))
+        }
+        SurfaceInner::Collision(b) => SurfaceInner::Collision(f.fold_surface_collision(b)),
+        SurfaceInner::CollisionEx(b) => SurfaceInner::CollisionEx(f.fold_surface_collision_ex(b)),
+        SurfaceInner::CharacterBalloonOffsetX(id, v) => {
+            SurfaceInner::character_balloon_offset_x(f.fold_surface_target_character_id(id), v)
+        }
+        SurfaceInner::CharacterBalloonOffsetY(id, v) => {
+            SurfaceInner::character_balloon_offset_y(f.fold_surface_target_character_id(id), v)
+        }
+        v @ (SurfaceInner::SakuraBalloonOffsetX(_)
+        | SurfaceInner::SakuraBalloonOffsetY(_)
+        | SurfaceInner::KeroBalloonOffsetX(_)
+        | SurfaceInner::KeroBalloonOffsetY(_)
+        | SurfaceInner::BalloonOffsetX(_)
+        | SurfaceInner::BalloonOffsetY(_)
+        | SurfaceInner::PointCenterX(_)
+        | SurfaceInner::PointCenterY(_)
+        | SurfaceInner::PointKinokoCenterX(_)
+        | SurfaceInner::PointKinokoCenterY(_)
+        | SurfaceInner::PointBaseposX(_)
+        | SurfaceInner::PointBaseposY(_)) => v,
+    }
+}
+
+pub fn fold_surface_element<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: SurfaceElement,
+) -> SurfaceElement {
+    *node.method_mut() = f.fold_draw_method(node.method().clone());
+    node
+}
+
+pub fn fold_draw_method<F: Fold + ?Sized>(_f: &mut F, node: DrawMethod) -> DrawMethod {
+    node
+}
+
+pub fn fold_draw_method_on_animation<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: DrawMethodOnAnimation,
+) -> DrawMethodOnAnimation {
+    node
+}
+
+pub fn fold_surface_animation_interval<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: SurfaceAnimationInterval,
+) -> SurfaceAnimationInterval {
+    let intervals = take(node.intervals_mut())
+        .into_iter()
+        .map(|i| f.fold_animation_interval(i))
+        .collect();
+    SurfaceAnimationInterval::new(*node.id(), intervals)
+}
+
+pub fn fold_animation_interval<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: AnimationInterval,
+) -> AnimationInterval {
+    node
+}
+
+pub fn fold_surface_animation_pattern<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: SurfaceAnimationPattern,
+) -> SurfaceAnimationPattern {
+    *node.method_mut() = f.fold_animation_pattern_draw_method(node.method().clone());
+    node
+}
+
+pub fn fold_animation_pattern_draw_method<F: Fold + ?Sized>(
+    f: &mut F,
+    node: AnimationPatternDrawMethod,
+) -> AnimationPatternDrawMethod {
+    match node {
+        AnimationPatternDrawMethod::Normal(m, p) => AnimationPatternDrawMethod::Normal(
+            f.fold_draw_method(m),
+            f.fold_animation_pattern_property(p),
+        ),
+        AnimationPatternDrawMethod::Animation(m) => {
+            AnimationPatternDrawMethod::Animation(f.fold_draw_method_on_animation(m))
+        }
+    }
+}
+
+pub fn fold_animation_pattern_property<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: AnimationPatternProperty,
+) -> AnimationPatternProperty {
+    node
+}
+
+pub fn fold_surface_animation_option<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: SurfaceAnimationOption,
+) -> SurfaceAnimationOption {
+    let options = take(node.options_mut())
+        .into_iter()
+        .map(|o| f.fold_animation_option_kind(o))
+        .collect();
+    SurfaceAnimationOption::new(*node.id(), options)
+}
+
+pub fn fold_animation_option_kind<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: AnimationOptionKind,
+) -> AnimationOptionKind {
+    node
+}
+
+pub fn fold_surface_animation_collision<F: Fold + ?Sized>(
+    f: &mut F,
+    node: SurfaceAnimationCollision,
+) -> SurfaceAnimationCollision {
+    let collision = f.fold_surface_collision(node.collision().clone());
+    SurfaceAnimationCollision::new(*node.id(), collision)
+}
+
+pub fn fold_surface_animation_collision_ex<F: Fold + ?Sized>(
+    f: &mut F,
+    node: SurfaceAnimationCollisionEx,
+) -> SurfaceAnimationCollisionEx {
+    let collision = f.fold_surface_collision_ex(node.collision().clone());
+    SurfaceAnimationCollisionEx::new(*node.id(), collision)
+}
+
+pub fn fold_surface_collision<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: SurfaceCollision,
+) -> SurfaceCollision {
+    node
+}
+
+pub fn fold_surface_collision_ex<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: SurfaceCollisionEx,
+) -> SurfaceCollisionEx {
+    *node.kind_mut() = f.fold_collision_ex_kind(node.kind().clone());
+    node
+}
+
+pub fn fold_collision_ex_kind<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: CollisionExKind,
+) -> CollisionExKind {
+    node
+}
+
+pub fn fold_surface_alias<F: Fold + ?Sized>(f: &mut F, mut node: SurfaceAlias) -> SurfaceAlias {
+    let id = f.fold_surface_target_character_id(node.id().clone());
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| {
+        f.fold_surface_alias_inner(b)
+    });
+    SurfaceAlias::new(id, lines)
+}
+
+pub fn fold_surface_target_character_id<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: SurfaceTargetCharacterId,
+) -> SurfaceTargetCharacterId {
+    node
+}
+
+pub fn fold_surface_alias_inner<F: Fold + ?Sized>(
+    _f: &mut F,
+    node: SurfaceAliasInner,
+) -> SurfaceAliasInner {
+    node
+}
+
+pub fn fold_shell_surfaces_cursor<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: ShellSurfacesCursor,
+) -> ShellSurfacesCursor {
+    let id = f.fold_surface_target_character_id(node.id().clone());
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| {
+        f.fold_shell_surfaces_cursor_gesture(b)
+    });
+    ShellSurfacesCursor::new(id, lines)
+}
+
+pub fn fold_shell_surfaces_cursor_gesture<F: Fold + ?Sized>(
+    f: &mut F,
+    mut node: ShellSurfacesCursorGesture,
+) -> ShellSurfacesCursorGesture {
+    *node.kind_mut() = f.fold_gesture_kind(node.kind().clone());
+    node
+}
+
+pub fn fold_gesture_kind<F: Fold + ?Sized>(_f: &mut F, node: GestureKind) -> GestureKind {
+    node
+}
+
+pub fn fold_tooltip<F: Fold + ?Sized>(f: &mut F, mut node: Tooltip) -> Tooltip {
+    let id = f.fold_surface_target_character_id(node.id().clone());
+    let lines = fold_lines(f, take(node.lines_mut()), |f, b| f.fold_tooltip_inner(b));
+    Tooltip::new(id, lines)
+}
+
+pub fn fold_tooltip_inner<F: Fold + ?Sized>(_f: &mut F, node: TooltipInner) -> TooltipInner {
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    struct Upgrade;
+
+    impl Fold for Upgrade {
+        fn fold_draw_method(&mut self, node: DrawMethod) -> DrawMethod {
+            match node {
+                DrawMethod::Overlayfast => DrawMethod::Overlay,
+                v => v,
+            }
+        }
+        fn fold_surface_target_character_id(
+            &mut self,
+            node: SurfaceTargetCharacterId,
+        ) -> SurfaceTargetCharacterId {
+            match node {
+                SurfaceTargetCharacterId::Sakura => SurfaceTargetCharacterId::Char(0),
+                SurfaceTargetCharacterId::Char(1) => SurfaceTargetCharacterId::Kero,
+                v => v,
+            }
+        }
+        fn fold_comment_line(&mut self, node: CommentLine) -> CommentLine {
+            CommentLine::new(node.body().trim_end().to_string())
+        }
+    }
+
+    mod fold_shell_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_folding_every_node() {
+            let case = r#"charset,UTF-8
+surface0
+{
+element0,overlayfast,body0.png,0,0
+animation0.pattern0,overlayfast,101,100,168,67
+char1.balloon.offsetx,10
+// comment
+}
+sakura.tooltips
+{
+Bust,怒ります。
+}
+"#;
+            let expected = r#"charset,UTF-8
+surface0
+{
+element0,overlay,body0.png,0,0
+animation0.pattern0,overlay,101,100,168,67
+kero.balloon.offsetx,10
+// comment
+}
+char0.tooltips
+{
+Bust,怒ります。
+}
+"#;
+            let result = Upgrade.fold_shell_surfaces(parse(case).unwrap());
+            assert_eq!(result, parse(expected).unwrap());
+        }
+
+        #[test]
+        fn success_when_folding_spaced_line() {
+            let case = "surface0\r\n{\r\nelement0, overlayfast, body0.png, 0, 0 // body\r\nelement1, overlay, face0.png, 0, 0\r\n}\r\n";
+            let expected = "surface0\r\n{\r\nelement0,overlay,body0.png,0,0 // body\r\nelement1, overlay, face0.png, 0, 0\r\n}\r\n";
+            let result = Upgrade.fold_shell_surfaces(parse(case).unwrap());
+            assert_eq!(result.to_string(), expected);
+        }
+    }
+}
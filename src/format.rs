@@ -0,0 +1,365 @@
+//! Rewrites [`ShellSurfaces`] into a canonical layout.
+//!
+//! - Animations are written in `animationN.` syntax (v1).
+//! - Lines inside braces are indented, and trailing spaces of comments are removed,
+//!   including comments after a line.
+//! - Spaces around commas are removed.
+//! - Blank lines are collapsed; one blank line is put before each brace, except a brace at the
+//!   top of the text.
+//! - Comments are kept in place. Lines of a `/* */` comment after the first are written as is.
+//! - `{` and `}` are put on their own lines, unless [`FormatOptions::keep_brace_layout`] is set.
+
+use std::collections::HashSet;
+
+use nom::Err;
+use shell_parser_common_rs::ShellParseError;
+
+use crate::{
+    ast::{BlockComment, Brace, BraceLayout, CommentLine, LineContainer, ShellSurfaces, SurfaceId},
+    display::{fits_one_line, NEWLINE},
+    layout::{brace_lines_len, first_line_offset, leading_lines_len, numbered},
+    parse::parse,
+};
+
+/// Line ending written by [`format_surfaces`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newline {
+    CrLf,
+    Lf,
+}
+
+impl Newline {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Newline::CrLf => "\r\n",
+            Newline::Lf => "\n",
+        }
+    }
+}
+
+/// Options of [`format_surfaces`].
+///
+/// Defaults are a tab for indentation, `\r\n` for line endings, and the original brace order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    indent: String,
+    newline: Newline,
+    sort_braces: bool,
+    keep_brace_layout: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: "\t".to_string(),
+            newline: Newline::CrLf,
+            sort_braces: false,
+            keep_brace_layout: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Sets the indentation of lines inside braces.
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = indent.to_string();
+        self
+    }
+
+    pub fn newline(mut self, newline: Newline) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    /// Sorts braces by kind (`descript`, `surface`, `surface.append`, aliases, cursors, tooltips),
+    /// then by the first surface id or character. Top-level `/* */` comments go last.
+    pub fn sort_braces(mut self, sort_braces: bool) -> Self {
+        self.sort_braces = sort_braces;
+        self
+    }
+
+    /// Keeps `surface0 {` and one-line braces as they are written, with a space before `{`.
+    /// A one-line brace that no longer fits on one line is written as `surface0 {`.
+    pub fn keep_brace_layout(mut self, keep_brace_layout: bool) -> Self {
+        self.keep_brace_layout = keep_brace_layout;
+        self
+    }
+}
+
+/// Formats `input` as `surfaces.txt`.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::format::{format_str, FormatOptions, Newline};
+///
+/// let case = "charset,UTF-8\r\n\r\n\r\nsurface0\r\n{\r\n\r\n0pattern0,100,5,overlay,0,0\r\n}\r\n";
+/// let options = FormatOptions::default().indent("  ").newline(Newline::Lf);
+///
+/// let result = format_str(case, &options).unwrap();
+/// assert_eq!(
+///     result,
+///     "charset,UTF-8\n\nsurface0\n{\n  animation0.pattern0,overlay,100,50,0,0\n}\n"
+/// );
+/// ```
+pub fn format_str(input: &str, options: &FormatOptions) -> Result<String, Err<ShellParseError>> {
+    parse(input).map(|v| format_surfaces(&v, options))
+}
+
+/// Formats a [`ShellSurfaces`] as `surfaces.txt`.
+pub fn format_surfaces(shell_surfaces: &ShellSurfaces, options: &FormatOptions) -> String {
+    let normalized = normalize(shell_surfaces, options);
+    let indented = indented_lines(&normalized);
+
+    let mut result = String::new();
+    for (i, line) in normalized.to_string().split(NEWLINE).enumerate() {
+        if indented.contains(&i) && !line.is_empty() {
+            result.push_str(&options.indent);
+        }
+        result.push_str(line);
+        result.push_str(options.newline.as_str());
+    }
+    // `split` yields an empty string after the last newline.
+    result.truncate(result.len() - options.newline.as_str().len());
+    result
+}
+
+fn normalize(shell_surfaces: &ShellSurfaces, options: &FormatOptions) -> ShellSurfaces {
+    let mut result = shell_surfaces.clone();
+
+    normalize_comments(result.header_comments_mut());
+    for brace in result.braces_mut() {
+        normalize_comments(brace.header_comments_mut());
+        let fits_one_line = match brace.body_mut() {
+            Brace::Descript(v) => normalize_lines(v.lines_mut()),
+            Brace::Surface(v) => normalize_lines(v.lines_mut()),
+            Brace::SurfaceAppend(v) => normalize_lines(v.lines_mut()),
+            Brace::SurfaceAlias(v) => normalize_lines(v.lines_mut()),
+            Brace::Cursor(v) => normalize_lines(v.lines_mut()),
+            Brace::Tooltip(v) => normalize_lines(v.lines_mut()),
+            Brace::BlockComment(v) => {
+                normalize_block_comment(v);
+                true
+            }
+        };
+        let layout = brace.layout_mut();
+        if !options.keep_brace_layout {
+            *layout = BraceLayout::OwnLines;
+        } else if *layout == BraceLayout::OneLine && !fits_one_line {
+            *layout = BraceLayout::SameLine;
+        }
+    }
+    normalize_comments(result.footer_comments_mut());
+    if !result.footer_comments().is_empty() {
+        result
+            .footer_comments_mut()
+            .insert(0, CommentLine::new(String::new()));
+    }
+
+    if options.sort_braces {
+        result.braces_mut().sort_by_key(|v| brace_order(v.body()));
+    }
+    // Nothing is written before the first brace without a `charset` line and header comments.
+    let leading = *result.charset_declared() || !result.header_comments().is_empty();
+    for (i, brace) in result.braces_mut().iter_mut().enumerate() {
+        if i > 0 || leading {
+            brace
+                .header_comments_mut()
+                .insert(0, CommentLine::new(String::new()));
+        }
+    }
+    result
+}
+
+fn is_blank(comment: &CommentLine) -> bool {
+    comment.body().is_empty()
+}
+
+/// Trims comments, collapses blank lines, and removes blank lines at both ends.
+fn normalize_comments(comments: &mut Vec<CommentLine>) {
+    for c in comments.iter_mut() {
+        let trimmed = c.body().trim().to_string();
+        *c.body_mut() = trimmed;
+    }
+    comments.dedup_by(|a, b| is_blank(a) && is_blank(b));
+    while matches!(comments.first(), Some(c) if is_blank(c)) {
+        comments.remove(0);
+    }
+    while matches!(comments.last(), Some(c) if is_blank(c)) {
+        comments.pop();
+    }
+}
+
+/// Trims comments, collapses blank lines, and returns whether the lines fit in a one-line brace.
+fn normalize_lines<T>(lines: &mut Vec<LineContainer<T>>) -> bool {
+    let blank = |line: &LineContainer<T>| matches!(line, LineContainer::Comment(c) if is_blank(c));
+
+    for line in lines.iter_mut() {
+        match line {
+            LineContainer::Comment(c) => {
+                let trimmed = c.body().trim().to_string();
+                *c.body_mut() = trimmed;
+            }
+            LineContainer::BlockComment(v) => normalize_block_comment(v),
+            LineContainer::Body(_, format) => {
+                *format.raw_mut() = None;
+                if let Some(c) = format.comment_mut() {
+                    *c = c.trim_end().to_string();
+                }
+            }
+        }
+    }
+    lines.dedup_by(|a, b| blank(a) && blank(b));
+    while lines.first().is_some_and(blank) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(blank) {
+        lines.pop();
+    }
+    fits_one_line(lines)
+}
+
+/// Returns 0-based numbers of the lines written inside braces, except one-line braces.
+fn indented_lines(shell_surfaces: &ShellSurfaces) -> HashSet<usize> {
+    fn starts<T>(result: &mut HashSet<usize>, lines: &[LineContainer<T>], first_line: usize) {
+        result.extend(numbered(lines, first_line).map(|(line, _)| line));
+    }
+
+    let mut result = HashSet::new();
+    let mut line = leading_lines_len(shell_surfaces);
+    for brace in shell_surfaces.braces() {
+        let name_line = line + brace.header_comments().len();
+        line = name_line + brace_lines_len(brace);
+        if *brace.layout() == BraceLayout::OneLine {
+            continue;
+        }
+        let first_line = name_line + first_line_offset(brace.layout());
+        match brace.body() {
+            Brace::Descript(v) => starts(&mut result, v.lines(), first_line),
+            Brace::Surface(v) => starts(&mut result, v.lines(), first_line),
+            Brace::SurfaceAppend(v) => starts(&mut result, v.lines(), first_line),
+            Brace::SurfaceAlias(v) => starts(&mut result, v.lines(), first_line),
+            Brace::Cursor(v) => starts(&mut result, v.lines(), first_line),
+            Brace::Tooltip(v) => starts(&mut result, v.lines(), first_line),
+            Brace::BlockComment(_) => {}
+        }
+    }
+    result
+}
+
+/// Writes newlines of the comment as [`NEWLINE`], so that [`format_surfaces`] can find its lines.
+fn normalize_block_comment(comment: &mut BlockComment) {
+    let normalized = comment
+        .body()
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', NEWLINE);
+    *comment.body_mut() = normalized;
+}
+
+fn brace_order(brace: &Brace) -> (u8, u64) {
+    match brace {
+        Brace::Descript(_) => (0, 0),
+        Brace::Surface(v) => (1, first_surface_id(v.ids())),
+        Brace::SurfaceAppend(v) => (2, first_surface_id(v.ids())),
+        Brace::SurfaceAlias(v) => (3, v.id().index() as u64),
+        Brace::Cursor(v) => (4, v.id().index() as u64),
+        Brace::Tooltip(v) => (5, v.id().index() as u64),
+        Brace::BlockComment(_) => (6, 0),
+    }
+}
+
+fn first_surface_id(ids: &[SurfaceId]) -> u64 {
+    ids.iter()
+        .filter_map(|v| match v {
+            SurfaceId::Unit(id) | SurfaceId::Range(id, _) | SurfaceId::RangeFrom(id) => {
+                Some(*id as u64)
+            }
+            SurfaceId::All => Some(0),
+            SurfaceId::Not(_) => None,
+        })
+        .min()
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod format_str {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "// header  \n\ncharset,Shift_JIS\n\n\n// surface\n\n\nsurface0\n  {\n\n    element0, overlay,body0.png ,0,0 // base  \n\n\n  // face   \n0interval,sometimes\n\n}\nsakura.surface.alias\n{\n通常,[0]\n}\n\n\n// footer\n\n";
+            let expected = "// header\r\ncharset,Shift_JIS\r\n\r\n// surface\r\nsurface0\r\n{\r\n\telement0,overlay,body0.png,0,0 // base\r\n\r\n\t// face\r\n\tanimation0.interval,sometimes\r\n}\r\n\r\nsakura.surface.alias\r\n{\r\n\t通常,[0]\r\n}\r\n\r\n// footer\r\n";
+            let result = format_str(case, &FormatOptions::default()).unwrap();
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn success_when_already_formatted() {
+            let case = "charset,UTF-8\n\ndescript\n{\n  version,1\n}\n\nsurface0\n{\n  // comment\n\n  collision0,0,0,10,10,Head\n}\n";
+            let options = FormatOptions::default().indent("  ").newline(Newline::Lf);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, case);
+            assert_eq!(format_str(&result, &options).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_sorting_braces() {
+            let case = "charset,UTF-8\nkero.tooltips\n{\n}\nsurface.append0\n{\n}\nsurface10\n{\n}\n// first\nsurface2,!5\n{\n}\ndescript\n{\n}\n";
+            let expected = "charset,UTF-8\n\ndescript\n{\n}\n\n// first\nsurface2,!5\n{\n}\n\nsurface10\n{\n}\n\nsurface.append0\n{\n}\n\nkero.tooltips\n{\n}\n";
+            let options = FormatOptions::default()
+                .newline(Newline::Lf)
+                .sort_braces(true);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn success_when_block_comments() {
+            let case = "charset,UTF-8\n/* surface1\r\n{\r\n}\r\n*/\nsurface0\n{\n  /* a\n    b */\n/**/\n}\n";
+            let expected = "charset,UTF-8\n\n/* surface1\n{\n}\n*/\n\nsurface0\n{\n\t/* a\n    b */\n\t/**/\n}\n";
+            let options = FormatOptions::default().newline(Newline::Lf);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, expected);
+            assert_eq!(format_str(&result, &options).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_brace_layouts() {
+            let case = "charset,UTF-8\nsurface0{\nelement0,base,a.png,0,0\n}\nsurface5 { element0 , base,b.png,0,0 }\n";
+            let options = FormatOptions::default().newline(Newline::Lf);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, "charset,UTF-8\n\nsurface0\n{\n\telement0,base,a.png,0,0\n}\n\nsurface5\n{\n\telement0,base,b.png,0,0\n}\n");
+
+            let options = options.keep_brace_layout(true);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, "charset,UTF-8\n\nsurface0 {\n\telement0,base,a.png,0,0\n}\n\nsurface5 { element0,base,b.png,0,0 }\n");
+            assert_eq!(format_str(&result, &options).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_no_charset() {
+            let case = "\n\nsurface0\n{\nelement0,base,a.png,0,0\n}\n// surface1\nsurface1\n{\n}\n";
+            let options = FormatOptions::default().newline(Newline::Lf);
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(
+                result,
+                "surface0\n{\n\telement0,base,a.png,0,0\n}\n\n// surface1\nsurface1\n{\n}\n"
+            );
+            assert_eq!(format_str(&result, &options).unwrap(), result);
+
+            let case = "// header\nsurface0\n{\n}\n";
+            let result = format_str(case, &options).unwrap();
+            assert_eq!(result, case);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "surface0\n{\n";
+            assert!(format_str(case, &FormatOptions::default()).is_err());
+        }
+    }
+}
@@ -0,0 +1,921 @@
+//! Editor features of `surfaces.txt`: document symbols, hover, go-to-definition and completion.
+//!
+//! Positions are 0-based, and `character` counts UTF-16 code units as in the Language Server
+//! Protocol. Features which need the whole file return nothing while it cannot be parsed.
+
+use crate::{
+    ast::{
+        AnimationPatternDrawMethod, Brace, BraceContainer, LineContainer, ShellSurfaces,
+        SurfaceIdPointerType, SurfaceIdType, SurfaceInner,
+    },
+    layout::{body_range, brace_lines_len, first_line_offset, leading_lines_len, numbered},
+    parse::{parse, split_lines},
+    resolve::resolve_surface,
+};
+
+/// A position in a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    line: usize,
+    character: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, character: usize) -> Position {
+        Position { line, character }
+    }
+
+    /// 0-based line number.
+    pub fn line(&self) -> &usize {
+        &self.line
+    }
+    /// 0-based offset in UTF-16 code units.
+    pub fn character(&self) -> &usize {
+        &self.character
+    }
+}
+
+/// A range in a text. `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    start: Position,
+    end: Position,
+}
+
+impl Range {
+    pub fn new(start: Position, end: Position) -> Range {
+        Range { start, end }
+    }
+
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+    pub fn end(&self) -> &Position {
+        &self.end
+    }
+}
+
+/// Kind of a [`DocumentSymbol`], one for each kind of brace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Descript,
+    Surface,
+    SurfaceAppend,
+    SurfaceAlias,
+    Cursor,
+    Tooltip,
+}
+
+/// A brace in a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentSymbol {
+    name: String,
+    kind: SymbolKind,
+    range: Range,
+    selection_range: Range,
+}
+
+impl DocumentSymbol {
+    pub fn new(
+        name: String,
+        kind: SymbolKind,
+        range: Range,
+        selection_range: Range,
+    ) -> DocumentSymbol {
+        DocumentSymbol {
+            name,
+            kind,
+            range,
+            selection_range,
+        }
+    }
+
+    /// Name line of the brace (e.g. `surface0-3`).
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn kind(&self) -> &SymbolKind {
+        &self.kind
+    }
+    /// From the name line to the closing `}`.
+    pub fn range(&self) -> &Range {
+        &self.range
+    }
+    /// The name line.
+    pub fn selection_range(&self) -> &Range {
+        &self.selection_range
+    }
+}
+
+/// Result of [`hover`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hover {
+    range: Range,
+    contents: String,
+}
+
+impl Hover {
+    pub fn new(range: Range, contents: String) -> Hover {
+        Hover { range, contents }
+    }
+
+    /// The hovered token.
+    pub fn range(&self) -> &Range {
+        &self.range
+    }
+    /// Markdown text.
+    pub fn contents(&self) -> &String {
+        &self.contents
+    }
+}
+
+/// Kind of a keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordKind {
+    /// A line of a brace (e.g. `element`).
+    Line,
+    /// A method of `element` and `pattern` lines.
+    DrawMethod,
+    /// A method of `pattern` lines controlling other animations.
+    AnimationMethod,
+    AnimationInterval,
+    AnimationOption,
+    CollisionExKind,
+}
+
+impl KeywordKind {
+    fn description(&self) -> &'static str {
+        match self {
+            KeywordKind::Line => "line",
+            KeywordKind::DrawMethod => "draw method",
+            KeywordKind::AnimationMethod => "animation method",
+            KeywordKind::AnimationInterval => "animation interval",
+            KeywordKind::AnimationOption => "animation option",
+            KeywordKind::CollisionExKind => "collisionex kind",
+        }
+    }
+}
+
+/// A completion candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionItem {
+    label: String,
+    kind: KeywordKind,
+    detail: String,
+}
+
+impl CompletionItem {
+    pub fn new(label: String, kind: KeywordKind, detail: String) -> CompletionItem {
+        CompletionItem {
+            label,
+            kind,
+            detail,
+        }
+    }
+
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+    pub fn kind(&self) -> &KeywordKind {
+        &self.kind
+    }
+    pub fn detail(&self) -> &String {
+        &self.detail
+    }
+}
+
+/// Keywords with a short explanation, in the order of the parser.
+///
+/// Line keywords are written without ids (e.g. `animation.pattern` for `animation0.pattern1`).
+const KEYWORDS: &[(&str, KeywordKind, &str)] = &[
+    (
+        "element",
+        KeywordKind::Line,
+        "`elementN,method,filename,x,y`: composes an image into the surface.",
+    ),
+    (
+        "animation.interval",
+        KeywordKind::Line,
+        "`animationN.interval,interval`: when the animation runs.",
+    ),
+    (
+        "animation.pattern",
+        KeywordKind::Line,
+        "`animationN.patternM,method,surface,weight,x,y`: a frame of the animation. `weight` is in milliseconds.",
+    ),
+    (
+        "animation.option",
+        KeywordKind::Line,
+        "`animationN.option,option`: options of the animation.",
+    ),
+    (
+        "animation.collision",
+        KeywordKind::Line,
+        "`animationN.collisionM,sx,sy,ex,ey,id`: a rectangle collision which follows the animation.",
+    ),
+    (
+        "animation.collisionex",
+        KeywordKind::Line,
+        "`animationN.collisionexM,id,kind,...`: a collision of any shape which follows the animation.",
+    ),
+    (
+        "interval",
+        KeywordKind::Line,
+        "`Ninterval,interval`: legacy syntax of `animationN.interval`.",
+    ),
+    (
+        "pattern",
+        KeywordKind::Line,
+        "`NpatternM,surface,weight,method,x,y`: legacy syntax of `animationN.patternM`. `weight` is in 10 milliseconds.",
+    ),
+    ("option", KeywordKind::Line, "`Noption,option`: legacy syntax of `animationN.option`."),
+    ("collision", KeywordKind::Line, "`collisionN,sx,sy,ex,ey,id`: a rectangle collision."),
+    ("collisionex", KeywordKind::Line, "`collisionexN,id,kind,...`: a collision of any shape."),
+    ("sakura.balloon.offsetx", KeywordKind::Line, "Horizontal offset of the balloon of sakura."),
+    ("sakura.balloon.offsety", KeywordKind::Line, "Vertical offset of the balloon of sakura."),
+    ("kero.balloon.offsetx", KeywordKind::Line, "Horizontal offset of the balloon of kero."),
+    ("kero.balloon.offsety", KeywordKind::Line, "Vertical offset of the balloon of kero."),
+    (
+        "char.balloon.offsetx",
+        KeywordKind::Line,
+        "Horizontal offset of the balloon of the character `charN`.",
+    ),
+    (
+        "char.balloon.offsety",
+        KeywordKind::Line,
+        "Vertical offset of the balloon of the character `charN`.",
+    ),
+    ("balloon.offsetx", KeywordKind::Line, "Horizontal offset of the balloon of this character."),
+    ("balloon.offsety", KeywordKind::Line, "Vertical offset of the balloon of this character."),
+    ("point.centerx", KeywordKind::Line, "Horizontal center of the surface."),
+    ("point.centery", KeywordKind::Line, "Vertical center of the surface."),
+    ("point.kinoko.centerx", KeywordKind::Line, "Horizontal center for kinoko."),
+    ("point.kinoko.centery", KeywordKind::Line, "Vertical center for kinoko."),
+    (
+        "point.basepos.x",
+        KeywordKind::Line,
+        "Horizontal base position for moving and aligning the character.",
+    ),
+    (
+        "point.basepos.y",
+        KeywordKind::Line,
+        "Vertical base position for moving and aligning the character.",
+    ),
+    ("base", KeywordKind::DrawMethod, "Replaces the whole surface with the image."),
+    (
+        "overlayfast",
+        KeywordKind::DrawMethod,
+        "Draws the image only where the lower surface is opaque.",
+    ),
+    (
+        "overlaymultiply",
+        KeywordKind::DrawMethod,
+        "Multiplies the colors of the image with the lower surface, only where the lower surface is opaque.",
+    ),
+    ("overlay", KeywordKind::DrawMethod, "Draws the image over the surface with its transparency."),
+    (
+        "replace",
+        KeywordKind::DrawMethod,
+        "Replaces the pixels in the area of the image, including transparency.",
+    ),
+    (
+        "interpolate",
+        KeywordKind::DrawMethod,
+        "Draws the image only where the lower surface is transparent.",
+    ),
+    ("asis", KeywordKind::DrawMethod, "Draws the image ignoring its transparency."),
+    (
+        "move",
+        KeywordKind::DrawMethod,
+        "Moves the drawn surface by `x`,`y`. The surface is not used.",
+    ),
+    (
+        "bind",
+        KeywordKind::DrawMethod,
+        "Draws the image like `overlay` while the dress-up part is bound.",
+    ),
+    ("add", KeywordKind::DrawMethod, "Adds the image like `overlay` to the base of the surface."),
+    (
+        "reduce",
+        KeywordKind::DrawMethod,
+        "Reduces the transparency of the surface by the transparency of the image.",
+    ),
+    ("insert", KeywordKind::AnimationMethod, "`insert,N`: inserts the patterns of animation N."),
+    ("start", KeywordKind::AnimationMethod, "`start,N`: starts animation N."),
+    ("stop", KeywordKind::AnimationMethod, "`stop,N`: stops animation N."),
+    (
+        "alternativestart",
+        KeywordKind::AnimationMethod,
+        "`alternativestart,(N,M)`: starts one of the animations at random.",
+    ),
+    (
+        "alternativestop",
+        KeywordKind::AnimationMethod,
+        "`alternativestop,(N,M)`: stops one of the animations at random.",
+    ),
+    (
+        "parallelstart",
+        KeywordKind::AnimationMethod,
+        "`parallelstart,(N,M)`: starts all of the animations.",
+    ),
+    (
+        "parallelstop",
+        KeywordKind::AnimationMethod,
+        "`parallelstop,(N,M)`: stops all of the animations.",
+    ),
+    (
+        "sometimes",
+        KeywordKind::AnimationInterval,
+        "Runs at random, with a chance of 1/2 per second.",
+    ),
+    ("rarely", KeywordKind::AnimationInterval, "Runs at random, with a chance of 1/4 per second."),
+    (
+        "random",
+        KeywordKind::AnimationInterval,
+        "`random,N`: runs at random, with a chance of 1/N per second.",
+    ),
+    ("periodic", KeywordKind::AnimationInterval, "`periodic,N`: runs every N seconds."),
+    ("always", KeywordKind::AnimationInterval, "Runs repeatedly."),
+    ("runonce", KeywordKind::AnimationInterval, "Runs once when the surface is shown."),
+    (
+        "never",
+        KeywordKind::AnimationInterval,
+        "Runs only when started by other animations or scripts.",
+    ),
+    ("yen-e", KeywordKind::AnimationInterval, "Runs at the end of a script (`\\e`)."),
+    ("talk", KeywordKind::AnimationInterval, "`talk,N`: runs every N characters of talk."),
+    ("bind", KeywordKind::AnimationInterval, "Runs while the dress-up part is bound."),
+    (
+        "exclusive",
+        KeywordKind::AnimationOption,
+        "Stops the other animations while running, or only the listed ones with `,(N,M)`.",
+    ),
+    ("background", KeywordKind::AnimationOption, "Draws the animation behind the surface."),
+    (
+        "shared-index",
+        KeywordKind::AnimationOption,
+        "Shares the pattern index with other animations.",
+    ),
+    ("rect", KeywordKind::CollisionExKind, "`rect,sx,sy,ex,ey`: a rectangle."),
+    (
+        "ellipse",
+        KeywordKind::CollisionExKind,
+        "`ellipse,sx,sy,ex,ey`: an ellipse in the rectangle.",
+    ),
+    ("circle", KeywordKind::CollisionExKind, "`circle,x,y,r`: a circle."),
+    (
+        "polygon",
+        KeywordKind::CollisionExKind,
+        "`polygon,x1,y1,x2,y2,...`: a polygon of three or more points.",
+    ),
+    (
+        "region",
+        KeywordKind::CollisionExKind,
+        "`region,filename,r,g,b`: pixels of the color in the image.",
+    ),
+];
+
+/// Returns a symbol for each brace of `input`.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::ide::{document_symbols, SymbolKind};
+///
+/// let case = "charset,UTF-8\r\nsurface0-3\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n";
+///
+/// let symbols = document_symbols(case);
+/// assert_eq!(symbols[0].name(), "surface0-3");
+/// assert_eq!(symbols[0].kind(), &SymbolKind::Surface);
+/// assert_eq!(symbols[0].range().start().line(), &1);
+/// assert_eq!(symbols[0].range().end().line(), &4);
+/// ```
+pub fn document_symbols(input: &str) -> Vec<DocumentSymbol> {
+    let shell_surfaces = match parse(input) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let lines: Vec<&str> = split_lines(input).collect();
+
+    brace_spans(&shell_surfaces)
+        .into_iter()
+        .filter_map(|(name_line, close_line, brace)| {
+            let kind = match brace.body() {
+                Brace::Descript(_) => SymbolKind::Descript,
+                Brace::Surface(_) => SymbolKind::Surface,
+                Brace::SurfaceAppend(_) => SymbolKind::SurfaceAppend,
+                Brace::SurfaceAlias(_) => SymbolKind::SurfaceAlias,
+                Brace::Cursor(_) => SymbolKind::Cursor,
+                Brace::Tooltip(_) => SymbolKind::Tooltip,
+                Brace::BlockComment(_) => return None,
+            };
+            let name = lines.get(name_line).copied().unwrap_or_default();
+            let close = lines.get(close_line).copied().unwrap_or_default();
+            Some(DocumentSymbol::new(
+                name.trim().to_string(),
+                kind,
+                Range::new(
+                    Position::new(name_line, 0),
+                    Position::new(close_line, utf16_len(close)),
+                ),
+                line_range(name_line, name),
+            ))
+        })
+        .collect()
+}
+
+/// Explains the keyword at `position`, or the target surface of a pattern.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::ide::{hover, Position};
+///
+/// let case = "charset,UTF-8\r\nsurface0\r\n{\r\nelement0,overlaymultiply,body0.png,0,0\r\n}\r\n";
+///
+/// let result = hover(case, Position::new(3, 12)).unwrap();
+/// assert!(result.contents().starts_with("`overlaymultiply` (draw method)"));
+/// assert_eq!(result.range().start().character(), &9);
+/// ```
+pub fn hover(input: &str, position: Position) -> Option<Hover> {
+    let text = split_lines(input).nth(position.line)?;
+    let token = token_at(text, position.character)?;
+    let range = Range::new(
+        Position::new(position.line, token.start),
+        Position::new(position.line, token.end),
+    );
+
+    let shell_surfaces = parse(input).ok();
+    let pattern = shell_surfaces.as_ref().and_then(|v| {
+        let surface_id = pattern_surface_at(v, position.line, text, &token)?;
+        Some((v, surface_id))
+    });
+    if let Some((shell_surfaces, surface_id)) = pattern {
+        let mut contents = format!("**surface{}**", surface_id);
+        match surface_id {
+            -1 => contents.push_str("\n\nHides the animation."),
+            -2 => contents.push_str("\n\nStops the animation."),
+            id if id >= 0 => match resolve_surface(shell_surfaces, id as SurfaceIdType) {
+                Some(surface) => {
+                    for element in surface.elements() {
+                        contents.push_str(&format!(
+                            "\n- element{}: {} `{}` at {},{}",
+                            element.id(),
+                            element.method(),
+                            element.filename(),
+                            element.x(),
+                            element.y()
+                        ));
+                    }
+                }
+                None => contents.push_str("\n\nNot defined by a `surface` brace in this file."),
+            },
+            _ => {}
+        }
+        return Some(Hover::new(range, contents));
+    }
+
+    let key = token.text.replace(|c: char| c.is_ascii_digit(), "");
+    let contents: Vec<String> = KEYWORDS
+        .iter()
+        .filter(|(name, _, _)| name.eq_ignore_ascii_case(&key))
+        .map(|(name, kind, doc)| format!("`{}` ({})\n\n{}", name, kind.description(), doc))
+        .collect();
+    if contents.is_empty() {
+        return None;
+    }
+    Some(Hover::new(range, contents.join("\n\n---\n\n")))
+}
+
+/// Returns the name lines of `surface` braces defining the target surface of the pattern at
+/// `position`.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::ide::{definition, Position};
+///
+/// let case = "charset,UTF-8\r\nsurface0\r\n{\r\nanimation0.pattern0,overlay,10,100,0,0\r\n}\r\nsurface10\r\n{\r\n}\r\n";
+///
+/// let result = definition(case, Position::new(3, 29));
+/// assert_eq!(result.len(), 1);
+/// assert_eq!(result[0].start().line(), &5);
+/// ```
+pub fn definition(input: &str, position: Position) -> Vec<Range> {
+    let shell_surfaces = match parse(input) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let surface_id = split_lines(input)
+        .nth(position.line)
+        .and_then(|text| Some((text, token_at(text, position.character)?)))
+        .and_then(|(text, token)| pattern_surface_at(&shell_surfaces, position.line, text, &token))
+        .and_then(|id| SurfaceIdType::try_from(id).ok());
+    let surface_id = match surface_id {
+        Some(v) => v,
+        None => return Vec::new(),
+    };
+    let lines: Vec<&str> = split_lines(input).collect();
+
+    brace_spans(&shell_surfaces)
+        .into_iter()
+        .filter(|(_, _, brace)| match brace.body() {
+            Brace::Surface(v) => v.defines(surface_id),
+            _ => false,
+        })
+        .map(|(name_line, _, _)| {
+            line_range(name_line, lines.get(name_line).copied().unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Returns keywords which can be written at `position`.
+///
+/// Draw methods are completed in `element` and `pattern` lines, and intervals and options in
+/// `interval` and `option` lines.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::ide::{completion, KeywordKind, Position};
+///
+/// let case = "charset,UTF-8\r\nsurface0\r\n{\r\nanimation0.interval,\r\n}\r\n";
+///
+/// let result = completion(case, Position::new(3, 20));
+/// assert!(result.iter().all(|v| v.kind() == &KeywordKind::AnimationInterval));
+/// assert!(result.iter().any(|v| v.label() == "sometimes"));
+/// ```
+pub fn completion(input: &str, position: Position) -> Vec<CompletionItem> {
+    let text = match split_lines(input).nth(position.line) {
+        Some(v) => v,
+        None => return Vec::new(),
+    };
+    let prefix = &text[..byte_offset(text, position.character)];
+    let mut fields = prefix.trim_start().split(',');
+    let head = fields
+        .next()
+        .unwrap_or_default()
+        .replace(|c: char| c.is_ascii_digit(), "")
+        .to_ascii_lowercase();
+    let index = fields.count();
+
+    let kinds: &[KeywordKind] = match (head.as_str(), index) {
+        ("element", 1) | ("pattern", 3) => &[KeywordKind::DrawMethod],
+        ("animation.pattern", 1) => &[KeywordKind::DrawMethod, KeywordKind::AnimationMethod],
+        ("animation.interval" | "interval", 1) => &[KeywordKind::AnimationInterval],
+        ("animation.option" | "option", 1) => &[KeywordKind::AnimationOption],
+        ("collisionex" | "animation.collisionex", 2) => &[KeywordKind::CollisionExKind],
+        _ => &[],
+    };
+    KEYWORDS
+        .iter()
+        .filter(|(_, kind, _)| kinds.contains(kind))
+        .map(|(name, kind, doc)| CompletionItem::new(name.to_string(), *kind, doc.to_string()))
+        .collect()
+}
+
+/// Returns lines of `input` without their newlines, with `\r\n`, `\r` and `\n` as newlines as the
+/// parser reads them, so that line numbers match [`Position::line`].
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    split_lines(input)
+}
+
+/// A token of a line, delimited by `,`, `+`, brackets and spaces.
+struct Token<'a> {
+    text: &'a str,
+    /// Start in UTF-16 code units.
+    start: usize,
+    /// End in UTF-16 code units.
+    end: usize,
+}
+
+fn is_delimiter(c: char) -> bool {
+    matches!(c, ',' | '+' | '[' | ']' | '(' | ')') || c.is_whitespace()
+}
+
+fn token_at(text: &str, character: usize) -> Option<Token<'_>> {
+    let offset = byte_offset(text, character);
+    let start = text[..offset]
+        .rfind(is_delimiter)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = text[offset..]
+        .find(is_delimiter)
+        .map(|i| offset + i)
+        .unwrap_or(text.len());
+    if start >= end {
+        return None;
+    }
+    Some(Token {
+        text: &text[start..end],
+        start: utf16_len(&text[..start]),
+        end: utf16_len(&text[..end]),
+    })
+}
+
+/// Returns the surface of the pattern parsed at `line` when `token` of its `text` is the surface
+/// field.
+fn pattern_surface_at(
+    shell_surfaces: &ShellSurfaces,
+    line: usize,
+    text: &str,
+    token: &Token,
+) -> Option<SurfaceIdPointerType> {
+    let (name_line, _, brace) = brace_spans(shell_surfaces)
+        .into_iter()
+        .find(|(name_line, close_line, _)| (*name_line..=*close_line).contains(&line))?;
+    let lines = match brace.body() {
+        Brace::Surface(v) => v.lines(),
+        Brace::SurfaceAppend(v) => v.lines(),
+        _ => return None,
+    };
+    let (pattern, format) = numbered(lines, name_line + first_line_offset(brace.layout()))
+        .find_map(|(i, inner)| match inner {
+            LineContainer::Body(SurfaceInner::AnimationPattern(v), format) if i == line => {
+                Some((v, format))
+            }
+            _ => None,
+        })?;
+    let property = match pattern.method() {
+        AnimationPatternDrawMethod::Normal(_, property) => property,
+        AnimationPatternDrawMethod::Animation(_) => return None,
+    };
+
+    let body = body_range(text, brace.layout(), format.comment().as_deref());
+    let start = byte_offset(text, token.start);
+    if !body.contains(&start) {
+        return None;
+    }
+    // Legacy patterns (`0pattern0,...`) have the surface before the method.
+    let surface_field = if text[body.clone()].starts_with(|c: char| c.is_ascii_digit()) {
+        1
+    } else {
+        2
+    };
+    (text[body.start..start].matches(',').count() == surface_field).then(|| *property.surface_id())
+}
+
+/// Returns 0-based name lines and closing lines of braces.
+fn brace_spans(shell_surfaces: &ShellSurfaces) -> Vec<(usize, usize, &BraceContainer)> {
+    let mut spans = Vec::new();
+    let mut line = leading_lines_len(shell_surfaces);
+    for brace in shell_surfaces.braces() {
+        let name_line = line + brace.header_comments().len();
+        line = name_line + brace_lines_len(brace);
+        spans.push((name_line, line - 1, brace));
+    }
+    spans
+}
+
+fn line_range(line: usize, text: &str) -> Range {
+    Range::new(Position::new(line, 0), Position::new(line, utf16_len(text)))
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Converts an offset in UTF-16 code units into a byte offset, clamped to `text`.
+fn byte_offset(text: &str, character: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= character {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASE: &str = r#"charset,UTF-8
+// header
+descript
+{
+version,1
+}
+
+surface0
+{
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes
+animation0.pattern0,overlay,10,100,0,0
+1pattern0,10,5,overlay,0,0
+}
+surface10
+{
+element0,base,face10.png,5,5
+}
+sakura.surface.alias
+{
+通常,[0]
+}
+"#;
+
+    mod document_symbols {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result: Vec<(String, SymbolKind, usize, usize)> = document_symbols(CASE)
+                .into_iter()
+                .map(|v| {
+                    (
+                        v.name().clone(),
+                        *v.kind(),
+                        *v.range().start().line(),
+                        *v.range().end().line(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    ("descript".to_string(), SymbolKind::Descript, 2, 5),
+                    ("surface0".to_string(), SymbolKind::Surface, 7, 13),
+                    ("surface10".to_string(), SymbolKind::Surface, 14, 17),
+                    (
+                        "sakura.surface.alias".to_string(),
+                        SymbolKind::SurfaceAlias,
+                        18,
+                        21
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_block_comments() {
+            let case = "/*\r\nsurface0\r\n{\r\n}\r\n*/\r\nsurface1\r\n{\r\n/* a\r\nb */\r\n}\r\n";
+            let result: Vec<(String, usize, usize)> = document_symbols(case)
+                .into_iter()
+                .map(|v| {
+                    (
+                        v.name().clone(),
+                        *v.range().start().line(),
+                        *v.range().end().line(),
+                    )
+                })
+                .collect();
+            assert_eq!(result, vec![("surface1".to_string(), 5, 9)]);
+        }
+
+        #[test]
+        fn success_when_cr_newlines() {
+            let case = "charset,UTF-8\rsurface0\r{\r}\rsurface1\r{\r}\r";
+            let result: Vec<Range> = document_symbols(case)
+                .iter()
+                .map(|v| *v.selection_range())
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    Range::new(Position::new(1, 0), Position::new(1, 8)),
+                    Range::new(Position::new(4, 0), Position::new(4, 8)),
+                ]
+            );
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            assert!(document_symbols("surface0\r\n{\r\n").is_empty());
+        }
+    }
+
+    mod hover {
+        use super::*;
+
+        #[test]
+        fn success_when_keyword() {
+            let result = hover(CASE, Position::new(10, 22)).unwrap();
+            assert!(result
+                .contents()
+                .starts_with("`sometimes` (animation interval)"));
+            assert_eq!(
+                result.range(),
+                &Range::new(Position::new(10, 20), Position::new(10, 29))
+            );
+
+            let result = hover(CASE, Position::new(11, 3)).unwrap();
+            assert!(result.contents().starts_with("`animation.pattern` (line)"));
+        }
+
+        #[test]
+        fn success_when_keyword_case() {
+            let case = "surface0\r{\rAnimation0.Interval,Sometimes\r}\r";
+            let result = hover(case, Position::new(2, 22)).unwrap();
+            assert!(result
+                .contents()
+                .starts_with("`sometimes` (animation interval)"));
+
+            let result = hover(case, Position::new(2, 3)).unwrap();
+            assert!(result.contents().starts_with("`animation.interval` (line)"));
+        }
+
+        #[test]
+        fn success_when_pattern_surface() {
+            let result = hover(CASE, Position::new(11, 29)).unwrap();
+            assert_eq!(
+                result.contents(),
+                "**surface10**\n- element0: base `face10.png` at 5,5"
+            );
+
+            let result = hover(CASE, Position::new(12, 10)).unwrap();
+            assert!(result.contents().starts_with("**surface10**"));
+        }
+
+        #[test]
+        fn failed_when_not_keyword() {
+            assert!(hover(CASE, Position::new(9, 20)).is_none());
+            assert!(hover(CASE, Position::new(11, 33)).is_none());
+            assert!(hover(CASE, Position::new(100, 0)).is_none());
+        }
+    }
+
+    mod definition {
+        use super::*;
+
+        #[test]
+        fn success_when_pattern_surface() {
+            let expected = vec![Range::new(Position::new(14, 0), Position::new(14, 9))];
+            assert_eq!(definition(CASE, Position::new(11, 28)), expected);
+            assert_eq!(definition(CASE, Position::new(12, 10)), expected);
+        }
+
+        #[test]
+        fn success_when_one_line_brace() {
+            let case =
+                "surface0 { animation0.pattern0,overlay,1,100,0,0 }\r\nsurface1\r\n{\r\n}\r\n";
+            let expected = vec![Range::new(Position::new(1, 0), Position::new(1, 8))];
+            assert_eq!(definition(case, Position::new(0, 39)), expected);
+            assert!(definition(case, Position::new(0, 28)).is_empty());
+        }
+
+        #[test]
+        fn success_when_keyword_case() {
+            let case = "surface0\r\n{\r\nAnimation0.Pattern0,Overlay,1,100,0,0\r\n}\r\nsurface1\r\n{\r\n}\r\n";
+            let expected = vec![Range::new(Position::new(4, 0), Position::new(4, 8))];
+            assert_eq!(definition(case, Position::new(2, 28)), expected);
+        }
+
+        #[test]
+        fn success_when_trailing_comment() {
+            let case = "surface0\r\n{\r\nanimation0.pattern0,overlay,1,100,0,0 // 0\r\n}\r\nsurface1\r\n{\r\n}\r\n";
+            let expected = vec![Range::new(Position::new(4, 0), Position::new(4, 8))];
+            assert_eq!(definition(case, Position::new(2, 28)), expected);
+            assert!(definition(case, Position::new(2, 41)).is_empty());
+        }
+
+        #[test]
+        fn failed_when_not_pattern_surface() {
+            assert!(definition(CASE, Position::new(11, 32)).is_empty());
+            assert!(definition(CASE, Position::new(9, 3)).is_empty());
+        }
+    }
+
+    mod completion {
+        use super::*;
+
+        #[test]
+        fn success_when_method_field() {
+            let result = completion(CASE, Position::new(9, 9));
+            assert!(result.iter().all(|v| v.kind() == &KeywordKind::DrawMethod));
+            assert_eq!(result.len(), 11);
+
+            let result = completion(CASE, Position::new(11, 20));
+            assert_eq!(result.len(), 18);
+        }
+
+        #[test]
+        fn success_when_keyword_case() {
+            let case = "surface0\r{\rElement0,\r}\r";
+            let result = completion(case, Position::new(2, 9));
+            assert_eq!(result.len(), 11);
+        }
+
+        #[test]
+        fn success_when_other_field() {
+            assert!(completion(CASE, Position::new(9, 3)).is_empty());
+            assert!(completion(CASE, Position::new(9, 20)).is_empty());
+        }
+    }
+
+    mod token_at {
+        use super::*;
+
+        #[test]
+        fn success_when_utf16_str() {
+            let token = token_at("🍣,あい,b", 4).unwrap();
+            assert_eq!(token.text, "あい");
+            assert_eq!((token.start, token.end), (3, 5));
+        }
+
+        #[test]
+        fn failed_when_delimiter() {
+            assert!(token_at("a,,b", 2).is_none());
+        }
+    }
+}
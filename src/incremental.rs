@@ -0,0 +1,464 @@
+//! Reparses only the braces touched by an edit.
+//!
+//! Braces do not depend on each other's text, so after an edit the braces overlapping it are
+//! parsed again and spliced into the previous tree. Edits of the header, the `charset` line or
+//! the footer, edits which may make a `charset` line, and edits whose result does not split
+//! into whole braces, fall back to parsing the whole text. [`Document`] keeps the text and the [`ParseOptions`] together, so every reparse
+//! uses the options the text was first parsed with.
+
+use std::ops::Range;
+
+use shell_parser_common_rs::ShellParseError;
+
+use crate::{
+    ast::ShellSurfaces,
+    layout::{brace_lines_len, leading_lines_len},
+    parse::{line_starts, parse_braces, parse_with_options, split_lines, ParseOptions},
+};
+
+/// Replacement of bytes `start..end` of a text by `text`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl TextEdit {
+    pub fn new(start: usize, end: usize, text: String) -> TextEdit {
+        TextEdit { start, end, text }
+    }
+
+    /// Byte offset where the replaced range starts.
+    pub fn start(&self) -> &usize {
+        &self.start
+    }
+    /// Byte offset where the replaced range ends (exclusive).
+    pub fn end(&self) -> &usize {
+        &self.end
+    }
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+
+    /// Returns `input` with this edit applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or `end` is out of `input` or not on a char boundary.
+    pub fn apply(&self, input: &str) -> String {
+        let mut result = String::with_capacity(input.len() + self.text.len());
+        result.push_str(&input[..self.start]);
+        result.push_str(&self.text);
+        result.push_str(&input[self.end..]);
+        result
+    }
+}
+
+/// Braces replaced by [`reparse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reparse {
+    old_braces: Range<usize>,
+    new_braces: Range<usize>,
+}
+
+impl Reparse {
+    /// Indices of the replaced braces in the previous tree.
+    pub fn old_braces(&self) -> &Range<usize> {
+        &self.old_braces
+    }
+    /// Indices of the reparsed braces in the updated tree.
+    pub fn new_braces(&self) -> &Range<usize> {
+        &self.new_braces
+    }
+}
+
+/// A text and its [`ShellSurfaces`], parsed with the same [`ParseOptions`] after every edit.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{
+///     incremental::{Document, TextEdit},
+///     ParseOptions,
+/// };
+///
+/// let input = "surface0\r\n{\r\n}\r\n";
+/// let options = ParseOptions::new().with_case_insensitive_keywords(false);
+/// let mut document = Document::new(input.to_string(), options).unwrap();
+///
+/// let edit = TextEdit::new(input.len(), input.len(), "surface1\r\n{\r\n}\r\n".to_string());
+/// document.apply(&edit).unwrap();
+/// assert_eq!(document.shell_surfaces().braces().len(), 2);
+///
+/// let end = document.text().len();
+/// let edit = TextEdit::new(end, end, "Surface2\r\n{\r\n}\r\n".to_string());
+/// assert!(document.apply(&edit).is_err());
+/// assert_eq!(document.shell_surfaces().braces().len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    text: String,
+    shell_surfaces: ShellSurfaces,
+    options: ParseOptions,
+}
+
+impl Document {
+    /// Parses `text` with `options`.
+    pub fn new(text: String, options: ParseOptions) -> Result<Document, nom::Err<ShellParseError>> {
+        let shell_surfaces = parse_with_options(&text, &options)?;
+        Ok(Document {
+            text,
+            shell_surfaces,
+            options,
+        })
+    }
+
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+    pub fn shell_surfaces(&self) -> &ShellSurfaces {
+        &self.shell_surfaces
+    }
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Applies `edit` to the text and reparses the braces it touches with [`Document::options`].
+    ///
+    /// The document is left as it is when the edited text cannot be parsed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of `edit` is out of the text or not on a char boundary.
+    pub fn apply(&mut self, edit: &TextEdit) -> Result<Reparse, nom::Err<ShellParseError>> {
+        let result =
+            reparse_with_options(&mut self.shell_surfaces, &self.text, edit, &self.options)?;
+        self.text = edit.apply(&self.text);
+        Ok(result)
+    }
+}
+
+/// Applies `edit` to `old_input`, which `shell_surfaces` was parsed from, and updates
+/// `shell_surfaces` by parsing only the braces the edit touches.
+///
+/// `shell_surfaces` is left as it is when the edited text cannot be parsed.
+///
+/// # Panics
+///
+/// Panics if the range of `edit` is out of `old_input` or not on a char boundary.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{
+///     incremental::{reparse, TextEdit},
+///     parse,
+/// };
+///
+/// let input = "charset,UTF-8\r\nsurface0\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\nsurface1\r\n{\r\n}\r\n";
+/// let mut shell_surfaces = parse(input).unwrap();
+///
+/// let start = input.find("body0").unwrap();
+/// let edit = TextEdit::new(start, start + 5, "body1".to_string());
+/// let result = reparse(&mut shell_surfaces, input, &edit).unwrap();
+///
+/// assert_eq!(result.old_braces(), &(0..1));
+/// assert_eq!(result.new_braces(), &(0..1));
+/// assert_eq!(shell_surfaces, parse(&edit.apply(input)).unwrap());
+/// ```
+pub fn reparse(
+    shell_surfaces: &mut ShellSurfaces,
+    old_input: &str,
+    edit: &TextEdit,
+) -> Result<Reparse, nom::Err<ShellParseError>> {
+    reparse_with_options(shell_surfaces, old_input, edit, &ParseOptions::default())
+}
+
+/// [`reparse`] of `shell_surfaces` parsed with `options`, used again for the edited braces and
+/// for the fallback.
+///
+/// # Panics
+///
+/// Panics if the range of `edit` is out of `old_input` or not on a char boundary.
+pub fn reparse_with_options(
+    shell_surfaces: &mut ShellSurfaces,
+    old_input: &str,
+    edit: &TextEdit,
+    options: &ParseOptions,
+) -> Result<Reparse, nom::Err<ShellParseError>> {
+    let new_input = edit.apply(old_input);
+
+    let spans = brace_spans(shell_surfaces, old_input);
+    let touched = touched_braces(&spans, edit)
+        .filter(|_| !may_declare_charset(shell_surfaces, old_input, edit));
+    if let Some(old_braces) = touched {
+        let start = spans[old_braces.start].start;
+        let old_end = spans[old_braces.end - 1].end;
+        let new_end = old_end + edit.text.len() + edit.start - edit.end;
+
+        if splits_at(&new_input, new_end) {
+            if let Some(braces) = parse_braces(&new_input[start..new_end], options) {
+                let new_braces = old_braces.start..old_braces.start + braces.len();
+                shell_surfaces
+                    .braces_mut()
+                    .splice(old_braces.clone(), braces);
+                return Ok(Reparse {
+                    old_braces,
+                    new_braces,
+                });
+            }
+        }
+    }
+
+    let result = parse_with_options(&new_input, options)?;
+    let reparse = Reparse {
+        old_braces: 0..shell_surfaces.braces().len(),
+        new_braces: 0..result.braces().len(),
+    };
+    *shell_surfaces = result;
+    Ok(reparse)
+}
+
+/// Returns indices of braces overlapping or touching `edit`, or `None` when the edit reaches
+/// out of the braces.
+fn touched_braces(spans: &[Range<usize>], edit: &TextEdit) -> Option<Range<usize>> {
+    let first = spans
+        .iter()
+        .position(|v| edit.start <= v.end && v.start <= edit.end)?;
+    let last = spans
+        .iter()
+        .rposition(|v| edit.start <= v.end && v.start <= edit.end)?;
+    if edit.start < spans[first].start || spans[last].end < edit.end {
+        return None;
+    }
+    Some(first..last + 1)
+}
+
+/// Whether `edit` may make a `charset` line, which a full parse takes out of the braces.
+///
+/// Without a `charset` line, comments at the top are header comments of the first brace, so an
+/// edit of them or a replacement with a `charset` line changes the header of the whole text.
+fn may_declare_charset(shell_surfaces: &ShellSurfaces, input: &str, edit: &TextEdit) -> bool {
+    let has_charset_line = split_lines(&edit.text).any(|v| {
+        v.trim_start()
+            .get(..7)
+            .is_some_and(|v| v.eq_ignore_ascii_case("charset"))
+    });
+    let in_first_header = !*shell_surfaces.charset_declared()
+        && shell_surfaces.braces().first().is_some_and(|v| {
+            let starts = line_starts(input);
+            let end = leading_lines_len(shell_surfaces) + v.header_comments().len();
+            edit.start <= starts.get(end).copied().unwrap_or(input.len())
+        });
+    has_charset_line || in_first_header
+}
+
+/// Whether a brace can end at byte `offset` of `input` without joining the following text.
+fn splits_at(input: &str, offset: usize) -> bool {
+    let (before, after) = input.split_at(offset);
+    after.is_empty()
+        || before.ends_with('\n')
+        || (before.ends_with('\r') && !after.starts_with('\n'))
+}
+
+/// Returns byte ranges of braces, from their header comments to the line of `}`.
+fn brace_spans(shell_surfaces: &ShellSurfaces, input: &str) -> Vec<Range<usize>> {
+    let starts = line_starts(input);
+    let offset = |line: usize| starts.get(line).copied().unwrap_or(input.len());
+
+    let mut spans = Vec::new();
+    let mut line = leading_lines_len(shell_surfaces);
+    for brace in shell_surfaces.braces() {
+        let start = line;
+        line += brace.header_comments().len() + brace_lines_len(brace);
+        spans.push(offset(start)..offset(line));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    const CASE: &str = "charset,UTF-8\r\n// header\r\n\r\ndescript\r\n{\r\nversion,1\r\n}\r\n\r\nsurface0\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n// surface1\r\nsurface1\r\n{\r\n}\r\n// footer\r\n";
+
+    fn check(target: &str, text: &str) -> Reparse {
+        let start = CASE.find(target).unwrap();
+        let edit = TextEdit::new(start, start + target.len(), text.to_string());
+        let mut shell_surfaces = parse(CASE).unwrap();
+        let result = reparse(&mut shell_surfaces, CASE, &edit).unwrap();
+        assert_eq!(shell_surfaces, parse(&edit.apply(CASE)).unwrap());
+        result
+    }
+
+    mod reparse {
+        use super::*;
+
+        #[test]
+        fn success_when_edit_in_brace() {
+            let result = check("body0", "face0");
+            assert_eq!(result.old_braces(), &(1..2));
+            assert_eq!(result.new_braces(), &(1..2));
+
+            let result = check("// surface1", "// surface one");
+            assert_eq!(result.old_braces(), &(1..3));
+            assert_eq!(result.new_braces(), &(1..3));
+        }
+
+        #[test]
+        fn success_when_braces_added_and_removed() {
+            let result = check("}\r\n\r\nsurface0", "}\r\nsurface5\r\n{\r\n}\r\nsurface0");
+            assert_eq!(result.old_braces(), &(0..2));
+            assert_eq!(result.new_braces(), &(0..3));
+
+            let result = check(
+                "element0,overlay,body0.png,0,0\r\n}\r\n// surface1\r\nsurface1\r\n{\r\n",
+                "",
+            );
+            assert_eq!(result.old_braces(), &(1..3));
+            assert_eq!(result.new_braces(), &(1..2));
+        }
+
+        #[test]
+        fn success_when_fallback() {
+            let result = check("UTF-8", "Shift_JIS");
+            assert_eq!(result.old_braces(), &(0..3));
+            assert_eq!(result.new_braces(), &(0..3));
+
+            let result = check("// footer", "// end");
+            assert_eq!(result.old_braces(), &(0..3));
+
+            let mut shell_surfaces = parse(CASE).unwrap();
+            let edit = TextEdit::new(CASE.len(), CASE.len(), "surface2\r\n{\r\n}\r\n".to_string());
+            let result = reparse(&mut shell_surfaces, CASE, &edit).unwrap();
+            assert_eq!(result.new_braces(), &(0..4));
+        }
+
+        #[test]
+        fn success_when_charset_made_without_charset() {
+            let input = "// charset,UTF-8\r\n// header\r\nsurface0\r\n{\r\n}\r\n";
+            let checks = [
+                TextEdit::new(0, 3, String::new()),
+                TextEdit::new(0, 0, "charset,UTF-8\r\n".to_string()),
+                TextEdit::new(
+                    input.find("}").unwrap() + 3,
+                    input.len(),
+                    "charset,UTF-8\r\n".to_string(),
+                ),
+            ];
+            for edit in checks {
+                let mut shell_surfaces = parse(input).unwrap();
+                let text = edit.apply(input);
+                match parse(&text) {
+                    Ok(expected) => {
+                        reparse(&mut shell_surfaces, input, &edit).unwrap();
+                        assert_eq!(shell_surfaces, expected, "{:?}", text);
+                    }
+                    Err(_) => assert!(reparse(&mut shell_surfaces, input, &edit).is_err()),
+                }
+            }
+
+            let mut document = Document::new(input.to_string(), ParseOptions::default()).unwrap();
+            document.apply(&TextEdit::new(0, 3, String::new())).unwrap();
+            assert!(document.shell_surfaces().charset_declared());
+            assert_eq!(document.shell_surfaces(), &parse(document.text()).unwrap());
+        }
+
+        #[test]
+        fn success_when_any_char_removed() {
+            for (start, c) in CASE.char_indices() {
+                let edit = TextEdit::new(start, start + c.len_utf8(), String::new());
+                let mut shell_surfaces = parse(CASE).unwrap();
+                match parse(&edit.apply(CASE)) {
+                    Ok(expected) => {
+                        reparse(&mut shell_surfaces, CASE, &edit).unwrap();
+                        assert_eq!(shell_surfaces, expected, "removed at {}", start);
+                    }
+                    Err(_) => assert!(reparse(&mut shell_surfaces, CASE, &edit).is_err()),
+                }
+            }
+        }
+
+        #[test]
+        fn failed_when_invalid_edit() {
+            let mut shell_surfaces = parse(CASE).unwrap();
+            let start = CASE.find("}\r\n//").unwrap();
+            let edit = TextEdit::new(start, start + 1, String::new());
+            assert!(reparse(&mut shell_surfaces, CASE, &edit).is_err());
+            assert_eq!(shell_surfaces, parse(CASE).unwrap());
+        }
+    }
+
+    mod document {
+        use super::*;
+
+        fn edit(input: &str, target: &str, text: &str) -> TextEdit {
+            let start = input.find(target).unwrap();
+            TextEdit::new(start, start + target.len(), text.to_string())
+        }
+
+        #[test]
+        fn success_when_options_kept() {
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+            let mut document = Document::new(CASE.to_string(), options.clone()).unwrap();
+
+            let result = document.apply(&edit(CASE, "// footer", "// end")).unwrap();
+            assert_eq!(result.old_braces(), &(0..3));
+            let text = CASE.replace("// footer", "// end");
+            assert_eq!(document.text(), &text);
+            assert_eq!(
+                document.shell_surfaces(),
+                &parse_with_options(&text, &options).unwrap()
+            );
+            assert_eq!(document.options(), &options);
+
+            let result = document.apply(&edit(&text, "body0", "face0")).unwrap();
+            assert_eq!(result.old_braces(), &(1..2));
+            assert_eq!(
+                document.shell_surfaces(),
+                &parse_with_options(&text.replace("body0", "face0"), &options).unwrap()
+            );
+        }
+
+        #[test]
+        fn failed_when_keyword_case_not_allowed() {
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+            let mut document = Document::new(CASE.to_string(), options.clone()).unwrap();
+
+            let braced = edit(CASE, "overlay", "Overlay");
+            document.apply(&braced).unwrap();
+            let expected = parse_with_options(&braced.apply(CASE), &options).unwrap();
+            assert_eq!(document.shell_surfaces(), &expected);
+            assert_ne!(&expected, &parse(&braced.apply(CASE)).unwrap());
+
+            let mut document = Document::new(CASE.to_string(), options).unwrap();
+            let before = document.clone();
+            let surface = edit(CASE, "// header", "Surface9\r\n{\r\n}\r\n// header");
+            assert!(document.apply(&surface).is_err());
+            assert_eq!(document, before);
+
+            let mut document = Document::new(CASE.to_string(), ParseOptions::default()).unwrap();
+            assert!(document.apply(&surface).is_ok());
+        }
+    }
+
+    mod brace_spans {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let spans = brace_spans(&parse(CASE).unwrap(), CASE);
+            let texts: Vec<&str> = spans.into_iter().map(|v| &CASE[v]).collect();
+            assert_eq!(
+                texts,
+                vec![
+                    "// header\r\n\r\ndescript\r\n{\r\nversion,1\r\n}\r\n",
+                    "\r\nsurface0\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n",
+                    "// surface1\r\nsurface1\r\n{\r\n}\r\n",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Line layout of parsed `surfaces.txt`, shared by everything that maps AST nodes back to
+//! source lines.
+
+use std::ops::Range;
+
+use crate::ast::{Brace, BraceContainer, BraceLayout, LineContainer, ShellSurfaces};
+
+/// Returns the number of lines before the first brace.
+pub(crate) fn leading_lines_len(shell_surfaces: &ShellSurfaces) -> usize {
+    shell_surfaces.header_comments().len() + usize::from(*shell_surfaces.charset_declared())
+}
+
+/// Number of lines of `brace` from its name to `}`, without its header comments.
+pub(crate) fn brace_lines_len(brace: &BraceContainer) -> usize {
+    let inner = |lines: usize| match brace.layout() {
+        BraceLayout::OwnLines => lines + 3,
+        BraceLayout::SameLine => lines + 2,
+        BraceLayout::OneLine => 1,
+    };
+    fn lines_len<T>(lines: &[LineContainer<T>]) -> usize {
+        lines.iter().map(LineContainer::lines_len).sum()
+    }
+
+    match brace.body() {
+        Brace::Descript(v) => inner(lines_len(v.lines())),
+        Brace::Surface(v) => inner(lines_len(v.lines())),
+        Brace::SurfaceAppend(v) => inner(lines_len(v.lines())),
+        Brace::SurfaceAlias(v) => inner(lines_len(v.lines())),
+        Brace::Cursor(v) => inner(lines_len(v.lines())),
+        Brace::Tooltip(v) => inner(lines_len(v.lines())),
+        Brace::BlockComment(v) => v.lines_len(),
+    }
+}
+
+/// Number of lines from the brace name to the first line inside the brace.
+pub(crate) fn first_line_offset(layout: &BraceLayout) -> usize {
+    match layout {
+        BraceLayout::OwnLines => 2,
+        BraceLayout::SameLine => 1,
+        BraceLayout::OneLine => 0,
+    }
+}
+
+/// Pairs `lines` with their line numbers, starting from `first_line`.
+pub(crate) fn numbered<T>(
+    lines: &[LineContainer<T>],
+    first_line: usize,
+) -> impl Iterator<Item = (usize, &LineContainer<T>)> {
+    lines.iter().scan(first_line, |line, inner| {
+        let result = (*line, inner);
+        *line += inner.lines_len();
+        Some(result)
+    })
+}
+
+/// Byte range of the body in `text` of a line inside a brace written in `layout`, without
+/// spaces around it and its trailing `comment`.
+pub(crate) fn body_range(text: &str, layout: &BraceLayout, comment: Option<&str>) -> Range<usize> {
+    let (start, end) = match layout {
+        BraceLayout::OneLine => (
+            text.find('{').map_or(0, |v| v + 1),
+            text.rfind('}').unwrap_or(text.len()),
+        ),
+        BraceLayout::OwnLines | BraceLayout::SameLine => {
+            (0, text.len() - comment.map_or(0, str::len))
+        }
+    };
+    let body = &text[start..end];
+    let start = start + body.len() - body.trim_start().len();
+    start..start + body.trim().len()
+}
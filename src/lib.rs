@@ -41,9 +41,53 @@
 //! };
 //! assert!(!shell_surfaces.braces().is_empty());
 //! ```
+//!
+//! ## Borrowed AST
+//!
+//! Every type in [`ast`] holding text is generic over its string type, which defaults to
+//! `String`. [`parse_borrowed`] returns `ShellSurfaces<&str>` borrowing from the input, which
+//! avoids one allocation per comment, file name and collision id when scanning many shells.
+//! [`IntoOwned`] converts it to the owned form. `cargo bench` compares both.
+//!
+//! ## Features
+//!
+//! - `serde`: implements `Serialize` and `Deserialize` for every type in [`ast`].
+//!   See [`ast`] for the JSON representation.
+//! - `schema`: generates the JSON Schema of that representation (enables `serde`).
+//! - `cli`: builds the command-line tools (enables `serde`).
+//!   - `surfaces-lint`: checks `surfaces*.txt` of a shell.
+//!   - `surfaces-fmt`: rewrites `surfaces*.txt` of a shell into a canonical layout.
+//!   - `surfaces-migrate`: rewrites legacy animation lines into `animationN.` syntax.
+//!   - `surfaces-dump`: prints the resolved view of surfaces after appends and aliases.
+//!   - `surfaces-diff`: prints semantic differences between two shells.
+//! - `lsp`: builds `surfaces-lsp`, a language server for `surfaces*.txt` (enables `cli`).
+//!   See [`ide`] for its features.
 
 pub mod ast;
+pub mod builder;
+pub mod characters;
+pub mod diff;
+pub mod dressup;
+pub mod encoding;
+pub mod files;
+pub mod fold;
+pub mod format;
+pub mod ide;
+pub mod incremental;
+pub mod lint;
+pub mod migrate;
 pub mod parse;
+pub mod resolve;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde_charset;
+pub mod visit;
+pub mod visit_mut;
+
+mod display;
+mod layout;
 
 pub use ast::*;
+pub use dressup::*;
 pub use parse::*;
@@ -0,0 +1,560 @@
+//! Semantic checks of [`ShellSurfaces`].
+//!
+//! Every line of `surfaces.txt` is kept in the AST, so diagnostics carry the 1-based line number
+//! of the source that was parsed.
+
+use std::collections::HashSet;
+
+use crate::{
+    ast::{Brace, CollisionExKind, DescriptInner, LineContainer, ShellSurfaces, SurfaceInner},
+    display::charset_name,
+    encoding::DetectedEncoding,
+    layout::{brace_lines_len, first_line_offset, leading_lines_len, numbered},
+    parse::{failed_line, line_starts, parse, ParseOptions},
+};
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in `surfaces.txt`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Diagnostic {
+    severity: Severity,
+    code: String,
+    line: usize,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: String, line: usize, message: String) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code,
+            line,
+            message,
+        }
+    }
+
+    pub fn severity(&self) -> &Severity {
+        &self.severity
+    }
+    /// Kebab-case identifier of the check (e.g. `duplicate-element`).
+    pub fn code(&self) -> &String {
+        &self.code
+    }
+    /// 1-based line number.
+    pub fn line(&self) -> &usize {
+        &self.line
+    }
+    pub fn message(&self) -> &String {
+        &self.message
+    }
+}
+
+/// Parses `input` and checks it.
+///
+/// A parse failure is reported as a `parse-error` diagnostic.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::lint::{lint_str, Severity};
+///
+/// let case = r#"charset,UTF-8
+/// surface0
+/// {
+/// element0,overlay,body0.png,0,0
+/// element0,overlay,face0.png,0,0
+/// }
+/// "#;
+///
+/// let diagnostics = lint_str(case);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].severity(), &Severity::Warning);
+/// assert_eq!(diagnostics[0].code(), "duplicate-element");
+/// assert_eq!(diagnostics[0].line(), &5);
+/// ```
+pub fn lint_str(input: &str) -> Vec<Diagnostic> {
+    match parse(input) {
+        Ok(v) => lint(&v),
+        Err(_) => vec![parse_error(input)],
+    }
+}
+
+/// Parses `input` decoded with `encoding` and checks it, like [`lint_str`].
+///
+/// A `charset` line not matching the bytes is reported as an `encoding-mismatch` warning.
+pub fn lint_decoded(input: &str, encoding: &DetectedEncoding) -> Vec<Diagnostic> {
+    let (charset_line, mut diagnostics) = match parse(input) {
+        Ok(v) => (v.header_comments().len() + 1, lint(&v)),
+        Err(_) => (1, vec![parse_error(input)]),
+    };
+    if let (true, Some(declared)) = (encoding.is_mismatch(), encoding.declared()) {
+        diagnostics.insert(
+            0,
+            Diagnostic::new(
+                Severity::Warning,
+                "encoding-mismatch".to_string(),
+                charset_line,
+                format!(
+                    "`charset` line declares `{}`, but the bytes are read as `{}`",
+                    charset_name(declared),
+                    charset_name(encoding.charset())
+                ),
+            ),
+        );
+    }
+    diagnostics
+}
+
+fn parse_error(input: &str) -> Diagnostic {
+    let line = failed_line(input, &ParseOptions::default()).unwrap_or(1);
+    let starts = line_starts(input);
+    let message = match starts.get(line - 1) {
+        Some(start) if *start < input.len() => {
+            let end = starts.get(line).copied().unwrap_or(input.len());
+            format!("failed to parse at `{}`", input[*start..end].trim())
+        }
+        _ => "failed to parse at the end".to_string(),
+    };
+    Diagnostic::new(Severity::Error, "parse-error".to_string(), line, message)
+}
+
+/// Checks a parsed [`ShellSurfaces`].
+pub fn lint(shell_surfaces: &ShellSurfaces) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        diagnostics: Vec::new(),
+    };
+
+    if !shell_surfaces.charset_declared() {
+        linter.warning(
+            "missing-charset",
+            1,
+            format!(
+                "`charset` line is missing, `{}` is assumed",
+                charset_name(shell_surfaces.charset())
+            ),
+        );
+    }
+
+    let mut aliases = HashSet::new();
+    let mut line = leading_lines_len(shell_surfaces) + 1;
+    let mut has_descript = false;
+    for brace in shell_surfaces.braces() {
+        let brace_line = line + brace.header_comments().len();
+        line = brace_line + first_line_offset(brace.layout());
+        match brace.body() {
+            Brace::Descript(v) => {
+                if has_descript {
+                    linter.warning(
+                        "duplicate-descript",
+                        brace_line,
+                        "`descript` is defined more than once".to_string(),
+                    );
+                }
+                has_descript = true;
+                linter.descript_lines(v.lines(), line);
+            }
+            Brace::Surface(v) => linter.surface_lines(v.lines(), line),
+            Brace::SurfaceAppend(v) => linter.surface_lines(v.lines(), line),
+            Brace::SurfaceAlias(v) => {
+                for (line, inner) in numbered(v.lines(), line) {
+                    match inner {
+                        LineContainer::Body(b, _) => {
+                            if !aliases.insert((v.id().index(), b.target())) {
+                                linter.warning(
+                                    "duplicate-alias",
+                                    line,
+                                    format!("alias `{}` is defined more than once", b.target()),
+                                );
+                            }
+                            for id in b
+                                .surfaces()
+                                .iter()
+                                .filter(|v| !shell_surfaces.defines_surface(**v))
+                            {
+                                linter.warning(
+                                    "undefined-alias-surface",
+                                    line,
+                                    format!(
+                                        "alias `{}` points to undefined surface{}",
+                                        b.target(),
+                                        id
+                                    ),
+                                );
+                            }
+                        }
+                        LineContainer::Comment(c) => linter.comment(c.body(), line),
+                        LineContainer::BlockComment(_) => {}
+                    }
+                }
+            }
+            Brace::Cursor(v) => linter.unknown_lines(v.lines(), line),
+            Brace::Tooltip(v) => linter.unknown_lines(v.lines(), line),
+            Brace::BlockComment(_) => {}
+        }
+        line = brace_line + brace_lines_len(brace);
+    }
+
+    linter.diagnostics
+}
+
+struct Linter {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    fn error(&mut self, code: &str, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            code.to_string(),
+            line,
+            message,
+        ));
+    }
+
+    fn warning(&mut self, code: &str, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            code.to_string(),
+            line,
+            message,
+        ));
+    }
+
+    fn comment(&mut self, body: &str, line: usize) {
+        let body = body.trim();
+        if !body.is_empty() && !body.starts_with("//") {
+            self.warning(
+                "unknown-line",
+                line,
+                format!("`{}` is not recognized and treated as a comment", body),
+            );
+        }
+    }
+
+    fn unknown_lines<T>(&mut self, lines: &[LineContainer<T>], first_line: usize) {
+        for (line, inner) in numbered(lines, first_line) {
+            if let LineContainer::Comment(c) = inner {
+                self.comment(c.body(), line);
+            }
+        }
+    }
+
+    fn descript_lines(&mut self, lines: &[LineContainer<DescriptInner>], first_line: usize) {
+        for (line, inner) in numbered(lines, first_line) {
+            match inner {
+                LineContainer::Body(DescriptInner::Version(v), _) if *v > 1 => self.warning(
+                    "unknown-version",
+                    line,
+                    format!("version {} is not known", v),
+                ),
+                LineContainer::Body(..) | LineContainer::BlockComment(_) => {}
+                LineContainer::Comment(c) => self.comment(c.body(), line),
+            }
+        }
+    }
+
+    fn surface_lines(&mut self, lines: &[LineContainer<SurfaceInner>], first_line: usize) {
+        let mut elements = HashSet::new();
+        let mut collisions = HashSet::new();
+        for (line, inner) in numbered(lines, first_line) {
+            let body = match inner {
+                LineContainer::Body(v, _) => v,
+                LineContainer::Comment(c) => {
+                    self.comment(c.body(), line);
+                    continue;
+                }
+                LineContainer::BlockComment(_) => continue,
+            };
+            match body {
+                SurfaceInner::Element(v) if !elements.insert(*v.id()) => {
+                    self.warning(
+                        "duplicate-element",
+                        line,
+                        format!("element{} is defined more than once", v.id()),
+                    );
+                }
+                SurfaceInner::Collision(v) => {
+                    if !collisions.insert(*v.id()) {
+                        self.warning(
+                            "duplicate-collision",
+                            line,
+                            format!("collision{} is defined more than once", v.id()),
+                        );
+                    }
+                    if v.start_x() > v.end_x() || v.start_y() > v.end_y() {
+                        self.warning(
+                            "inverted-collision",
+                            line,
+                            format!("collision{} has its start after its end", v.id()),
+                        );
+                    }
+                }
+                SurfaceInner::CollisionEx(v) => {
+                    if !collisions.insert(*v.id()) {
+                        self.warning(
+                            "duplicate-collision",
+                            line,
+                            format!("collision{} is defined more than once", v.id()),
+                        );
+                    }
+                    self.collision_ex_kind(v.kind(), line);
+                }
+                SurfaceInner::AnimationCollisionEx(v) => {
+                    self.collision_ex_kind(v.collision().kind(), line)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collision_ex_kind(&mut self, kind: &CollisionExKind, line: usize) {
+        if let CollisionExKind::Polygon(v) = kind {
+            if v.len() % 2 != 0 || v.len() < 6 {
+                self.error(
+                    "invalid-polygon",
+                    line,
+                    "polygon needs at least 3 pairs of coordinates".to_string(),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod lint_str {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"// header
+charset,UTF-8
+
+descript
+{
+version,1
+}
+
+surface0-1
+{
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes
+animation0.pattern0,overlay,1,100,0,0
+animation0.pattern1,overlay,-1,100,0,0
+// comment
+collision0,40,56,95,90,Head
+collisionex1,Face,polygon,0,0,10,0,10,10
+}
+
+surface.append0
+{
+collision2,0,0,10,10,Bust
+}
+
+sakura.surface.alias
+{
+通常,[0,1]
+}
+"#;
+            assert!(lint_str(case).is_empty());
+        }
+
+        #[test]
+        fn success_when_warning_str() {
+            let case = r#"charset,UTF-8
+descript
+{
+version,2
+}
+// comment
+surface0
+{
+element0,overlay,body0.png,0,0
+element0,overlay,face0.png,0,0
+animation0.pattern0,overlay,5,100,0,0
+collision0,95,56,40,90,Head
+collision0,0,0,10,10,Face
+typo0,1,2
+}
+surface.append3
+{
+}
+sakura.surface.alias
+{
+通常,[0]
+通常,[9]
+}
+descript
+{
+}
+"#;
+            let result: Vec<(String, usize)> = lint_str(case)
+                .iter()
+                .map(|v| (v.code().clone(), *v.line()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    ("unknown-version".to_string(), 4),
+                    ("duplicate-element".to_string(), 10),
+                    ("inverted-collision".to_string(), 12),
+                    ("duplicate-collision".to_string(), 13),
+                    ("unknown-line".to_string(), 14),
+                    ("duplicate-alias".to_string(), 22),
+                    ("undefined-alias-surface".to_string(), 22),
+                    ("duplicate-descript".to_string(), 24),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_block_comments() {
+            let case = r#"charset,UTF-8
+/*
+surface0
+{
+}
+*/
+surface0
+{
+/* a
+b */
+element0,overlay,body0.png,0,0
+element0,overlay,face0.png,0,0
+}
+sakura.surface.alias
+{
+通常,[0]
+/**/
+通常,[9]
+}
+"#;
+            let result: Vec<(String, usize)> = lint_str(case)
+                .iter()
+                .map(|v| (v.code().clone(), *v.line()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    ("duplicate-element".to_string(), 12),
+                    ("duplicate-alias".to_string(), 18),
+                    ("undefined-alias-surface".to_string(), 18),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_brace_layouts() {
+            let case = r#"charset,UTF-8
+sakura.surface.alias { 通常,[0] }
+surface0 {
+element0,overlay,body0.png,0,0
+element0,overlay,face0.png,0,0
+}
+descript {}
+descript { version,2 }
+"#;
+            let result: Vec<(String, usize)> = lint_str(case)
+                .iter()
+                .map(|v| (v.code().clone(), *v.line()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    ("duplicate-element".to_string(), 5),
+                    ("duplicate-descript".to_string(), 8),
+                    ("unknown-version".to_string(), 8),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_alias_duplicated_across_braces() {
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\nsakura.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nkero.surface.alias\r\n{\r\n通常,[0]\r\n}\r\nchar0.surface.alias\r\n{\r\n通常,[0]\r\n}\r\n";
+            let result: Vec<(String, usize)> = lint_str(case)
+                .iter()
+                .map(|v| (v.code().clone(), *v.line()))
+                .collect();
+            assert_eq!(result, vec![("duplicate-alias".to_string(), 15)]);
+        }
+
+        #[test]
+        fn success_when_charset_missing() {
+            let case = "// comment\r\nsurface0\r\n{\r\nelement0,overlay,a.png,0,0\r\nelement0,overlay,b.png,0,0\r\n}\r\n";
+            let result: Vec<(String, usize)> = lint_str(case)
+                .iter()
+                .map(|v| (v.code().clone(), *v.line()))
+                .collect();
+            assert_eq!(
+                result,
+                vec![
+                    ("missing-charset".to_string(), 1),
+                    ("duplicate-element".to_string(), 5),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_error_str() {
+            let case =
+                "charset,UTF-8\r\nsurface0\r\n{\r\ncollisionex0,Head,polygon,0,0,10,0\r\n}\r\n";
+            let result = lint_str(case);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].severity(), &Severity::Error);
+            assert_eq!(result[0].code(), "invalid-polygon");
+            assert_eq!(result[0].line(), &4);
+        }
+
+        #[test]
+        fn success_when_invalid_str() {
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n}\r\n";
+            let result = lint_str(case);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].code(), "parse-error");
+            assert_eq!(result[0].line(), &5);
+
+            let result = lint_str("surface0\r\n{\r\n");
+            assert_eq!(result[0].code(), "parse-error");
+            assert_eq!(result[0].line(), &2);
+
+            let result = lint_str("charset,UTF-8\rsurface0\r{\r}\r}\r");
+            assert_eq!(result[0].line(), &5);
+            assert_eq!(result[0].message(), "failed to parse at `}`");
+        }
+    }
+
+    mod lint_decoded {
+        use shell_parser_common_rs::charset::Charset;
+
+        use super::*;
+
+        #[test]
+        fn success_when_mismatch() {
+            let case = "// header\r\ncharset,Shift_JIS\r\nsurface0\r\n{\r\n}\r\n";
+            let encoding = DetectedEncoding::new(Charset::UTF8, Some(Charset::ShiftJIS), false);
+            let result = lint_decoded(case, &encoding);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].severity(), &Severity::Warning);
+            assert_eq!(result[0].code(), "encoding-mismatch");
+            assert_eq!(result[0].line(), &2);
+        }
+
+        #[test]
+        fn success_when_declared_charset_matches() {
+            let case = "charset,Shift_JIS\r\nsurface0\r\n{\r\n}\r\n";
+            let encoding = DetectedEncoding::new(Charset::ShiftJIS, Some(Charset::ShiftJIS), false);
+            assert!(lint_decoded(case, &encoding).is_empty());
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Rewrites legacy animation lines into `animationN.` syntax.
+//!
+//! Legacy lines (`0interval,...`, `0pattern0,...`, `0option,...`) are converted one by one,
+//! and every other line is kept as it is.
+//! In legacy patterns, the surface comes before the method and the weight is in 10ms units:
+//!
+//! ```text
+//! 0pattern0,100,5,overlay,0,0
+//! animation0.pattern0,overlay,100,50,0,0
+//! ```
+
+use std::ops::Range;
+
+use shell_parser_common_rs::ShellParseError;
+
+use crate::{
+    ast::{Brace, LineContainer, SurfaceInner},
+    layout::{body_range, brace_lines_len, first_line_offset, leading_lines_len, numbered},
+    parse::{failed_line, line_starts, parse, ParseOptions},
+};
+
+/// A legacy line which could not be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnconvertedLine {
+    line: usize,
+    text: String,
+}
+
+impl UnconvertedLine {
+    pub fn new(line: usize, text: String) -> UnconvertedLine {
+        UnconvertedLine { line, text }
+    }
+
+    /// 1-based line number.
+    pub fn line(&self) -> &usize {
+        &self.line
+    }
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+}
+
+/// Result of [`migrate_str`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    output: String,
+    converted: Vec<usize>,
+    unconverted: Vec<UnconvertedLine>,
+}
+
+impl Migration {
+    pub fn output(&self) -> &String {
+        &self.output
+    }
+    /// 1-based line numbers of converted lines.
+    pub fn converted(&self) -> &Vec<usize> {
+        &self.converted
+    }
+    pub fn unconverted(&self) -> &Vec<UnconvertedLine> {
+        &self.unconverted
+    }
+}
+
+/// Error of [`migrate_str`] when the input is not parsed.
+#[derive(Debug, PartialEq)]
+pub struct MigrateError {
+    error: nom::Err<ShellParseError>,
+    line: usize,
+}
+
+impl MigrateError {
+    pub fn error(&self) -> &nom::Err<ShellParseError> {
+        &self.error
+    }
+    /// 1-based line number at which parsing stops.
+    pub fn line(&self) -> &usize {
+        &self.line
+    }
+}
+
+/// Rewrites legacy animation lines in `input`.
+///
+/// Only lines parsed inside `surface` and `surface.append` braces are looked at, so lines in
+/// other braces and in block comments are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::migrate::migrate_str;
+///
+/// let case = "charset,UTF-8\r\nsurface0\r\n{\r\n0interval,sometimes\r\n0pattern0,100,5,overlay,0,0\r\n0pattern1,100,5,unknown,0,0\r\n}\r\n";
+///
+/// let migration = migrate_str(case).unwrap();
+/// assert_eq!(
+///     migration.output(),
+///     "charset,UTF-8\r\nsurface0\r\n{\r\nanimation0.interval,sometimes\r\nanimation0.pattern0,overlay,100,50,0,0\r\n0pattern1,100,5,unknown,0,0\r\n}\r\n"
+/// );
+/// assert_eq!(migration.converted(), &vec![4, 5]);
+/// assert_eq!(migration.unconverted()[0].line(), &6);
+/// ```
+pub fn migrate_str(input: &str) -> Result<Migration, MigrateError> {
+    let shell_surfaces = parse(input).map_err(|error| MigrateError {
+        error,
+        line: failed_line(input, &ParseOptions::default()).unwrap_or(1),
+    })?;
+    let starts = line_starts(input);
+    let line_text = |line: usize| {
+        let start = starts[line];
+        let end = starts.get(line + 1).copied().unwrap_or(input.len());
+        (start, input[start..end].trim_end_matches(['\r', '\n']))
+    };
+
+    let mut replaced: Vec<(Range<usize>, String)> = Vec::new();
+    let mut converted = Vec::new();
+    let mut unconverted = Vec::new();
+
+    let mut line = leading_lines_len(&shell_surfaces);
+    for brace in shell_surfaces.braces() {
+        line += brace.header_comments().len();
+        let lines = match brace.body() {
+            Brace::Surface(v) => v.lines(),
+            Brace::SurfaceAppend(v) => v.lines(),
+            _ => {
+                line += brace_lines_len(brace);
+                continue;
+            }
+        };
+
+        let first_line = line + first_line_offset(brace.layout());
+        for (i, inner) in numbered(lines, first_line) {
+            let (start, text) = line_text(i);
+            match inner {
+                LineContainer::Body(
+                    v @ (SurfaceInner::AnimationInterval(_)
+                    | SurfaceInner::AnimationPattern(_)
+                    | SurfaceInner::AnimationOption(_)),
+                    format,
+                ) => {
+                    let body = body_range(text, brace.layout(), format.comment().as_deref());
+                    if text[body.clone()].starts_with(|c: char| c.is_ascii_digit()) {
+                        replaced.push((start + body.start..start + body.end, v.to_string()));
+                        converted.push(i + 1);
+                    }
+                }
+                LineContainer::Comment(c) if is_legacy_animation_line(c.body().trim()) => {
+                    unconverted.push(UnconvertedLine::new(i + 1, c.body().trim().to_string()));
+                }
+                _ => {}
+            }
+        }
+        line += brace_lines_len(brace);
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut end = 0;
+    for (range, text) in replaced {
+        output.push_str(&input[end..range.start]);
+        output.push_str(&text);
+        end = range.end;
+    }
+    output.push_str(&input[end..]);
+
+    Ok(Migration {
+        output,
+        converted,
+        unconverted,
+    })
+}
+
+/// Whether `body` starts with `Ninterval`, `NpatternM` or `Noption`, in any case.
+fn is_legacy_animation_line(body: &str) -> bool {
+    let rest = body.trim_start_matches(|c: char| c.is_ascii_digit());
+    let starts_with = |name: &str| {
+        rest.get(..name.len())
+            .is_some_and(|v| v.eq_ignore_ascii_case(name))
+    };
+    rest.len() != body.len()
+        && (starts_with("interval") || starts_with("pattern") || starts_with("option"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod migrate_str {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+// 0pattern0 outside braces is not converted
+surface0
+{
+  0interval,periodic,5
+	1pattern0,-1,10,overlay,20,-30
+1pattern1,0,0,alternativestart,[2,3]
+2option,exclusive
+animation3.pattern0,overlay,100,50,0,0
+element0,overlay,body0.png,0,0
+}
+"#;
+            let expected = r#"charset,UTF-8
+// 0pattern0 outside braces is not converted
+surface0
+{
+  animation0.interval,periodic,5
+	animation1.pattern0,overlay,-1,100,20,-30
+animation1.pattern1,alternativestart,(2,3)
+animation2.option,exclusive
+animation3.pattern0,overlay,100,50,0,0
+element0,overlay,body0.png,0,0
+}
+"#;
+            let result = migrate_str(case).unwrap();
+            assert_eq!(result.output(), expected);
+            assert_eq!(result.converted(), &vec![5, 6, 7, 8]);
+            assert!(result.unconverted().is_empty());
+            assert_eq!(parse(case).unwrap(), parse(expected).unwrap());
+        }
+
+        #[test]
+        fn success_when_unconvertible_str() {
+            let case =
+                "charset,UTF-8\r\nsurface0\r\n{\r\n0pattern0,100,5\r\n1interval,often\r\n}\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(result.output(), case);
+            assert!(result.converted().is_empty());
+            assert_eq!(
+                result.unconverted(),
+                &vec![
+                    UnconvertedLine::new(4, "0pattern0,100,5".to_string()),
+                    UnconvertedLine::new(5, "1interval,often".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_trailing_comment() {
+            let case = "surface0\r\n{\r\n0interval,always // blink\r\n}\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(
+                result.output(),
+                "surface0\r\n{\r\nanimation0.interval,always // blink\r\n}\r\n"
+            );
+            assert_eq!(result.converted(), &vec![3]);
+        }
+
+        #[test]
+        fn success_when_brace_on_name_line() {
+            let case = "surface0 {\r\n\t0interval,always // keep\r\n}\r\nsurface.append1 {\r\n2option,exclusive\r\n}\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(
+                result.output(),
+                "surface0 {\r\n\tanimation0.interval,always // keep\r\n}\r\nsurface.append1 {\r\nanimation2.option,exclusive\r\n}\r\n"
+            );
+            assert_eq!(result.converted(), &vec![2, 5]);
+        }
+
+        #[test]
+        fn success_when_one_line_brace() {
+            let case =
+                "surface0 { 0pattern0,100,5,overlay,0,0 }\r\nsurface1 { 1interval,often }\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(
+                result.output(),
+                "surface0 { animation0.pattern0,overlay,100,50,0,0 }\r\nsurface1 { 1interval,often }\r\n"
+            );
+            assert_eq!(result.converted(), &vec![1]);
+            assert_eq!(
+                result.unconverted(),
+                &vec![UnconvertedLine::new(2, "1interval,often".to_string())]
+            );
+        }
+
+        #[test]
+        fn success_when_block_comment() {
+            let case = "/*\r\nsurface0\r\n{\r\n0interval,always\r\n}\r\n*/\r\nsurface1\r\n{\r\n/* 1interval,never\r\n1option,exclusive */\r\n1Interval,never\r\n}\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(
+                result.output(),
+                &case.replace("1Interval,never", "animation1.interval,never")
+            );
+            assert_eq!(result.converted(), &vec![11]);
+            assert!(result.unconverted().is_empty());
+        }
+
+        #[test]
+        fn success_when_other_brace() {
+            let case = "descript\r\n{\r\n0interval,always\r\n}\r\n";
+            let result = migrate_str(case).unwrap();
+            assert_eq!(result.output(), case);
+            assert!(result.converted().is_empty());
+            assert!(result.unconverted().is_empty());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let result = migrate_str("surface0\r\n{\r\n0interval,always\r\n").unwrap_err();
+            assert_eq!(result.line(), &2);
+        }
+    }
+
+    mod is_legacy_animation_line {
+        use super::*;
+
+        #[test]
+        fn success_when_legacy_line() {
+            assert!(is_legacy_animation_line("0interval,always"));
+            assert!(is_legacy_animation_line("12pattern3,0,1,overlay,0,0"));
+            assert!(is_legacy_animation_line("5option,background"));
+            assert!(is_legacy_animation_line("0Interval,always"));
+        }
+
+        #[test]
+        fn failed_when_other_line() {
+            assert!(!is_legacy_animation_line("animation0.interval,always"));
+            assert!(!is_legacy_animation_line("pattern0,1"));
+            assert!(!is_legacy_animation_line("0collision,1"));
+        }
+    }
+}
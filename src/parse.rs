@@ -1,29 +1,40 @@
 //! Parses a [`ShellSurfaces`] from `&str`.
 //!
 //! [`ShellSurfaces`]: crate::ast::ShellSurfaces
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+};
 
 use nom::{
     branch::alt,
-    combinator::{eof, map},
+    character::complete::{one_of, space0},
+    combinator::{eof, map, opt, value},
     multi::many0,
     sequence::{preceded, terminated, tuple},
     IResult,
 };
-use shell_parser_common_rs::ShellParseError;
+use shell_parser_common_rs::{charset::Charset, ShellParseError};
 
-use crate::{BraceContainer, ShellSurfaces};
+use crate::{
+    display::charset_name,
+    dressup::BindDescript,
+    encoding::{decode_detected, DetectedEncoding},
+    Brace, BraceContainer, ShellSurfaces,
+};
 
 use self::{
+    bind_group::bind_descript,
     charset::charset,
-    cursor::brace_shell_surfaces_cursor,
-    descript::brace_descript,
-    parts::{header_comments_func, whole_line_as_comment_line},
-    surface::{brace_surface, brace_surface_append},
-    surface_alias::brace_surface_alias,
-    tooltip::brace_tooltip,
+    cursor::{brace_shell_surfaces_cursor, shell_surfaces_cursor_name},
+    descript::{brace_descript, descript_name},
+    parts::{block_comment_lines, header_comments_func, whole_line_as_comment_line},
+    surface::{brace_surface, brace_surface_append, surface_append_name, surface_name},
+    surface_alias::{brace_surface_alias, surface_alias_name},
+    tooltip::{brace_tooltip, tooltip_name},
 };
 
+mod bind_group;
 mod charset;
 mod cursor;
 mod descript;
@@ -32,7 +43,12 @@ mod surface;
 mod surface_alias;
 mod tooltip;
 
-/// Decodes bytes to `Cow<'a, str>` from specified charset.
+/// Decodes bytes to `Cow<'a, str>` from the charset declared in them.
+///
+/// Fails when the `charset` line is missing. Use [`decode_detected`] to skip a UTF-8 BOM and
+/// sniff the encoding instead.
+///
+/// [`decode_detected`]: crate::encoding::decode_detected
 ///
 /// # Examples
 ///
@@ -71,14 +87,70 @@ mod tooltip;
 /// ```
 pub fn decode_bytes<'a>(input: &'a [u8]) -> Result<Cow<'a, str>, String> {
     let temp_str = String::from_utf8_lossy(input);
-    let (_remain, charset) = match preceded(header_comments_func(charset), charset)(&temp_str) {
+    let options = ParseOptions::default();
+    let (_remain, charset) = match preceded(
+        header_comments_func::<_, _, &str>(charset(&options)),
+        charset(&options),
+    )(&temp_str)
+    {
         Ok(v) => v,
         Err(e) => return Err(e.to_string()),
     };
 
     match charset.decode(input) {
         Ok(v) => Ok(v),
-        Err(_) => Err(format!("Encoding failed: to {:?}", charset)),
+        Err(_) => Err(format!("Encoding failed: to {}", charset_name(&charset))),
+    }
+}
+
+/// Parses a [`ShellSurfaces`] from bytes, detecting their encoding.
+///
+/// Returns the detected encoding alongside, where a declared charset not matching the bytes is
+/// reported by [`DetectedEncoding::is_mismatch`].
+///
+/// [`ShellSurfaces`]: crate::ast::ShellSurfaces
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::parse_bytes;
+///
+/// let case = "\u{FEFF}charset,UTF-8\r\nsurface0\r\n{\r\nelement0,base,通常.png,0,0\r\n}\r\n";
+///
+/// let (shell_surfaces, encoding) = parse_bytes(case.as_bytes()).unwrap();
+/// assert_eq!(shell_surfaces.braces().len(), 1);
+/// assert_eq!(encoding.charset(), &Charset::UTF8);
+/// assert!(encoding.bom());
+/// assert!(!encoding.is_mismatch());
+/// ```
+pub fn parse_bytes(input: &[u8]) -> Result<(ShellSurfaces, DetectedEncoding), ParseBytesError> {
+    let (content, encoding) = decode_detected(input).map_err(ParseBytesError::Decode)?;
+    let options = ParseOptions::new().with_default_charset(encoding.charset().clone());
+    match parse_with_options(&content, &options) {
+        Ok(v) => Ok((v, encoding)),
+        Err(e) => Err(ParseBytesError::Parse(
+            e,
+            failed_line(&content, &options).unwrap_or(1),
+        )),
+    }
+}
+
+/// Error of [`parse_bytes`].
+#[derive(Debug, PartialEq)]
+pub enum ParseBytesError {
+    /// The bytes are not in any detected encoding.
+    Decode(String),
+    /// The decoded text is not parsed, with the 1-based line at which parsing stops.
+    Parse(nom::Err<ShellParseError>, usize),
+}
+
+impl Display for ParseBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBytesError::Decode(e) => write!(f, "{}", e),
+            ParseBytesError::Parse(_, line) => write!(f, "Parsing failed: at line {}", line),
+        }
     }
 }
 
@@ -121,36 +193,340 @@ pub fn decode_bytes<'a>(input: &'a [u8]) -> Result<Cow<'a, str>, String> {
 ///  assert_eq!(shell_surfaces.braces().len(), 2);
 /// ```
 pub fn parse<'a>(input: &'a str) -> Result<ShellSurfaces, nom::Err<ShellParseError>> {
-    shell_surfaces(input).map(|(_, v)| v)
+    parse_with_options(input, &ParseOptions::default())
+}
+
+/// Options of [`parse_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    default_charset: Charset,
+    case_insensitive_keywords: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> ParseOptions {
+        ParseOptions {
+            default_charset: Charset::ShiftJIS,
+            case_insensitive_keywords: true,
+        }
+    }
+
+    /// Charset assumed when the `charset` line is missing. Shift_JIS as the baseware by default.
+    pub fn default_charset(&self) -> &Charset {
+        &self.default_charset
+    }
+    pub fn with_default_charset(mut self, charset: Charset) -> ParseOptions {
+        self.default_charset = charset;
+        self
+    }
+
+    /// Whether keywords such as `overlay`, `surface` and `charset` are matched ignoring ASCII
+    /// case, as the baseware does. `true` by default.
+    ///
+    /// File names, collision ids and other values keep their case either way.
+    pub fn case_insensitive_keywords(&self) -> &bool {
+        &self.case_insensitive_keywords
+    }
+    pub fn with_case_insensitive_keywords(mut self, value: bool) -> ParseOptions {
+        self.case_insensitive_keywords = value;
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::new()
+    }
+}
+
+/// Parses a [`ShellSurfaces`] from `&str` with `options`.
+///
+/// The `charset` line may be omitted, and then [`ParseOptions::default_charset`] is assumed.
+/// [`ShellSurfaces::charset_declared`] tells which is the case.
+///
+/// Keywords are written in lower case by `Display`, whatever case they were parsed in.
+///
+/// [`ShellSurfaces`]: crate::ast::ShellSurfaces
+/// [`ShellSurfaces::charset_declared`]: crate::ast::ShellSurfaces::charset_declared
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::{parse_with_options, ParseOptions};
+///
+/// let case = "// no charset\r\nsurface0\r\n{\r\n}\r\n";
+/// let options = ParseOptions::new().with_default_charset(Charset::UTF8);
+///
+/// let shell_surfaces = parse_with_options(case, &options).unwrap();
+/// assert_eq!(shell_surfaces.charset(), &Charset::UTF8);
+/// assert_eq!(shell_surfaces.charset_declared(), &false);
+/// assert_eq!(shell_surfaces.to_string(), case);
+/// ```
+pub fn parse_with_options<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<ShellSurfaces, nom::Err<ShellParseError>> {
+    shell_surfaces(options)(input).map(|(_, v)| assume_charset(v, options))
 }
 
-fn shell_surfaces<'a>(input: &'a str) -> IResult<&'a str, ShellSurfaces, ShellParseError> {
+/// Parses a [`ShellSurfaces`] whose strings borrow from `input`.
+///
+/// Same as [`parse`] without allocating a `String` for each comment, file name and collision id.
+/// Use [`IntoOwned`] to convert the result to the owned form.
+///
+/// [`ShellSurfaces`]: crate::ast::ShellSurfaces
+/// [`IntoOwned`]: crate::ast::IntoOwned
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{parse, parse_borrowed, Brace, IntoOwned, SurfaceInner};
+///
+/// let case = "charset,UTF-8\r\nsurface0\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n";
+///
+/// let shell_surfaces = parse_borrowed(case).unwrap();
+/// let filename = match shell_surfaces.braces()[0].body() {
+///     Brace::Surface(s) => match s.lines()[0].body() {
+///         Some(SurfaceInner::Element(e)) => *e.filename(),
+///         _ => unreachable!(),
+///     },
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(filename, "body0.png");
+///
+/// assert_eq!(shell_surfaces.into_owned(), parse(case).unwrap());
+/// ```
+pub fn parse_borrowed<'a>(
+    input: &'a str,
+) -> Result<ShellSurfaces<&'a str>, nom::Err<ShellParseError>> {
+    parse_borrowed_with_options(input, &ParseOptions::default())
+}
+
+/// Parses a [`ShellSurfaces`] whose strings borrow from `input`, with `options`.
+///
+/// Same as [`parse_with_options`] without allocating a `String` for each comment, file name
+/// and collision id.
+///
+/// [`ShellSurfaces`]: crate::ast::ShellSurfaces
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::{
+///     parse_borrowed_with_options, parse_with_options, IntoOwned, ParseOptions,
+/// };
+///
+/// let case = "surface0\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n";
+/// let options = ParseOptions::new().with_default_charset(Charset::UTF8);
+///
+/// let shell_surfaces = parse_borrowed_with_options(case, &options).unwrap();
+/// assert_eq!(shell_surfaces.charset(), &Charset::UTF8);
+/// assert_eq!(
+///     shell_surfaces.into_owned(),
+///     parse_with_options(case, &options).unwrap()
+/// );
+/// ```
+pub fn parse_borrowed_with_options<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<ShellSurfaces<&'a str>, nom::Err<ShellParseError>> {
+    shell_surfaces(options)(input).map(|(_, v)| assume_charset(v, options))
+}
+
+/// Parses bind group definitions from the content of `descript.txt`.
+///
+/// Lines other than `bindgroup` and `bindoption` are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{parse_bind_descript, SurfaceTargetCharacterId};
+///
+/// let case = r#"charset,UTF-8
+/// sakura.bindgroup20.name,帽子,麦わら帽子
+/// sakura.bindgroup20.default,1
+/// "#;
+///
+/// let descript = parse_bind_descript(case);
+/// let group = descript.groups().get(0).unwrap();
+/// assert_eq!(group.character(), &SurfaceTargetCharacterId::Sakura);
+/// assert_eq!(group.part(), "麦わら帽子");
+/// assert_eq!(group.default(), &true);
+/// ```
+pub fn parse_bind_descript(input: &str) -> BindDescript {
+    bind_descript(input, &ParseOptions::default())
+}
+
+/// Parses `input` consisting only of braces.
+pub(crate) fn parse_braces(input: &str, options: &ParseOptions) -> Option<Vec<BraceContainer>> {
+    terminated(braces(options), eof)(input).ok().map(|(_, v)| v)
+}
+
+/// Returns the 1-based line number at which [`parse`] stops, or `None` when `input` is parsed.
+pub(crate) fn failed_line(input: &str, options: &ParseOptions) -> Option<usize> {
+    let remain = match tuple((
+        opt(tuple((
+            header_comments_func::<_, _, &str>(charset(options)),
+            charset(options),
+        ))),
+        braces::<&str>(options),
+    ))(input)
+    {
+        Ok((remain, _)) => match many0(whole_line_as_comment_line::<&str>)(remain) {
+            Ok(("", _)) => return None,
+            Ok((remain, _)) => remain,
+            Err(_) => remain,
+        },
+        Err(_) => input,
+    };
+    let consumed = &input[..input.len() - remain.len()];
+    Some(line_starts(consumed).len())
+}
+
+/// Returns byte offsets where lines start, with `\r\n`, `\r` and `\n` as newlines.
+pub(crate) fn line_starts(input: &str) -> Vec<usize> {
+    let bytes = input.as_bytes();
+    let mut starts = vec![0];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                i += 2;
+                starts.push(i);
+            }
+            b'\r' | b'\n' => {
+                i += 1;
+                starts.push(i);
+            }
+            _ => i += 1,
+        }
+    }
+    starts
+}
+
+/// Returns lines of `input` without their newlines, split at [`line_starts`].
+pub(crate) fn split_lines(input: &str) -> impl Iterator<Item = &str> {
+    let starts = line_starts(input);
+    (0..starts.len()).map(move |i| {
+        let end = starts.get(i + 1).copied().unwrap_or(input.len());
+        input[starts[i]..end].trim_end_matches(['\r', '\n'])
+    })
+}
+
+/// Returns the charset of the `charset` line in `input`, searched without decoding the lines
+/// before it.
+///
+/// The search stops at the first line starting a brace, with `{` on it or not.
+pub(crate) fn declared_charset(input: &[u8]) -> Option<Charset> {
+    let options = ParseOptions::default();
+    let mut remain = input;
+    while !remain.is_empty() {
+        let end = match remain.iter().position(|v| *v == b'\r' || *v == b'\n') {
+            Some(i) if remain[i..].starts_with(b"\r\n") => i + 2,
+            Some(i) => i + 1,
+            None => remain.len(),
+        };
+        let (line, next) = remain.split_at(end);
+        remain = next;
+
+        let line = match std::str::from_utf8(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if brace_header(&options)(line).is_ok() {
+            return None;
+        }
+        if let Ok((_, v)) = terminated(charset(&options), eof)(line) {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// A line starting a brace: its name followed by `{` or a newline, or `{` or `}` alone.
+fn brace_header<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (), ShellParseError> {
+    alt((
+        value((), descript_name(options)),
+        value((), surface_append_name(options)),
+        value((), surface_name(options)),
+        value((), surface_alias_name(options)),
+        value((), shell_surfaces_cursor_name(options)),
+        value((), tooltip_name(options)),
+        value((), tuple((space0, one_of("{}")))),
+    ))
+}
+
+fn assume_charset<S>(
+    mut shell_surfaces: ShellSurfaces<S>,
+    options: &ParseOptions,
+) -> ShellSurfaces<S> {
+    if !shell_surfaces.charset_declared() {
+        *shell_surfaces.charset_mut() = options.default_charset().clone();
+    }
+    shell_surfaces
+}
+
+/// Parses a [`ShellSurfaces`] whose charset is `Charset::Default` when the `charset` line is
+/// missing.
+fn shell_surfaces<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ShellSurfaces<S>, ShellParseError> {
     map(
         tuple((
-            header_comments_func(charset),
-            charset,
-            braces,
+            opt(tuple((
+                header_comments_func(charset(options)),
+                charset(options),
+            ))),
+            braces(options),
             terminated(many0(whole_line_as_comment_line), eof),
         )),
-        |(header_comments, c, b, footer_comments)| {
-            ShellSurfaces::new(header_comments, c, b, footer_comments)
+        |(header, b, footer_comments)| match header {
+            Some((header_comments, c)) => {
+                ShellSurfaces::new(header_comments, c, b, footer_comments)
+            }
+            None => {
+                let mut result = ShellSurfaces::new(vec![], Charset::Default, b, footer_comments);
+                *result.charset_declared_mut() = false;
+                result
+            }
         },
-    )(input)
+    )
 }
 
-fn braces<'a>(input: &'a str) -> IResult<&'a str, Vec<BraceContainer>, ShellParseError> {
-    many0(brace)(input)
+fn braces<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<BraceContainer<S>>, ShellParseError> {
+    many0(brace(options))
 }
 
-fn brace<'a>(input: &'a str) -> IResult<&'a str, BraceContainer, ShellParseError> {
+fn brace<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     alt((
-        brace_descript,
-        brace_surface,
-        brace_surface_append,
-        brace_surface_alias,
-        brace_shell_surfaces_cursor,
-        brace_tooltip,
-    ))(input)
+        brace_descript(options),
+        brace_surface(options),
+        brace_surface_append(options),
+        brace_surface_alias(options),
+        brace_shell_surfaces_cursor(options),
+        brace_tooltip(options),
+        brace_block_comment,
+    ))
+}
+
+fn brace_block_comment<'a, S: From<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
+    map(
+        tuple((
+            header_comments_func(block_comment_lines::<&str>),
+            block_comment_lines,
+        )),
+        |(header_comments, body)| BraceContainer::new(header_comments, Brace::BlockComment(body)),
+    )(input)
 }
 
 #[cfg(test)]
@@ -184,6 +560,16 @@ animation0.pattern3,overlay,-1,100,168,67
             let result = decode_bytes(&case).unwrap();
             assert_eq!(result, case_raw);
         }
+
+        #[test]
+        fn failed_when_charset_missing() {
+            let case = "// 通常\r\nsurface0\r\n{\r\n}\r\n".as_bytes();
+            assert!(decode_bytes(case).is_err());
+            assert!(decode_detected(case).is_ok());
+
+            let case = "\u{FEFF}charset,UTF-8\r\n".as_bytes();
+            assert!(decode_bytes(case).is_err());
+        }
     }
 
     mod parse {
@@ -268,13 +654,30 @@ collision3,154,311,248,362,Skirt
         }
 
         #[test]
-        fn failed_when_invalid_str() {
-            let case = r#""#;
-            assert!(parse(case).is_err());
+        fn success_when_charset_missing() {
+            let case = r#"// header
+descript
+{
+}"#;
+            let result = parse(case).unwrap();
+            assert_eq!(result.charset(), &Charset::ShiftJIS);
+            assert_eq!(result.charset_declared(), &false);
+            assert!(result.header_comments().is_empty());
+            assert_eq!(result.braces()[0].header_comments().len(), 1);
+
+            let result = parse("").unwrap();
+            assert_eq!(result.charset_declared(), &false);
+            assert!(result.braces().is_empty());
+
+            let result = parse("charset,UTF-8").unwrap();
+            assert_eq!(result.charset_declared(), &true);
+        }
 
+        #[test]
+        fn failed_when_invalid_str() {
             let case = r#"descript
 {
-}"#;
+"#;
             assert!(parse(case).is_err());
 
             let case = r#"
@@ -286,6 +689,154 @@ descript
         }
     }
 
+    mod parse_with_options {
+        use crate::Brace;
+        use shell_parser_common_rs::charset::Charset;
+
+        use super::*;
+
+        #[test]
+        fn success_when_charset_missing() {
+            let case = "surface0\r\n{\r\n}\r\n";
+            let options = ParseOptions::new().with_default_charset(Charset::EUCJP);
+            let result = parse_with_options(case, &options).unwrap();
+            assert_eq!(result.charset(), &Charset::EUCJP);
+            assert_eq!(result.charset_declared(), &false);
+            assert_eq!(result.to_string(), case);
+
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n";
+            let result = parse_with_options(case, &options).unwrap();
+            assert_eq!(result.charset(), &Charset::UTF8);
+            assert_eq!(result.charset_declared(), &true);
+        }
+
+        #[test]
+        fn success_when_keywords_in_other_case() {
+            let case = "Charset,utf-8\r\nDescript\r\n{\r\nVersion,1\r\n}\r\nSURFACE0\r\n{\r\nElement0,Overlay,Body.png,0,0\r\nAnimation0.Interval,Sometimes+Random,2\r\n}\r\nSakura.Cursor\r\n{\r\nMouseUp0,Head,system:hand\r\n}\r\n";
+            let expected = "charset,UTF-8\r\ndescript\r\n{\r\nversion,1\r\n}\r\nsurface0\r\n{\r\nelement0,overlay,Body.png,0,0\r\nanimation0.interval,sometimes+random,2\r\n}\r\nsakura.cursor\r\n{\r\nmouseup0,Head,system:hand\r\n}\r\n";
+            let result = parse_with_options(case, &ParseOptions::new()).unwrap();
+            assert_eq!(result, parse(expected).unwrap());
+            assert_eq!(result.to_string(), expected);
+        }
+
+        #[test]
+        fn failed_when_case_sensitive_keywords() {
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+
+            let case = "SURFACE0\r\n{\r\n}\r\n";
+            assert!(parse_with_options(case, &options).is_err());
+
+            let case = "surface0\r\n{\r\nelement0,Overlay,body.png,0,0\r\n}\r\n";
+            let result = parse_with_options(case, &options).unwrap();
+            match result.braces()[0].body() {
+                Brace::Surface(s) => assert!(s.lines()[0].body().is_none()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    mod parse_borrowed_with_options {
+        use crate::{Brace, IntoOwned};
+        use shell_parser_common_rs::charset::Charset;
+
+        use super::*;
+
+        #[test]
+        fn success_when_same_as_owned() {
+            let case = "Surface0\r\n{\r\nElement0,overlay,body.png,0,0\r\n}\r\n";
+            for options in [
+                ParseOptions::new(),
+                ParseOptions::new().with_default_charset(Charset::UTF8),
+            ] {
+                let result = parse_borrowed_with_options(case, &options).unwrap();
+                assert_eq!(
+                    result.into_owned(),
+                    parse_with_options(case, &options).unwrap()
+                );
+            }
+        }
+
+        #[test]
+        fn failed_when_case_sensitive_keywords() {
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+
+            let case = "SURFACE0\r\n{\r\n}\r\n";
+            assert!(parse_borrowed_with_options(case, &options).is_err());
+
+            let case = "surface0\r\n{\r\nelement0,Overlay,body.png,0,0\r\n}\r\n";
+            let result = parse_borrowed_with_options(case, &options).unwrap();
+            match result.braces()[0].body() {
+                Brace::Surface(s) => assert!(s.lines()[0].body().is_none()),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    mod parse_bytes {
+        use encoding_rs::SHIFT_JIS;
+        use shell_parser_common_rs::charset::Charset;
+
+        use super::*;
+
+        #[test]
+        fn success_when_valid_bytes() {
+            let (case, _, _) =
+                SHIFT_JIS.encode("// 通常\r\ncharset,Shift_JIS\r\nsurface0\r\n{\r\n}\r\n");
+            let (result, encoding) = parse_bytes(&case).unwrap();
+            assert_eq!(result.header_comments()[0].body(), "// 通常");
+            assert_eq!(
+                encoding,
+                DetectedEncoding::new(Charset::ShiftJIS, Some(Charset::ShiftJIS), false)
+            );
+
+            let case = "\u{FEFF}charset,Shift_JIS\r\nsurface0\r\n{\r\n// 通常\r\n}\r\n";
+            let (result, encoding) = parse_bytes(case.as_bytes()).unwrap();
+            assert_eq!(result.charset(), &Charset::ShiftJIS);
+            assert!(encoding.is_mismatch());
+        }
+
+        #[test]
+        fn failed_when_invalid_bytes() {
+            let result = parse_bytes(b"charset,UTF-8\r\nsurface0\r\n{\r\n").unwrap_err();
+            assert!(matches!(result, ParseBytesError::Parse(_, 3)));
+            assert_eq!(result.to_string(), "Parsing failed: at line 3");
+            assert!(matches!(
+                parse_bytes(b"charset,UTF-8\r\n\xff\xff\r\n"),
+                Err(ParseBytesError::Decode(_))
+            ));
+        }
+    }
+
+    mod parse_borrowed {
+        use std::{fs, path::PathBuf};
+
+        use crate::{decode_bytes, IntoOwned};
+
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("test_target/surfaces/surfaces01.txt");
+            let buffer = fs::read(path).unwrap();
+            let case = decode_bytes(&buffer).unwrap();
+
+            let result = parse_borrowed(&case).unwrap();
+            assert_eq!(result.into_owned(), parse(&case).unwrap());
+
+            let case = "// header\r\ncharset,UTF-8\r\nsakura.tooltips\r\n{\r\nHead,頭\r\n}\r\n";
+            let result = parse_borrowed(case).unwrap();
+            assert_eq!(result.header_comments()[0].body(), &"// header");
+            assert_eq!(result.into_owned(), parse(case).unwrap());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "descript\r\n{\r\n";
+            assert!(parse_borrowed(case).is_err());
+        }
+    }
+
     mod shell_surfaces {
         use crate::Brace;
         use shell_parser_common_rs::charset::Charset;
@@ -337,7 +888,8 @@ collision3,154,311,248,362,Skirt
 }
 // aaa
 "#;
-            let (remain, result) = shell_surfaces(case).unwrap();
+            let (remain, result) =
+                shell_surfaces::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.charset(), &Charset::ShiftJIS);
             assert!(matches!(
@@ -362,7 +914,8 @@ collision3,154,311,248,362,Skirt
             ));
 
             let case = "charset,UTF-8";
-            let (remain, result) = shell_surfaces(case).unwrap();
+            let (remain, result) =
+                shell_surfaces::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.charset(), &Charset::UTF8);
             assert!(result.braces().is_empty());
@@ -370,20 +923,17 @@ collision3,154,311,248,362,Skirt
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = r#""#;
-            assert!(shell_surfaces(case).is_err());
-
             let case = r#"descript
 {
-}"#;
-            assert!(shell_surfaces(case).is_err());
+"#;
+            assert!(shell_surfaces::<String>(&ParseOptions::default())(case).is_err());
 
             let case = r#"
 charset,Shift_JIS
 descript
 {
 "#;
-            assert!(shell_surfaces(case).is_err());
+            assert!(shell_surfaces::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -403,7 +953,7 @@ descript
 surface0
 {
 }"#;
-            let (remain, result) = braces(case).unwrap();
+            let (remain, result) = braces::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.get(0).unwrap().body(), Brace::Descript(_)));
             assert!(matches!(result.get(1).unwrap().body(), Brace::Surface(_)));
@@ -411,47 +961,139 @@ surface0
     }
 
     mod brace {
-        use crate::Brace;
+        use crate::{BlockComment, Brace, CommentLine};
 
         use super::*;
 
         #[test]
         fn success_when_valid_str() {
             let case = "descript\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::Descript(_)));
 
             let case = "surface0\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::Surface(_)));
 
             let case = "surface.append0\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::SurfaceAppend(_)));
 
             let case = "sakura.surface.alias\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::SurfaceAlias(_)));
 
             let case = "sakura.cursor\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::Cursor(_)));
 
             let case = "sakura.tooltips\r\n{\r\n}\r\n";
-            let (remain, result) = brace(case).unwrap();
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert!(matches!(result.body(), Brace::Tooltip(_)));
+
+            let case = "// a\r\n/*\r\nsurface0\r\n{\r\n}\r\n*/\r\nsurface1\r\n{\r\n}\r\n";
+            let (remain, result) = brace::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "surface1\r\n{\r\n}\r\n");
+            assert_eq!(
+                result,
+                BraceContainer::new(
+                    vec![CommentLine::new("// a".to_string())],
+                    Brace::BlockComment(BlockComment::new(
+                        "\r\nsurface0\r\n{\r\n}\r\n".to_string()
+                    ))
+                )
+            );
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = "descript{}";
-            assert!(brace(case).is_err());
+            let case = "descript{";
+            assert!(brace::<String>(&ParseOptions::default())(case).is_err());
+        }
+    }
+
+    mod declared_charset {
+        use shell_parser_common_rs::charset::Charset;
+
+        use super::*;
+
+        #[test]
+        fn success_when_declared() {
+            let case = b"// \x92\xca\x8f\xed\r\n  charset,EUC-JP \r\nsurface0\r\n";
+            assert_eq!(declared_charset(case), Some(Charset::EUCJP));
+        }
+
+        #[test]
+        fn failed_when_not_declared() {
+            assert_eq!(
+                declared_charset(b"surface0\r\n{\r\ncharset,UTF-8\r\n}\r\n"),
+                None
+            );
+            assert_eq!(declared_charset(b"// charset,UTF-8\r\n"), None);
+        }
+
+        #[test]
+        fn failed_when_after_brace_name() {
+            assert_eq!(
+                declared_charset(b"surface0 {\r\ncharset,UTF-8\r\n}\r\n"),
+                None
+            );
+            assert_eq!(
+                declared_charset(b"descript { version,1 }\r\ncharset,UTF-8\r\n"),
+                None
+            );
+            assert_eq!(
+                declared_charset(b"surface0\r\n  {\r\ncharset,UTF-8\r\n}\r\n"),
+                None
+            );
+        }
+    }
+
+    mod failed_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n";
+            assert_eq!(failed_line(case, &ParseOptions::default()), None);
+        }
+
+        #[test]
+        fn success_when_invalid_str() {
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\n}\r\n}\r\n";
+            assert_eq!(failed_line(case, &ParseOptions::default()), Some(5));
+
+            let case = "// comment\r\nsurface0\r\n{\r\n";
+            assert_eq!(failed_line(case, &ParseOptions::default()), Some(3));
+
+            let case = "charset,UTF-8\rsurface0\r{\r}\r}\r";
+            assert_eq!(failed_line(case, &ParseOptions::default()), Some(5));
+        }
+    }
+
+    mod line_starts {
+        use super::*;
+
+        #[test]
+        fn success_when_mixed_newlines() {
+            assert_eq!(line_starts("a\r\nb\rc\nd"), vec![0, 3, 5, 7]);
+            assert_eq!(line_starts("a\n"), vec![0, 2]);
+        }
+    }
+
+    mod split_lines {
+        use super::*;
+
+        #[test]
+        fn success_when_mixed_newlines() {
+            let result: Vec<_> = split_lines("a\r\nb\rc\nd").collect();
+            assert_eq!(result, vec!["a", "b", "c", "d"]);
         }
     }
 }
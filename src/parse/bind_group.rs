@@ -0,0 +1,305 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space0,
+    combinator::{eof, map, opt},
+    multi::separated_list1,
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+use shell_parser_common_rs::ShellParseError;
+
+use crate::{
+    dressup::{BindDescript, BindGroup, BindOption, BindOptionKind},
+    parse::{split_lines, ParseOptions},
+    AnimationIdType, SurfaceTargetCharacterId,
+};
+
+use super::parts::{comma, digit, keyword, keyword_comma, surface_target_character_id, text_field};
+
+enum BindDescriptLineTemp {
+    Name(
+        SurfaceTargetCharacterId,
+        AnimationIdType,
+        String,
+        String,
+        Option<String>,
+    ),
+    Default(SurfaceTargetCharacterId, AnimationIdType, bool),
+    Option(BindOption),
+}
+
+pub(super) fn bind_descript(input: &str, options: &ParseOptions) -> BindDescript {
+    let mut line = bind_descript_line(options);
+    let temps: Vec<BindDescriptLineTemp> = split_lines(input)
+        .filter_map(|v| line(v).ok().map(|(_, v)| v))
+        .collect();
+
+    let mut groups: Vec<BindGroup> = Vec::new();
+    let mut bind_options = Vec::new();
+    for temp in temps.iter() {
+        match temp {
+            BindDescriptLineTemp::Name(character, id, category, part, thumbnail) => {
+                groups.retain(|v| !(v.character().index() == character.index() && v.id() == id));
+                groups.push(BindGroup::new(
+                    character.clone(),
+                    *id,
+                    category.clone(),
+                    part.clone(),
+                    thumbnail.clone(),
+                    false,
+                ));
+            }
+            BindDescriptLineTemp::Option(v) => bind_options.push(v.clone()),
+            BindDescriptLineTemp::Default(_, _, _) => {}
+        }
+    }
+    for temp in temps.iter() {
+        if let BindDescriptLineTemp::Default(character, id, default) = temp {
+            if let Some(group) = groups
+                .iter_mut()
+                .find(|v| v.character().index() == character.index() && v.id() == id)
+            {
+                *group.default_mut() = *default;
+            }
+        }
+    }
+
+    BindDescript::new(groups, bind_options)
+}
+
+fn bind_descript_line<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BindDescriptLineTemp, ShellParseError> {
+    preceded(
+        space0,
+        terminated(
+            alt((
+                bind_group_name(options),
+                bind_group_default(options),
+                bind_option_group(options),
+            )),
+            tuple((space0, eof)),
+        ),
+    )
+}
+
+fn bind_group_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BindDescriptLineTemp, ShellParseError> {
+    map(
+        tuple((
+            surface_target_character_id(options),
+            preceded(keyword(options, ".bindgroup"), digit),
+            preceded(keyword_comma(options, ".name"), text_field(",")),
+            preceded(comma, text_field(",")),
+            opt(preceded(comma, text_field(","))),
+        )),
+        |(character, id, category, part, thumbnail): (_, _, &'a str, &'a str, Option<&'a str>)| {
+            BindDescriptLineTemp::Name(
+                character,
+                id,
+                category.trim_end().to_string(),
+                part.trim_end().to_string(),
+                thumbnail.map(|v| v.trim_end().to_string()),
+            )
+        },
+    )
+}
+
+fn bind_group_default<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BindDescriptLineTemp, ShellParseError> {
+    map(
+        tuple((
+            surface_target_character_id(options),
+            preceded(keyword(options, ".bindgroup"), digit),
+            preceded(
+                keyword_comma(options, ".default"),
+                alt((map(tag("1"), |_| true), map(tag("0"), |_| false))),
+            ),
+        )),
+        |(character, id, default)| BindDescriptLineTemp::Default(character, id, default),
+    )
+}
+
+fn bind_option_group<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BindDescriptLineTemp, ShellParseError> {
+    map(
+        tuple((
+            surface_target_character_id(options),
+            preceded(keyword(options, ".bindoption"), digit::<u32>),
+            preceded(keyword_comma(options, ".group"), text_field(",")),
+            preceded(comma, separated_list1(tag("+"), bind_option_kind(options))),
+        )),
+        |(character, _, category, kinds)| {
+            BindDescriptLineTemp::Option(BindOption::new(
+                character,
+                category.trim_end().to_string(),
+                kinds,
+            ))
+        },
+    )
+}
+
+fn bind_option_kind<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BindOptionKind, ShellParseError> {
+    alt((
+        map(keyword(options, "multiple"), |_| BindOptionKind::Multiple),
+        map(keyword(options, "mustselect"), |_| {
+            BindOptionKind::MustSelect
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod bind_descript {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+name,Test Shell
+sakura.bindgroup20.name,帽子,麦わら帽子,hat.png
+sakura.bindgroup20.default,1
+sakura.bindgroup21.name,帽子,リボン
+kero.bindgroup0.name,アクセサリ,眼鏡
+kero.bindgroup0.default,0
+sakura.bindgroup99.default,1
+sakura.bindoption0.group,アクセサリ,multiple+mustselect
+"#;
+            let result = bind_descript(case, &ParseOptions::default());
+            assert_eq!(
+                result.groups(),
+                &vec![
+                    BindGroup::new(
+                        SurfaceTargetCharacterId::Sakura,
+                        20,
+                        "帽子".to_string(),
+                        "麦わら帽子".to_string(),
+                        Some("hat.png".to_string()),
+                        true
+                    ),
+                    BindGroup::new(
+                        SurfaceTargetCharacterId::Sakura,
+                        21,
+                        "帽子".to_string(),
+                        "リボン".to_string(),
+                        None,
+                        false
+                    ),
+                    BindGroup::new(
+                        SurfaceTargetCharacterId::Kero,
+                        0,
+                        "アクセサリ".to_string(),
+                        "眼鏡".to_string(),
+                        None,
+                        false
+                    ),
+                ]
+            );
+            assert_eq!(
+                result.options(),
+                &vec![BindOption::new(
+                    SurfaceTargetCharacterId::Sakura,
+                    "アクセサリ".to_string(),
+                    vec![BindOptionKind::Multiple, BindOptionKind::MustSelect]
+                )]
+            );
+        }
+
+        #[test]
+        fn success_when_character_written_as_char() {
+            let case = "sakura.bindgroup20.name,帽子,麦わら帽子\r\nchar0.bindgroup20.default,1\r\n";
+            let result = bind_descript(case, &ParseOptions::default());
+            assert_eq!(result.groups().len(), 1);
+            assert_eq!(result.groups()[0].default(), &true);
+        }
+
+        #[test]
+        fn success_when_cr_newlines_and_spaces_before_comma() {
+            let case = "sakura.bindgroup20.name,帽子 ,麦わら帽子\rsakura.bindoption0.group,帽子 ,multiple\r";
+            let result = bind_descript(case, &ParseOptions::default());
+            assert_eq!(result.groups().len(), 1);
+            assert_eq!(result.groups()[0].category(), "帽子");
+            assert_eq!(result.options().len(), 1);
+            assert_eq!(result.options()[0].category(), "帽子");
+        }
+
+        #[test]
+        fn success_when_empty_str() {
+            let result = bind_descript("", &ParseOptions::default());
+            assert!(result.groups().is_empty());
+            assert!(result.options().is_empty());
+        }
+    }
+
+    mod bind_group_name {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "char2.bindgroup3.name,服,制服,uniform.png";
+            let (remain, result) = bind_group_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert!(matches!(
+                result,
+                BindDescriptLineTemp::Name(SurfaceTargetCharacterId::Char(2), 3, c, p, Some(t))
+                    if c == "服" && p == "制服" && t == "uniform.png"
+            ));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "sakura.bindgroup3.name,服";
+            assert!(bind_group_name(&ParseOptions::default())(case).is_err());
+        }
+    }
+
+    mod bind_group_default {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "sakura.bindgroup3.default,1";
+            let (remain, result) = bind_group_default(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert!(matches!(
+                result,
+                BindDescriptLineTemp::Default(SurfaceTargetCharacterId::Sakura, 3, true)
+            ));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "sakura.bindgroup3.default,on";
+            assert!(bind_group_default(&ParseOptions::default())(case).is_err());
+        }
+    }
+
+    mod bind_option_group {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "sakura.bindoption0.group,帽子,mustselect";
+            let (remain, result) = bind_option_group(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert!(matches!(
+                result,
+                BindDescriptLineTemp::Option(v) if v.kinds() == &vec![BindOptionKind::MustSelect]
+            ));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "sakura.bindoption0.group,帽子";
+            assert!(bind_option_group(&ParseOptions::default())(case).is_err());
+        }
+    }
+}
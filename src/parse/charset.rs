@@ -1,27 +1,57 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::space0,
     combinator::eof,
+    error::{ErrorKind, ParseError},
     sequence::{preceded, terminated, tuple},
     IResult,
 };
-use shell_parser_common_rs::{
-    charset::{parse_charset, Charset},
-    ShellParseError,
+use shell_parser_common_rs::{charset::Charset, ShellParseError};
+
+use crate::{
+    display::{self, CHARSETS},
+    parse::ParseOptions,
 };
 
-use super::parts::newline_body;
+use super::parts::{keyword, keyword_comma, newline_body};
 
-pub(super) fn charset<'a>(input: &'a str) -> IResult<&'a str, Charset, ShellParseError> {
+pub(super) fn charset<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Charset, ShellParseError> {
     preceded(
         space0,
-        terminated(charset_body, tuple((space0, alt((newline_body, eof))))),
-    )(input)
+        terminated(
+            charset_body(options),
+            tuple((space0, alt((newline_body, eof)))),
+        ),
+    )
 }
 
-fn charset_body<'a>(input: &'a str) -> IResult<&'a str, Charset, ShellParseError> {
-    preceded(tag("charset,"), parse_charset)(input)
+fn charset_body<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Charset, ShellParseError> {
+    preceded(keyword_comma(options, "charset"), charset_name(options))
+}
+
+/// Names written by `Display`, matched as [`keyword`]s.
+fn charset_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Charset, ShellParseError> {
+    let mut names: Vec<_> = CHARSETS
+        .iter()
+        .map(|v| (v, keyword(options, display::charset_name(v))))
+        .collect();
+    move |input| {
+        for (charset, name) in names.iter_mut() {
+            if let Ok((remain, _)) = name(input) {
+                return Ok((remain, (*charset).clone()));
+            }
+        }
+        Err(nom::Err::Error(ShellParseError::from_error_kind(
+            input,
+            ErrorKind::Tag,
+        )))
+    }
 }
 
 #[cfg(test)]
@@ -34,12 +64,12 @@ mod tests {
         #[test]
         fn sucess_when_valid_str() {
             let case = r#"charset,Shift_JIS"#;
-            let (remain, result) = charset(case).unwrap();
+            let (remain, result) = charset(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, Charset::ShiftJIS);
 
             let case = "charset,UTF-8\r\n\r\n";
-            let (remain, result) = charset(case).unwrap();
+            let (remain, result) = charset(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, Charset::UTF8);
         }
@@ -50,13 +80,13 @@ mod tests {
 // かきくけこ
 
 "#;
-            assert!(charset(case).is_err());
+            assert!(charset(&ParseOptions::default())(case).is_err());
 
             let case = r#"surface0
 {
 }
 "#;
-            assert!(charset(case).is_err());
+            assert!(charset(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -66,7 +96,7 @@ mod tests {
         #[test]
         fn sucess_when_valid_str_ascii() {
             let case = "charset,ASCII\r\n";
-            let (remain, result) = charset_body(case).unwrap();
+            let (remain, result) = charset_body(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, Charset::ASCII);
         }
@@ -74,7 +104,7 @@ mod tests {
         #[test]
         fn sucess_when_valid_str_shift_jis() {
             let case = "charset,Shift_JIS\r\n";
-            let (remain, result) = charset_body(case).unwrap();
+            let (remain, result) = charset_body(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, Charset::ShiftJIS);
         }
@@ -82,7 +112,12 @@ mod tests {
         #[test]
         fn sucess_when_valid_str_utf_8() {
             let case = "charset,UTF-8\r\n";
-            let (remain, result) = charset_body(case).unwrap();
+            let (remain, result) = charset_body(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
+            assert_eq!(result, Charset::UTF8);
+
+            let case = "Charset,utf-8\r\n";
+            let (remain, result) = charset_body(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, Charset::UTF8);
         }
@@ -90,7 +125,21 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "charset,x76";
-            assert!(charset_body(case).is_err());
+            assert!(charset_body(&ParseOptions::default())(case).is_err());
+        }
+    }
+
+    mod charset_name {
+        use super::*;
+
+        #[test]
+        fn success_when_every_charset() {
+            for v in CHARSETS.iter() {
+                let case = display::charset_name(v);
+                let (remain, result) = charset_name(&ParseOptions::default())(case).unwrap();
+                assert_eq!(remain, "");
+                assert_eq!(&result, v);
+            }
         }
     }
 }
@@ -1,6 +1,5 @@
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag, take_until},
     combinator::map,
     sequence::{preceded, terminated, tuple},
     IResult,
@@ -9,67 +8,79 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{GestureKind, ShellSurfacesCursor, ShellSurfacesCursorGesture},
-    Brace, BraceContainer, SurfaceTargetCharacterId,
+    parse::ParseOptions,
+    Brace, BraceContainer, BraceLayout, SurfaceTargetCharacterId,
 };
 
 use super::parts::{
-    brace_name_func, digit, header_comments_func, inner_brace_func, surface_target_character_id,
+    brace_container, brace_name_func, comma, digit, header_comments_func, inner_brace_func,
+    keyword, surface_target_character_id, text_before_comment, text_field,
 };
 
-pub(super) fn brace_shell_surfaces_cursor<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_shell_surfaces_cursor<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
         tuple((
-            header_comments_func(shell_surfaces_cursor_name),
-            shell_surfaces_cursor,
+            header_comments_func(shell_surfaces_cursor_name(options)),
+            shell_surfaces_cursor(options),
         )),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::Cursor(body)),
-    )(input)
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::Cursor(body), layout)
+        },
+    )
 }
 
-fn shell_surfaces_cursor<'a>(
-    input: &'a str,
-) -> IResult<&'a str, ShellSurfacesCursor, ShellParseError> {
+fn shell_surfaces_cursor<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (ShellSurfacesCursor<S>, BraceLayout), ShellParseError>
+{
     map(
         tuple((
-            shell_surfaces_cursor_name,
-            inner_brace_func(shell_surfaces_cursor_define),
+            shell_surfaces_cursor_name(options),
+            inner_brace_func(shell_surfaces_cursor_define(options)),
         )),
-        |(id, lines)| ShellSurfacesCursor::new(id, lines),
-    )(input)
+        |(id, (layout, lines))| (ShellSurfacesCursor::new(id, lines), layout),
+    )
 }
 
-fn shell_surfaces_cursor_name<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
-    brace_name_func(terminated(surface_target_character_id, tag(".cursor")))(input)
+pub(super) fn shell_surfaces_cursor_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    brace_name_func(terminated(
+        surface_target_character_id(options),
+        keyword(options, ".cursor"),
+    ))
 }
 
-fn shell_surfaces_cursor_define<'a>(
-    input: &'a str,
-) -> IResult<&'a str, ShellSurfacesCursorGesture, ShellParseError> {
+fn shell_surfaces_cursor_define<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ShellSurfacesCursorGesture<S>, ShellParseError> {
     map(
         tuple((
-            gesture_kind,
+            gesture_kind(options),
             digit,
-            preceded(tag(","), take_until(",")),
-            preceded(tag(","), is_not("\r\n")),
+            preceded(comma, text_field(",\r\n")),
+            preceded(comma, text_before_comment),
         )),
         |(kind, id, collision, filename)| {
-            ShellSurfacesCursorGesture::new(kind, id, collision.to_string(), filename.to_string())
+            ShellSurfacesCursorGesture::new(kind, id, S::from(collision), S::from(filename))
         },
-    )(input)
+    )
 }
 
-fn gesture_kind<'a>(input: &'a str) -> IResult<&'a str, GestureKind, ShellParseError> {
+fn gesture_kind<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, GestureKind, ShellParseError> {
     alt((
-        map(tag("mouseup"), |_| GestureKind::MouseUp),
-        map(tag("mousedown"), |_| GestureKind::MouseDown),
-        map(tag("mouserightdown"), |_| GestureKind::MouseRightDown),
-        map(tag("mousewheel"), |_| GestureKind::MouseWheel),
-        map(tag("mousehover"), |_| GestureKind::MouseHover),
-    ))(input)
+        map(keyword(options, "mouseup"), |_| GestureKind::MouseUp),
+        map(keyword(options, "mousedown"), |_| GestureKind::MouseDown),
+        map(keyword(options, "mouserightdown"), |_| {
+            GestureKind::MouseRightDown
+        }),
+        map(keyword(options, "mousewheel"), |_| GestureKind::MouseWheel),
+        map(keyword(options, "mousehover"), |_| GestureKind::MouseHover),
+    ))
 }
 
 #[cfg(test)]
@@ -94,7 +105,8 @@ mouseup1,Bust,system:hand
 mousedown1,Bust,system:grip
 }
 "#;
-            let (remain, result) = brace_shell_surfaces_cursor(case).unwrap();
+            let (remain, result) =
+                brace_shell_surfaces_cursor(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -108,26 +120,26 @@ mousedown1,Bust,system:grip
                 &Brace::Cursor(ShellSurfacesCursor::new(
                     crate::ast::SurfaceTargetCharacterId::Sakura,
                     vec![
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseUp,
                             0,
                             "Head".to_string(),
                             "system:hand".to_string()
                         )),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseDown,
                             0,
                             "Head".to_string(),
                             "system:finger".to_string()
                         )),
                         LineContainer::Comment(CommentLine::new("".to_string())),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseUp,
                             1,
                             "Bust".to_string(),
                             "system:hand".to_string()
                         )),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseDown,
                             1,
                             "Bust".to_string(),
@@ -142,7 +154,7 @@ mousedown1,Bust,system:grip
         fn failed_when_invalid_str() {
             let case = r#"
 
-sakura.cursor {
+sakura.cursor x {
 mouseup0,Head,system:hand
 mousedown0,Head,system:finger
 
@@ -150,7 +162,7 @@ mouseup1,Bust,system:hand
 mousedown1,Bust,system:grip
 }
 "#;
-            assert!(brace_shell_surfaces_cursor(case).is_err());
+            assert!(brace_shell_surfaces_cursor::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -170,33 +182,34 @@ mouseup1,Bust,system:hand
 mousedown1,Bust,system:grip
 }
 "#;
-            let (remain, result) = shell_surfaces_cursor(case).unwrap();
+            let (remain, (result, _)) =
+                shell_surfaces_cursor(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
                 ShellSurfacesCursor::new(
                     crate::ast::SurfaceTargetCharacterId::Sakura,
                     vec![
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseUp,
                             0,
                             "Head".to_string(),
                             "system:hand".to_string()
                         )),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseDown,
                             0,
                             "Head".to_string(),
                             "system:finger".to_string()
                         )),
                         LineContainer::Comment(CommentLine::new("".to_string())),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseUp,
                             1,
                             "Bust".to_string(),
                             "system:hand".to_string()
                         )),
-                        LineContainer::Body(ShellSurfacesCursorGesture::new(
+                        LineContainer::body_line(ShellSurfacesCursorGesture::new(
                             GestureKind::MouseDown,
                             1,
                             "Bust".to_string(),
@@ -210,7 +223,7 @@ mousedown1,Bust,system:grip
         #[test]
         fn failed_when_invalid_str() {
             let case = r#"sakuracursor{}"#;
-            assert!(shell_surfaces_cursor(case).is_err());
+            assert!(shell_surfaces_cursor::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -220,15 +233,16 @@ mousedown1,Bust,system:grip
         #[test]
         fn success_when_valid_str() {
             let case = "sakura.cursor\r\n{";
-            let (remain, result) = shell_surfaces_cursor_name(case).unwrap();
-            assert_eq!(remain, "{");
+            let (remain, result) =
+                shell_surfaces_cursor_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n{");
             assert_eq!(result, SurfaceTargetCharacterId::Sakura);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "hoge.cursor\r\n";
-            assert!(shell_surfaces_cursor_name(case).is_err());
+            assert!(shell_surfaces_cursor_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -238,7 +252,8 @@ mousedown1,Bust,system:grip
         #[test]
         fn success_when_valid_str() {
             let case = "mouseup0,Head,system:hand";
-            let (remain, result) = shell_surfaces_cursor_define(case).unwrap();
+            let (remain, result) =
+                shell_surfaces_cursor_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -254,7 +269,9 @@ mousedown1,Bust,system:grip
         #[test]
         fn failed_when_invalid_str() {
             let case = "mouseup0,Head,\r\n";
-            assert!(shell_surfaces_cursor_define(case).is_err());
+            assert!(
+                shell_surfaces_cursor_define::<String>(&ParseOptions::default())(case).is_err()
+            );
         }
     }
 
@@ -264,27 +281,32 @@ mousedown1,Bust,system:grip
         #[test]
         fn success_when_valid_str() {
             let case = "mouseup";
-            let (remain, result) = gesture_kind(case).unwrap();
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, GestureKind::MouseUp);
 
             let case = "mousedown";
-            let (remain, result) = gesture_kind(case).unwrap();
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, GestureKind::MouseDown);
 
             let case = "mouserightdown";
-            let (remain, result) = gesture_kind(case).unwrap();
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, GestureKind::MouseRightDown);
 
             let case = "mousewheel";
-            let (remain, result) = gesture_kind(case).unwrap();
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, GestureKind::MouseWheel);
 
             let case = "mousehover";
-            let (remain, result) = gesture_kind(case).unwrap();
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, GestureKind::MouseHover);
+
+            let case = "MouseHover";
+            let (remain, result) = gesture_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, GestureKind::MouseHover);
         }
@@ -292,7 +314,11 @@ mousedown1,Bust,system:grip
         #[test]
         fn failed_when_invalid_str() {
             let case = "Mousehover";
-            assert!(gesture_kind(case).is_err());
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+            assert!(gesture_kind(&options)(case).is_err());
+
+            let case = "hover";
+            assert!(gesture_kind(&ParseOptions::default())(case).is_err());
         }
     }
 }
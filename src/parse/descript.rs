@@ -1,71 +1,118 @@
-use nom::{branch::alt, bytes::complete::tag, combinator::map, sequence::tuple, IResult};
+use nom::{branch::alt, combinator::map, sequence::tuple, IResult};
 use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{Descript, SortOrder},
-    Brace, BraceContainer, DescriptInner,
+    parse::ParseOptions,
+    Brace, BraceContainer, BraceLayout, DescriptInner,
 };
 
-use super::parts::{brace_name_func, digit, header_comments_func, inner_brace_func};
+use super::parts::{
+    brace_container, brace_name_func, digit, header_comments_func, inner_brace_func, keyword,
+    keyword_comma,
+};
 
-pub(super) fn brace_descript<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_descript<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
-        tuple((header_comments_func(descript_name), descript)),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::Descript(body)),
-    )(input)
+        tuple((
+            header_comments_func(descript_name(options)),
+            descript(options),
+        )),
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::Descript(body), layout)
+        },
+    )
 }
 
-fn descript<'a>(input: &'a str) -> IResult<&'a str, Descript, ShellParseError> {
+fn descript<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (Descript<S>, BraceLayout), ShellParseError> {
     map(
-        tuple((descript_name, inner_brace_func(descript_inner))),
-        |(_, lines)| Descript::new(lines),
-    )(input)
+        tuple((
+            descript_name(options),
+            inner_brace_func(descript_inner(options)),
+        )),
+        |(_, (layout, lines))| (Descript::new(lines), layout),
+    )
 }
 
-fn descript_name<'a>(input: &'a str) -> IResult<&'a str, &'a str, ShellParseError> {
-    brace_name_func(tag("descript"))(input)
+pub(super) fn descript_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    brace_name_func(keyword(options, "descript"))
 }
 
-fn descript_inner<'a>(input: &'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
-    alt((version, max_width, collision_sort, animation_sort))(input)
+fn descript_inner<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
+    alt((
+        version(options),
+        max_width(options),
+        collision_sort(options),
+        animation_sort(options),
+    ))
 }
 
-fn version<'a>(input: &'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
-    map(tuple((tag("version,"), digit)), |(_, v)| {
-        DescriptInner::Version(v)
-    })(input)
+fn version<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
+    map(
+        tuple((keyword_comma(options, "version"), digit)),
+        |(_, v)| DescriptInner::Version(v),
+    )
 }
 
-fn max_width<'a>(input: &'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
-    map(tuple((tag("maxwidth,"), digit)), |(_, v)| {
-        DescriptInner::MaxWidth(v)
-    })(input)
+fn max_width<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
+    map(
+        tuple((keyword_comma(options, "maxwidth"), digit)),
+        |(_, v)| DescriptInner::MaxWidth(v),
+    )
 }
 
-fn collision_sort<'a>(input: &'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
-    map(tuple((tag("collision-sort,"), sort_order)), |(_, v)| {
-        DescriptInner::CollistionSort(v)
-    })(input)
+fn collision_sort<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
+    map(
+        tuple((
+            keyword_comma(options, "collision-sort"),
+            sort_order(options),
+        )),
+        |(_, v)| DescriptInner::CollistionSort(v),
+    )
 }
 
-fn animation_sort<'a>(input: &'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
-    map(tuple((tag("animation-sort,"), sort_order)), |(_, v)| {
-        DescriptInner::AnimationSort(v)
-    })(input)
+fn animation_sort<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DescriptInner, ShellParseError> {
+    map(
+        tuple((
+            keyword_comma(options, "animation-sort"),
+            sort_order(options),
+        )),
+        |(_, v)| DescriptInner::AnimationSort(v),
+    )
 }
 
-fn sort_order<'a>(input: &'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
-    alt((sort_order_ascend, sort_order_descend))(input)
+fn sort_order<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
+    alt((sort_order_ascend(options), sort_order_descend(options)))
 }
 
-fn sort_order_ascend<'a>(input: &'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
-    map(tag("ascend"), |_| SortOrder::Ascend)(input)
+fn sort_order_ascend<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
+    map(keyword(options, "ascend"), |_| SortOrder::Ascend)
 }
 
-fn sort_order_descend<'a>(input: &'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
-    map(tag("descend"), |_| SortOrder::Descend)(input)
+fn sort_order_descend<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SortOrder, ShellParseError> {
+    map(keyword(options, "descend"), |_| SortOrder::Descend)
 }
 
 #[cfg(test)]
@@ -86,7 +133,7 @@ descript
         maxwidth,320
     }
 "#;
-            let (remain, result) = brace_descript(case).unwrap();
+            let (remain, result) = brace_descript(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -98,16 +145,16 @@ descript
             assert_eq!(
                 result.body(),
                 &Brace::Descript(Descript::new(vec![
-                    LineContainer::Body(DescriptInner::Version(1)),
-                    LineContainer::Body(DescriptInner::MaxWidth(320))
+                    LineContainer::body_line(DescriptInner::Version(1)),
+                    LineContainer::body_line(DescriptInner::MaxWidth(320))
                 ]))
             );
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = "descript{}";
-            assert!(brace_descript(case).is_err());
+            let case = "descript{";
+            assert!(brace_descript::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -124,13 +171,13 @@ descript
         maxwidth,320
     }
 "#;
-            let (remain, result) = descript(case).unwrap();
+            let (remain, (result, _)) = descript::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.lines(),
                 &vec![
-                    LineContainer::Body(DescriptInner::Version(1)),
-                    LineContainer::Body(DescriptInner::MaxWidth(320))
+                    LineContainer::body_line(DescriptInner::Version(1)),
+                    LineContainer::body_line(DescriptInner::MaxWidth(320))
                 ]
             );
 
@@ -142,15 +189,15 @@ collision-sort,ascend
 animation-sort,descend
 
     }"#;
-            let (remain, result) = descript(case).unwrap();
+            let (remain, (result, _)) = descript(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.lines(),
                 &vec![
-                    LineContainer::Body(DescriptInner::Version(1)),
-                    LineContainer::Body(DescriptInner::MaxWidth(320)),
-                    LineContainer::Body(DescriptInner::CollistionSort(SortOrder::Ascend)),
-                    LineContainer::Body(DescriptInner::AnimationSort(SortOrder::Descend)),
+                    LineContainer::body_line(DescriptInner::Version(1)),
+                    LineContainer::body_line(DescriptInner::MaxWidth(320)),
+                    LineContainer::body_line(DescriptInner::CollistionSort(SortOrder::Ascend)),
+                    LineContainer::body_line(DescriptInner::AnimationSort(SortOrder::Descend)),
                     LineContainer::Comment(CommentLine::new("".to_string())),
                 ]
             );
@@ -159,7 +206,7 @@ animation-sort,descend
 {
 }
 "#;
-            assert!(descript(case).is_ok());
+            assert!(descript::<String>(&ParseOptions::default())(case).is_ok());
         }
 
         #[test]
@@ -169,14 +216,14 @@ descript {
 version,1
 }
 "#;
-            assert!(descript(case).is_err());
+            assert!(descript::<String>(&ParseOptions::default())(case).is_err());
 
             let case = r#"
 descript
 {
 version,1}
 "#;
-            assert!(descript(case).is_err());
+            assert!(descript::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -186,15 +233,15 @@ version,1}
         #[test]
         fn success_when_valid_str() {
             let case = "descript\r\n{";
-            let (remain, result) = descript_name(case).unwrap();
-            assert_eq!(remain, "{");
+            let (remain, result) = descript_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n{");
             assert_eq!(result, "descript");
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "descipt";
-            assert!(descript_name(case).is_err());
+            assert!(descript_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -204,12 +251,12 @@ version,1}
         #[test]
         fn success_when_valid_str() {
             let case = "version,1\r\n";
-            let (remain, result) = version(case).unwrap();
+            let (remain, result) = version(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::Version(1));
 
             let case = "version,0";
-            let (remain, result) = version(case).unwrap();
+            let (remain, result) = version(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DescriptInner::Version(0));
         }
@@ -217,10 +264,10 @@ version,1}
         #[test]
         fn failed_when_invalid_str() {
             let case = "version,-1\r\n";
-            assert!(version(case).is_err());
+            assert!(version(&ParseOptions::default())(case).is_err());
 
             let case = "vertion,1\r\n";
-            assert!(version(case).is_err());
+            assert!(version(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -230,7 +277,7 @@ version,1}
         #[test]
         fn success_when_valid_str() {
             let case = "maxwidth,100\r\n";
-            let (remain, result) = max_width(case).unwrap();
+            let (remain, result) = max_width(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::MaxWidth(100));
         }
@@ -238,10 +285,10 @@ version,1}
         #[test]
         fn failed_when_invalid_str() {
             let case = "maxwidth,-1\r\n";
-            assert!(max_width(case).is_err());
+            assert!(max_width(&ParseOptions::default())(case).is_err());
 
             let case = "vertion,1\r\n";
-            assert!(max_width(case).is_err());
+            assert!(max_width(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -251,12 +298,12 @@ version,1}
         #[test]
         fn success_when_valid_str() {
             let case = "collision-sort,ascend\r\n";
-            let (remain, result) = collision_sort(case).unwrap();
+            let (remain, result) = collision_sort(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::CollistionSort(SortOrder::Ascend));
 
             let case = "collision-sort,descend\r\n";
-            let (remain, result) = collision_sort(case).unwrap();
+            let (remain, result) = collision_sort(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::CollistionSort(SortOrder::Descend));
         }
@@ -264,10 +311,10 @@ version,1}
         #[test]
         fn failed_when_invalid_str() {
             let case = "collision-sort,desssend\r\n";
-            assert!(collision_sort(case).is_err());
+            assert!(collision_sort(&ParseOptions::default())(case).is_err());
 
             let case = "vertion,1\r\n";
-            assert!(collision_sort(case).is_err());
+            assert!(collision_sort(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -277,12 +324,12 @@ version,1}
         #[test]
         fn success_when_valid_str() {
             let case = "animation-sort,ascend\r\n";
-            let (remain, result) = animation_sort(case).unwrap();
+            let (remain, result) = animation_sort(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::AnimationSort(SortOrder::Ascend));
 
             let case = "animation-sort,descend\r\n";
-            let (remain, result) = animation_sort(case).unwrap();
+            let (remain, result) = animation_sort(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DescriptInner::AnimationSort(SortOrder::Descend));
         }
@@ -290,10 +337,10 @@ version,1}
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation-sort,desssend\r\n";
-            assert!(animation_sort(case).is_err());
+            assert!(animation_sort(&ParseOptions::default())(case).is_err());
 
             let case = "vertion,1\r\n";
-            assert!(animation_sort(case).is_err());
+            assert!(animation_sort(&ParseOptions::default())(case).is_err());
         }
     }
 }
@@ -2,16 +2,36 @@ use std::str::FromStr;
 
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag},
+    bytes::complete::{is_not, tag, tag_no_case, take_until},
     character::complete::{digit1, space0},
-    combinator::{eof, map, map_res, not, opt},
+    combinator::{consumed, eof, map, map_res, not, opt, peek, recognize, value},
+    error::{ErrorKind, ParseError},
     multi::many0,
-    sequence::{preceded, terminated, tuple},
+    sequence::{delimited, pair, preceded, terminated, tuple},
     IResult,
 };
 use shell_parser_common_rs::ShellParseError;
 
-use crate::{CommentLine, LineContainer, SurfaceTargetCharacterId};
+use crate::{
+    parse::ParseOptions, BlockComment, Brace, BraceContainer, BraceLayout, CommentLine,
+    LineContainer, LineFormat, SurfaceTargetCharacterId,
+};
+
+/// A keyword such as `overlay` or `surface`, ignoring ASCII case unless disabled by
+/// [`ParseOptions::case_insensitive_keywords`].
+pub(super) fn keyword<'a>(
+    options: &ParseOptions,
+    name: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    let case_insensitive = *options.case_insensitive_keywords();
+    move |input| {
+        if case_insensitive {
+            tag_no_case(name)(input)
+        } else {
+            tag(name)(input)
+        }
+    }
+}
 
 pub(super) fn newline_body<'a>(input: &'a str) -> IResult<&'a str, &'a str, ShellParseError> {
     alt((tag("\r\n"), tag("\r"), tag("\n")))(input)
@@ -19,41 +39,115 @@ pub(super) fn newline_body<'a>(input: &'a str) -> IResult<&'a str, &'a str, Shel
 
 pub(super) fn whole_line_without_newline<'a>(
     input: &'a str,
-) -> IResult<&'a str, String, ShellParseError> {
-    map(
-        alt((
-            map(newline_body, |_v| ""),
-            map(
-                tuple((not(alt((tag("{"), tag("}")))), is_not("\r\n"), newline_body)),
-                |(_, v, _)| v,
-            ),
-        )),
-        |s| s.to_string(),
-    )(input)
+) -> IResult<&'a str, &'a str, ShellParseError> {
+    alt((
+        map(newline_body, |_v| ""),
+        map(
+            tuple((not(alt((tag("{"), tag("}")))), is_not("\r\n"), newline_body)),
+            |(_, v, _)| v,
+        ),
+    ))(input)
 }
 
-pub(super) fn whole_line_as_comment_line<'a>(
+pub(super) fn whole_line_as_comment_line<'a, S: From<&'a str>>(
     input: &'a str,
-) -> IResult<&'a str, CommentLine, ShellParseError> {
-    map(whole_line_without_newline, |v| CommentLine::new(v))(input)
+) -> IResult<&'a str, CommentLine<S>, ShellParseError> {
+    map(whole_line_without_newline, |v| CommentLine::new(S::from(v)))(input)
 }
 
-pub(super) fn parse_inner_line_func<'a, T, F>(
+pub(super) fn parse_inner_line_func<'a, T, F, S: From<&'a str>>(
     f: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, LineContainer<T>, ShellParseError>
+) -> impl FnMut(&'a str) -> IResult<&'a str, LineContainer<T, S>, ShellParseError>
 where
     F: FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>,
 {
     alt((
-        map(tuple((space0, f, space0, newline_body)), |(_, v, _, _)| {
-            LineContainer::Body(v)
-        }),
+        map(
+            tuple((
+                space0,
+                consumed(f),
+                space0,
+                opt(trailing_comment),
+                newline_body,
+            )),
+            |(_, (text, v), _, comment, _)| {
+                let raw = has_spaced_comma(text).then(|| S::from(text));
+                LineContainer::Body(v, LineFormat::new(raw, comment.map(S::from)))
+            },
+        ),
+        map(block_comment_lines, LineContainer::BlockComment),
         map(tuple((space0, whole_line_as_comment_line)), |(_, v)| {
             LineContainer::Comment(v)
         }),
     ))
 }
 
+/// `/* ... */` comment from the start of a line to the end of a line, which may be another line.
+pub(super) fn block_comment_lines<'a, S: From<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, BlockComment<S>, ShellParseError> {
+    map(
+        tuple((
+            space0,
+            delimited(tag("/*"), take_until("*/"), tag("*/")),
+            space0,
+            alt((newline_body, eof)),
+        )),
+        |(_, v, _, _)| BlockComment::new(S::from(v)),
+    )(input)
+}
+
+/// `//` comment after the body of a line, up to the end of the line.
+fn trailing_comment<'a>(input: &'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    recognize(pair(tag("//"), opt(is_not("\r\n"))))(input)
+}
+
+/// A free-text field up to the end of the line, or up to a `//` comment after spaces or tabs.
+///
+/// `//` not after spaces or tabs is part of the field, as in `http://`.
+pub(super) fn text_before_comment<'a>(
+    input: &'a str,
+) -> IResult<&'a str, &'a str, ShellParseError> {
+    let line = &input[..input.find(['\r', '\n']).unwrap_or(input.len())];
+    let end = line
+        .match_indices("//")
+        .map(|(i, _)| &line[..i])
+        .find(|v| v.ends_with([' ', '\t']))
+        .map_or(line.len(), |v| v.trim_end_matches([' ', '\t']).len());
+    if end == 0 {
+        return Err(nom::Err::Error(ShellParseError::from_error_kind(
+            input,
+            ErrorKind::IsNot,
+        )));
+    }
+    Ok((&input[end..], &input[..end]))
+}
+
+/// Whether `text` has spaces or tabs around a comma, which the canonical text does not.
+fn has_spaced_comma(text: &str) -> bool {
+    [", ", ",\t", " ,", "\t,"].iter().any(|v| text.contains(v))
+}
+
+/// A comma between fields, with optional spaces and tabs around it.
+pub(super) fn comma<'a>(input: &'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    delimited(space0, tag(","), space0)(input)
+}
+
+/// [`keyword`] `name` followed by a [`comma`].
+pub(super) fn keyword_comma<'a>(
+    options: &ParseOptions,
+    name: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    terminated(keyword(options, name), comma)
+}
+
+/// A free-text field up to one of `stop`, without trailing spaces and tabs.
+pub(super) fn text_field<'a>(
+    stop: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, ShellParseError> {
+    map(is_not(stop), |v: &'a str| v.trim_end_matches([' ', '\t']))
+}
+
 pub(super) fn digit<'a, T>(input: &'a str) -> IResult<&'a str, T, ShellParseError>
 where
     T: FromStr,
@@ -74,47 +168,61 @@ where
     })(input)
 }
 
-pub(super) fn boolean<'a>(input: &'a str) -> IResult<&'a str, bool, ShellParseError> {
-    alt((map(tag("true"), |_| true), map(tag("false"), |_| false)))(input)
+pub(super) fn boolean<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, bool, ShellParseError> {
+    alt((
+        map(keyword(options, "true"), |_| true),
+        map(keyword(options, "false"), |_| false),
+    ))
 }
 
 pub(super) fn surface_target_character_id<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
     alt((
-        surface_target_character_id_sakura,
-        surface_target_character_id_kero,
-        surface_target_character_id_char,
-    ))(input)
+        surface_target_character_id_sakura(options),
+        surface_target_character_id_kero(options),
+        surface_target_character_id_char(options),
+    ))
 }
 
 fn surface_target_character_id_sakura<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
-    map(tag("sakura"), |_| SurfaceTargetCharacterId::Sakura)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    map(keyword(options, "sakura"), |_| {
+        SurfaceTargetCharacterId::Sakura
+    })
 }
 
 fn surface_target_character_id_kero<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
-    map(tag("kero"), |_| SurfaceTargetCharacterId::Kero)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    map(keyword(options, "kero"), |_| SurfaceTargetCharacterId::Kero)
 }
 
 fn surface_target_character_id_char<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
-    map(tuple((tag("char"), digit)), |(_, v)| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    map(tuple((keyword(options, "char"), digit)), |(_, v)| {
         SurfaceTargetCharacterId::Char(v)
-    })(input)
+    })
 }
 
-pub(super) fn header_comments_func<'a, T, F>(
+pub(super) fn header_comments_func<'a, T, F, S: From<&'a str>>(
     f: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<CommentLine>, ShellParseError>
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<CommentLine<S>>, ShellParseError>
 where
     F: FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>,
 {
-    many0(map(tuple((not(f), whole_line_as_comment_line)), |(_, v)| v))
+    many0(map(
+        tuple((
+            not(f),
+            not(block_comment_lines::<&str>),
+            whole_line_as_comment_line,
+        )),
+        |(_, _, v)| v,
+    ))
 }
 
 pub(super) fn brace_name_func<'a, T, F>(
@@ -123,23 +231,84 @@ pub(super) fn brace_name_func<'a, T, F>(
 where
     F: FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>,
 {
-    preceded(space0, terminated(f, tuple((space0, newline_body))))
+    preceded(
+        space0,
+        terminated(f, tuple((space0, peek(alt((newline_body, tag("{"))))))),
+    )
 }
 
-pub(super) fn inner_brace_func<'a, T, F>(
-    f: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<LineContainer<T>>, ShellParseError>
+/// Lines between `{` and `}`, with how the braces are written.
+pub(super) type InnerBrace<T, S> = (BraceLayout, Vec<LineContainer<T, S>>);
+
+/// `{`, lines and `}` after a brace name, in any [`BraceLayout`].
+pub(super) fn inner_brace_func<'a, T, F, S: From<&'a str>>(
+    mut f: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, InnerBrace<T, S>, ShellParseError>
 where
     F: FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>,
 {
-    map(
-        tuple((
-            tuple((space0, tag("{"), space0, newline_body)),
-            many0(parse_inner_line_func(f)),
-            tuple((space0, tag("}"), space0, alt((newline_body, eof)))),
-        )),
-        |(_, v, _)| v,
-    )
+    move |input| {
+        if let Ok((remain, lines)) = one_line_brace(&mut f)(input) {
+            return Ok((remain, (BraceLayout::OneLine, lines)));
+        }
+        let (input, layout) = alt((
+            value(
+                BraceLayout::OwnLines,
+                tuple((space0, newline_body, space0, tag("{"))),
+            ),
+            value(BraceLayout::SameLine, tuple((space0, tag("{")))),
+        ))(input)?;
+        map(
+            tuple((
+                tuple((space0, newline_body)),
+                many0(parse_inner_line_func(&mut f)),
+                tuple((space0, tag("}"), space0, alt((newline_body, eof)))),
+            )),
+            move |(_, v, _)| (layout, v),
+        )(input)
+    }
+}
+
+/// `{ ... }` on one line, holding one line or nothing. A line not parsed by `f` is a comment.
+fn one_line_brace<'a, T, F, S: From<&'a str>>(
+    mut f: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<LineContainer<T, S>>, ShellParseError>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>,
+{
+    move |input| {
+        let (remain, (_, _, line, _)) =
+            tuple((space0, tag("{"), is_not("\r\n"), alt((newline_body, eof))))(input)?;
+        let inner = line
+            .trim_end_matches([' ', '\t'])
+            .strip_suffix('}')
+            .ok_or_else(|| {
+                nom::Err::Error(ShellParseError::from_error_kind(input, ErrorKind::Tag))
+            })?
+            .trim_matches([' ', '\t']);
+        if inner.is_empty() {
+            return Ok((remain, vec![]));
+        }
+        let line = match terminated(consumed(&mut f), eof)(inner) {
+            Ok((_, (text, v))) => {
+                let raw = has_spaced_comma(text).then(|| S::from(text));
+                LineContainer::Body(v, LineFormat::new(raw, None))
+            }
+            Err(_) => LineContainer::Comment(CommentLine::new(S::from(inner))),
+        };
+        Ok((remain, vec![line]))
+    }
+}
+
+/// A brace read with `layout`.
+pub(super) fn brace_container<S>(
+    header_comments: Vec<CommentLine<S>>,
+    body: Brace<S>,
+    layout: BraceLayout,
+) -> BraceContainer<S> {
+    let mut result = BraceContainer::new(header_comments, body);
+    *result.layout_mut() = layout;
+    result
 }
 
 #[cfg(test)]
@@ -222,7 +391,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "aaa";
-            assert!(whole_line_as_comment_line(case).is_err());
+            assert!(whole_line_as_comment_line::<String>(case).is_err());
         }
     }
 
@@ -237,12 +406,12 @@ mod tests {
             let case = "    abc    \r\naaa";
             let (remain, result) = case_func(case).unwrap();
             assert_eq!(remain, "aaa");
-            assert_eq!(result, LineContainer::Body("abc"));
+            assert_eq!(result, LineContainer::body_line("abc"));
 
             let case = "abc\r\n";
             let (remain, result) = case_func(case).unwrap();
             assert_eq!(remain, "");
-            assert_eq!(result, LineContainer::Body("abc"));
+            assert_eq!(result, LineContainer::body_line("abc"));
 
             let case = "    aaa    \r\nbbb";
             let (remain, result) = case_func(case).unwrap();
@@ -280,7 +449,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case_t_func = tag("abc");
-            let mut case_func = parse_inner_line_func(case_t_func);
+            let mut case_func = parse_inner_line_func::<&str, _, String>(case_t_func);
 
             let case = "abc";
             assert!(case_func(case).is_err());
@@ -291,6 +460,176 @@ mod tests {
             let case = "";
             assert!(case_func(case).is_err());
         }
+
+        #[test]
+        fn success_when_spaced_comma() {
+            let mut case_func = parse_inner_line_func(tuple((tag("a"), comma, tag("b"))));
+
+            let case = "  a , b  \r\n";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                LineContainer::Body(
+                    ("a", ",", "b"),
+                    LineFormat::new(Some("a , b".to_string()), None)
+                )
+            );
+
+            let case = "a,b\r\n";
+            let (_, result) = case_func(case).unwrap();
+            assert_eq!(result, LineContainer::body_line(("a", ",", "b")));
+        }
+
+        #[test]
+        fn success_when_trailing_comment() {
+            let mut case_func = parse_inner_line_func(tag("abc"));
+
+            let case = "  abc  // note \r\naaa";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "aaa");
+            assert_eq!(
+                result,
+                LineContainer::Body("abc", LineFormat::new(None, Some("// note ".to_string())))
+            );
+
+            let case = "abc//\r\n";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                LineContainer::Body("abc", LineFormat::new(None, Some("//".to_string())))
+            );
+
+            let case = "abc / note\r\n";
+            let (_, result) = case_func(case).unwrap();
+            assert_eq!(
+                result,
+                LineContainer::Comment(CommentLine::new("abc / note".to_string()))
+            );
+        }
+
+        #[test]
+        fn success_when_block_comment() {
+            let mut case_func = parse_inner_line_func(tag("abc"));
+
+            let case = "  /* abc\r\n}\r\n*/  \r\naaa";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "aaa");
+            assert_eq!(
+                result,
+                LineContainer::BlockComment(BlockComment::new(" abc\r\n}\r\n".to_string()))
+            );
+
+            let case = "/* abc */ abc\r\n";
+            let (_, result) = case_func(case).unwrap();
+            assert_eq!(
+                result,
+                LineContainer::Comment(CommentLine::new("/* abc */ abc".to_string()))
+            );
+        }
+    }
+
+    mod block_comment_lines {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "/**/";
+            let (remain, result) = block_comment_lines(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, BlockComment::new("".to_string()));
+
+            let case = "  /* a\n * b\n */\nc";
+            let (remain, result) = block_comment_lines(case).unwrap();
+            assert_eq!(remain, "c");
+            assert_eq!(result, BlockComment::new(" a\n * b\n ".to_string()));
+            assert_eq!(result.lines_len(), 3);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "/* a\n";
+            assert!(block_comment_lines::<String>(case).is_err());
+
+            let case = "// a\n";
+            assert!(block_comment_lines::<String>(case).is_err());
+        }
+    }
+
+    mod comma {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = ",abc";
+            let (remain, result) = comma(case).unwrap();
+            assert_eq!(remain, "abc");
+            assert_eq!(result, ",");
+
+            let case = " \t,\t abc";
+            let (remain, result) = comma(case).unwrap();
+            assert_eq!(remain, "abc");
+            assert_eq!(result, ",");
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = " abc";
+            assert!(comma(case).is_err());
+
+            let case = "\r\n,";
+            assert!(comma(case).is_err());
+        }
+    }
+
+    mod text_field {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "body0.png \t, 0";
+            let (remain, result) = text_field(",")(case).unwrap();
+            assert_eq!(remain, ", 0");
+            assert_eq!(result, "body0.png");
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = ",body0.png";
+            assert!(text_field(",")(case).is_err());
+        }
+    }
+
+    mod text_before_comment {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "怒ります。\r\n";
+            let (remain, result) = text_before_comment(case).unwrap();
+            assert_eq!(remain, "\r\n");
+            assert_eq!(result, "怒ります。");
+
+            let case = "怒ります。 \t// note\r\n";
+            let (remain, result) = text_before_comment(case).unwrap();
+            assert_eq!(remain, " \t// note\r\n");
+            assert_eq!(result, "怒ります。");
+
+            let case = "http://example.com/ a";
+            let (remain, result) = text_before_comment(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, "http://example.com/ a");
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "\r\n";
+            assert!(text_before_comment(case).is_err());
+
+            let case = " // note";
+            assert!(text_before_comment(case).is_err());
+        }
     }
 
     mod digit {
@@ -353,20 +692,29 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "true";
-            let (remain, result) = boolean(case).unwrap();
+            let (remain, result) = boolean(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, true);
 
             let case = "false";
-            let (remain, result) = boolean(case).unwrap();
+            let (remain, result) = boolean(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, false);
+
+            let case = "True";
+            let (remain, result) = boolean(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, true);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "True";
-            assert!(boolean(case).is_err());
+            let options = ParseOptions::new().with_case_insensitive_keywords(false);
+            assert!(boolean(&options)(case).is_err());
+
+            let case = "yes";
+            assert!(boolean(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -376,17 +724,20 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "sakura.tooltips";
-            let (remain, result) = surface_target_character_id(case).unwrap();
+            let (remain, result) =
+                surface_target_character_id(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, ".tooltips");
             assert_eq!(result, SurfaceTargetCharacterId::Sakura);
 
             let case = "kero.tooltips";
-            let (remain, result) = surface_target_character_id(case).unwrap();
+            let (remain, result) =
+                surface_target_character_id(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, ".tooltips");
             assert_eq!(result, SurfaceTargetCharacterId::Kero);
 
             let case = "char502.tooltips";
-            let (remain, result) = surface_target_character_id(case).unwrap();
+            let (remain, result) =
+                surface_target_character_id(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, ".tooltips");
             assert_eq!(result, SurfaceTargetCharacterId::Char(502));
         }
@@ -394,7 +745,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "kk.tooltips";
-            assert!(surface_target_character_id(case).is_err());
+            assert!(surface_target_character_id(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -432,6 +783,11 @@ mod tests {
                     CommentLine::new("".to_string())
                 ]
             );
+
+            let case = "// a\n/* b */\n";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "/* b */\n");
+            assert_eq!(result, vec![CommentLine::new("// a".to_string())]);
         }
     }
 
@@ -445,8 +801,12 @@ mod tests {
 
             let case = "    descript\r\n";
             let (remain, result) = case_func(case).unwrap();
-            assert_eq!(remain, "");
+            assert_eq!(remain, "\r\n");
             assert_eq!(result, "descript");
+
+            let case = "descript {";
+            let (remain, _) = case_func(case).unwrap();
+            assert_eq!(remain, "{");
         }
 
         #[test]
@@ -456,6 +816,9 @@ mod tests {
 
             let case = "    descript";
             assert!(case_func(case).is_err());
+
+            let case = "descriptive\r\n";
+            assert!(case_func(case).is_err());
         }
     }
 
@@ -485,22 +848,63 @@ bbb
             );
             assert_eq!(
                 result,
-                vec![
-                    LineContainer::Comment(CommentLine::new("bbb".to_string())),
-                    LineContainer::Body("abc"),
-                    LineContainer::Body("abc"),
-                    LineContainer::Comment(CommentLine::new("bbb".to_string())),
-                ]
+                (
+                    BraceLayout::SameLine,
+                    vec![
+                        LineContainer::Comment(CommentLine::new("bbb".to_string())),
+                        LineContainer::body_line("abc"),
+                        LineContainer::body_line("abc"),
+                        LineContainer::Comment(CommentLine::new("bbb".to_string())),
+                    ]
+                )
             );
 
-            let case = r#"{
+            let case = r#"
+{
 bbb
 }"#;
             let (remain, result) = case_func(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
-                vec![LineContainer::Comment(CommentLine::new("bbb".to_string()))]
+                (
+                    BraceLayout::OwnLines,
+                    vec![LineContainer::Comment(CommentLine::new("bbb".to_string()))]
+                )
+            );
+        }
+
+        #[test]
+        fn success_when_one_line() {
+            let mut case_func = inner_brace_func(tuple((tag("a"), comma, tag("b"))));
+
+            let case = " { a, b } \r\nc";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "c");
+            assert_eq!(
+                result,
+                (
+                    BraceLayout::OneLine,
+                    vec![LineContainer::Body(
+                        ("a", ",", "b"),
+                        LineFormat::new(Some("a, b".to_string()), None)
+                    )]
+                )
+            );
+
+            let case = "{}";
+            let (remain, result) = case_func(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, (BraceLayout::OneLine, vec![]));
+
+            let case = "{ b }\n";
+            let (_, result) = case_func(case).unwrap();
+            assert_eq!(
+                result,
+                (
+                    BraceLayout::OneLine,
+                    vec![LineContainer::Comment(CommentLine::new("b".to_string()))]
+                )
             );
         }
     }
@@ -508,7 +912,7 @@ bbb
     #[test]
     fn failed_when_invalid_str() {
         let case_t_func = tag("abc");
-        let mut case_func = inner_brace_func(case_t_func);
+        let mut case_func = inner_brace_func::<&str, _, String>(case_t_func);
 
         let case = r#"{
 abc"#;
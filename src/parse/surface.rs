@@ -1,15 +1,18 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::map,
+    combinator::{map, value},
     multi::separated_list1,
     sequence::tuple,
-    sequence::{preceded, separated_pair},
+    sequence::{preceded, separated_pair, terminated},
     IResult,
 };
 use shell_parser_common_rs::ShellParseError;
 
-use crate::{Brace, BraceContainer, Surface, SurfaceAppend, SurfaceId, SurfaceInner};
+use crate::{
+    parse::ParseOptions, Brace, BraceContainer, BraceLayout, Surface, SurfaceAppend, SurfaceId,
+    SurfaceInner,
+};
 
 use self::{
     animation_collision::{animation_collision, animation_collision_ex},
@@ -19,13 +22,15 @@ use self::{
     collision::{collision, collision_ex},
     element::element,
     offset::{
-        balloon_offset_x, balloon_offset_y, kero_balloon_offset_x, kero_balloon_offset_y,
+        balloon_offset_x, balloon_offset_y, character_balloon_offset_x, character_balloon_offset_y,
         point_basepos_x, point_basepos_y, point_center_x, point_center_y, point_kinoko_center_x,
-        point_kinoko_center_y, sakura_balloon_offset_x, sakura_balloon_offset_y,
+        point_kinoko_center_y,
     },
 };
 
-use super::parts::{brace_name_func, digit, header_comments_func, inner_brace_func};
+use super::parts::{
+    brace_container, brace_name_func, comma, digit, header_comments_func, inner_brace_func, keyword,
+};
 
 mod animation_collision;
 mod animation_interval;
@@ -36,65 +41,104 @@ mod draw_method;
 mod element;
 mod offset;
 
-pub(super) fn brace_surface<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_surface<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
-        tuple((header_comments_func(surface_name), surface)),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::Surface(body)),
-    )(input)
+        tuple((
+            header_comments_func(surface_name(options)),
+            surface(options),
+        )),
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::Surface(body), layout)
+        },
+    )
 }
 
-fn surface<'a>(input: &'a str) -> IResult<&'a str, Surface, ShellParseError> {
+fn surface<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (Surface<S>, BraceLayout), ShellParseError> {
     map(
-        tuple((surface_name, inner_brace_func(surface_inner))),
-        |(ids, lines)| Surface::new(ids, lines),
-    )(input)
+        tuple((
+            surface_name(options),
+            inner_brace_func(surface_inner(options)),
+        )),
+        |(ids, (layout, lines))| (Surface::new(ids, lines), layout),
+    )
 }
 
-fn surface_name<'a>(input: &'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
-    alt((surface_bracename_ssp, surface_bracename_materia))(input)
+pub(super) fn surface_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
+    alt((
+        surface_bracename_ssp(options),
+        surface_bracename_materia(options),
+    ))
 }
 
-pub(super) fn brace_surface_append<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_surface_append<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
-        tuple((header_comments_func(surface_append), surface_append)),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::SurfaceAppend(body)),
-    )(input)
+        tuple((
+            header_comments_func(surface_append::<&str>(options)),
+            surface_append(options),
+        )),
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::SurfaceAppend(body), layout)
+        },
+    )
 }
 
-fn surface_append<'a>(input: &'a str) -> IResult<&'a str, SurfaceAppend, ShellParseError> {
+fn surface_append<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (SurfaceAppend<S>, BraceLayout), ShellParseError> {
     map(
-        tuple((surface_append_name, inner_brace_func(surface_inner))),
-        |(ids, lines)| SurfaceAppend::new(ids, lines),
-    )(input)
+        tuple((
+            surface_append_name(options),
+            inner_brace_func(surface_inner(options)),
+        )),
+        |(ids, (layout, lines))| (SurfaceAppend::new(ids, lines), layout),
+    )
 }
 
-fn surface_append_name<'a>(input: &'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
-    brace_name_func(preceded(tag("surface.append"), surface_ids))(input)
+pub(super) fn surface_append_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
+    brace_name_func(preceded(keyword(options, "surface.append"), surface_ids))
 }
 
-fn surface_bracename_ssp<'a>(input: &'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
-    brace_name_func(preceded(tag("surface"), surface_ids))(input)
+fn surface_bracename_ssp<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
+    brace_name_func(preceded(keyword(options, "surface"), surface_ids))
 }
 
 fn surface_bracename_materia<'a>(
-    input: &'a str,
-) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
     brace_name_func(separated_list1(
-        tag(","),
-        preceded(tag("surface"), surface_id_unit),
-    ))(input)
+        comma,
+        preceded(keyword(options, "surface"), surface_id_unit),
+    ))
 }
 
 fn surface_ids<'a>(input: &'a str) -> IResult<&'a str, Vec<SurfaceId>, ShellParseError> {
-    separated_list1(tag(","), surface_id)(input)
+    separated_list1(comma, surface_id)(input)
 }
 
 fn surface_id<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellParseError> {
-    alt((surface_id_not, surface_id_range, surface_id_unit))(input)
+    alt((
+        surface_id_not,
+        surface_id_all,
+        surface_id_range,
+        surface_id_range_from,
+        surface_id_unit,
+    ))(input)
+}
+
+fn surface_id_all<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellParseError> {
+    value(SurfaceId::All, tag("*"))(input)
 }
 
 fn surface_id_unit<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellParseError> {
@@ -107,38 +151,53 @@ fn surface_id_range<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellPars
     })(input)
 }
 
+fn surface_id_range_from<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellParseError> {
+    map(terminated(digit, tag("-")), |v| SurfaceId::RangeFrom(v))(input)
+}
+
 fn surface_id_not<'a>(input: &'a str) -> IResult<&'a str, SurfaceId, ShellParseError> {
     map(
-        preceded(tag("!"), alt((surface_id_range, surface_id_unit))),
+        preceded(
+            tag("!"),
+            alt((surface_id_range, surface_id_range_from, surface_id_unit)),
+        ),
         |v| SurfaceId::Not(Box::new(v)),
     )(input)
 }
 
-fn surface_inner<'a>(input: &'a str) -> IResult<&'a str, SurfaceInner, ShellParseError> {
+fn surface_inner<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
     alt((
-        map(element, |v| SurfaceInner::Element(v)),
-        map(animation_interval, |v| SurfaceInner::AnimationInterval(v)),
-        map(animation_pattern, |v| SurfaceInner::AnimationPattern(v)),
-        map(animation_option, |v| SurfaceInner::AnimationOption(v)),
-        map(animation_collision, |v| SurfaceInner::AnimationCollision(v)),
-        map(animation_collision_ex, |v| {
+        map(element(options), |v| SurfaceInner::Element(v)),
+        map(animation_interval(options), |v| {
+            SurfaceInner::AnimationInterval(v)
+        }),
+        map(animation_pattern(options), |v| {
+            SurfaceInner::AnimationPattern(v)
+        }),
+        map(animation_option(options), |v| {
+            SurfaceInner::AnimationOption(v)
+        }),
+        map(animation_collision(options), |v| {
+            SurfaceInner::AnimationCollision(v)
+        }),
+        map(animation_collision_ex(options), |v| {
             SurfaceInner::AnimationCollisionEx(v)
         }),
-        map(collision, |v| SurfaceInner::Collision(v)),
-        map(collision_ex, |v| SurfaceInner::CollisionEx(v)),
-        sakura_balloon_offset_x,
-        sakura_balloon_offset_y,
-        kero_balloon_offset_x,
-        kero_balloon_offset_y,
-        balloon_offset_x,
-        balloon_offset_y,
-        point_center_x,
-        point_center_y,
-        point_kinoko_center_x,
-        point_kinoko_center_y,
-        point_basepos_x,
-        point_basepos_y,
-    ))(input)
+        map(collision(options), |v| SurfaceInner::Collision(v)),
+        map(collision_ex(options), |v| SurfaceInner::CollisionEx(v)),
+        character_balloon_offset_x(options),
+        character_balloon_offset_y(options),
+        balloon_offset_x(options),
+        balloon_offset_y(options),
+        point_center_x(options),
+        point_center_y(options),
+        point_kinoko_center_x(options),
+        point_kinoko_center_y(options),
+        point_basepos_x(options),
+        point_basepos_y(options),
+    ))
 }
 
 #[cfg(test)]
@@ -163,7 +222,7 @@ surface1-3,!25-30
 collision0,188,25,252,63,Head
 collision1,180,191,220,222,Bust
 }"#;
-            let (remain, result) = brace_surface(case).unwrap();
+            let (remain, result) = brace_surface(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -180,7 +239,7 @@ collision1,180,191,220,222,Bust
                         SurfaceId::Not(Box::new(SurfaceId::Range(25, 30)))
                     ],
                     vec![
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             0,
                             188,
                             25,
@@ -188,7 +247,7 @@ collision1,180,191,220,222,Bust
                             63,
                             "Head".to_string()
                         ))),
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             1,
                             180,
                             191,
@@ -205,7 +264,7 @@ collision1,180,191,220,222,Bust
 surface1,surface3,surface4
 {
 }"#;
-            let (remain, result) = brace_surface(case).unwrap();
+            let (remain, result) = brace_surface(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -225,8 +284,8 @@ surface1,surface3,surface4
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = r#"surface1{}"#;
-            assert!(surface(case).is_err());
+            let case = r#"surface1{"#;
+            assert!(surface::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -242,7 +301,7 @@ surface1,surface3,surface4
 collision0,188,25,252,63,Head
 collision1,180,191,220,222,Bust
 }"#;
-            let (remain, result) = surface(case).unwrap();
+            let (remain, (result, _)) = surface(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -252,7 +311,7 @@ collision1,180,191,220,222,Bust
                         SurfaceId::Not(Box::new(SurfaceId::Range(25, 30)))
                     ],
                     vec![
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             0,
                             188,
                             25,
@@ -260,7 +319,7 @@ collision1,180,191,220,222,Bust
                             63,
                             "Head".to_string()
                         ))),
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             1,
                             180,
                             191,
@@ -275,7 +334,7 @@ collision1,180,191,220,222,Bust
             let case = r#"surface1,surface3,surface4
 {
 }"#;
-            let (remain, result) = surface(case).unwrap();
+            let (remain, (result, _)) = surface::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -294,15 +353,15 @@ sakura.balloon.offsety,-100
 kero.balloon.offsetx,-30
 kero.balloon.offsety,20
 }"#;
-            let (remain, result) = surface(case).unwrap();
+            let (remain, (result, _)) = surface::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.ids(), &vec![SurfaceId::Unit(10)]);
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = r#"surface1{}"#;
-            assert!(surface(case).is_err());
+            let case = r#"surface1{"#;
+            assert!(surface::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -312,20 +371,20 @@ kero.balloon.offsety,20
         #[test]
         fn success_when_valid_str() {
             let case = "surface0,1\r\n";
-            let (remain, result) = surface_name(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) = surface_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(result, vec![SurfaceId::Unit(0), SurfaceId::Unit(1)]);
 
             let case = "surface0,surface1\r\n";
-            let (remain, result) = surface_name(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) = surface_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(result, vec![SurfaceId::Unit(0), SurfaceId::Unit(1)]);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "surface0,";
-            assert!(surface_name(case).is_err());
+            assert!(surface_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -346,7 +405,7 @@ surface.append1-3,!25-30
 collision0,188,25,252,63,Head
 collision1,180,191,220,222,Bust
 }"#;
-            let (remain, result) = brace_surface_append(case).unwrap();
+            let (remain, result) = brace_surface_append(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -363,7 +422,7 @@ collision1,180,191,220,222,Bust
                         SurfaceId::Not(Box::new(SurfaceId::Range(25, 30)))
                     ],
                     vec![
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             0,
                             188,
                             25,
@@ -371,7 +430,7 @@ collision1,180,191,220,222,Bust
                             63,
                             "Head".to_string()
                         ))),
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             1,
                             180,
                             191,
@@ -386,8 +445,8 @@ collision1,180,191,220,222,Bust
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = "surface.append0{}";
-            assert!(brace_surface_append(case).is_err());
+            let case = "surface.append0{";
+            assert!(brace_surface_append::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -403,7 +462,7 @@ collision1,180,191,220,222,Bust
 collision0,188,25,252,63,Head
 collision1,180,191,220,222,Bust
 }"#;
-            let (remain, result) = surface_append(case).unwrap();
+            let (remain, (result, _)) = surface_append(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -413,7 +472,7 @@ collision1,180,191,220,222,Bust
                         SurfaceId::Not(Box::new(SurfaceId::Range(25, 30)))
                     ],
                     vec![
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             0,
                             188,
                             25,
@@ -421,7 +480,7 @@ collision1,180,191,220,222,Bust
                             63,
                             "Head".to_string()
                         ))),
-                        LineContainer::Body(SurfaceInner::Collision(SurfaceCollision::new(
+                        LineContainer::body_line(SurfaceInner::Collision(SurfaceCollision::new(
                             1,
                             180,
                             191,
@@ -441,7 +500,7 @@ surface.append1,surface2
 {
 }
 "#;
-            assert!(surface_append(case).is_err());
+            assert!(surface_append::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -451,15 +510,22 @@ surface.append1,surface2
         #[test]
         fn success_when_valid_str() {
             let case = "surface.append0\r\n";
-            let (remain, result) = surface_append_name(case).unwrap();
-            assert_eq!(remain, "");
-            assert_eq!(result, vec![SurfaceId::Unit(0)])
+            let (remain, result) = surface_append_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
+            assert_eq!(result, vec![SurfaceId::Unit(0)]);
+
+            let case = "surface.append*,!3\r\n";
+            let (_, result) = surface_append_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(
+                result,
+                vec![SurfaceId::All, SurfaceId::Not(Box::new(SurfaceId::Unit(3)))]
+            );
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "surface.append0";
-            assert!(surface_append_name(case).is_err());
+            assert!(surface_append_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -469,8 +535,8 @@ surface.append1,surface2
         #[test]
         fn success_when_valid_str() {
             let case = "surface1,3,4,6,12\r\n";
-            let (remain, result) = surface_bracename_ssp(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) = surface_bracename_ssp(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(
                 result,
                 vec![
@@ -483,13 +549,13 @@ surface.append1,surface2
             );
 
             let case = "surface1-12\r\n";
-            let (remain, result) = surface_bracename_ssp(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) = surface_bracename_ssp(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(result, vec![SurfaceId::Range(1, 12)]);
 
             let case = "surface1-30,!15,!20-25\r\n";
-            let (remain, result) = surface_bracename_ssp(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) = surface_bracename_ssp(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(
                 result,
                 vec![
@@ -503,7 +569,7 @@ surface.append1,surface2
         #[test]
         fn failed_when_invalid_str() {
             let case = "surface,1,surface3,surface4";
-            assert!(surface_bracename_ssp(case).is_err());
+            assert!(surface_bracename_ssp(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -513,23 +579,25 @@ surface.append1,surface2
         #[test]
         fn success_when_valid_str() {
             let case = "surface1,surface3,surface4\r\n";
-            let (remain, result) = surface_bracename_materia(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) =
+                surface_bracename_materia(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(
                 result,
                 vec![SurfaceId::Unit(1), SurfaceId::Unit(3), SurfaceId::Unit(4)]
             );
 
             let case = "surface1\r\n";
-            let (remain, result) = surface_bracename_materia(case).unwrap();
-            assert_eq!(remain, "");
+            let (remain, result) =
+                surface_bracename_materia(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n");
             assert_eq!(result, vec![SurfaceId::Unit(1)]);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "surface,";
-            assert!(surface_bracename_materia(case).is_err());
+            assert!(surface_bracename_materia(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -555,12 +623,28 @@ surface.append1,surface2
             let (remain, result) = surface_ids(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, vec![SurfaceId::Unit(0)]);
+
+            let case = "*,10-,!20-,!5-7";
+            let (remain, result) = surface_ids(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                vec![
+                    SurfaceId::All,
+                    SurfaceId::RangeFrom(10),
+                    SurfaceId::Not(Box::new(SurfaceId::RangeFrom(20))),
+                    SurfaceId::Not(Box::new(SurfaceId::Range(5, 7))),
+                ]
+            );
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "!!30";
             assert!(surface_ids(case).is_err());
+
+            let case = "!*";
+            assert!(surface_ids(case).is_err());
         }
     }
 }
@@ -8,33 +8,34 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{SurfaceAnimationCollision, SurfaceAnimationCollisionEx},
-    parse::parts::digit,
+    parse::parts::{digit, keyword},
+    parse::ParseOptions,
 };
 
 use super::collision::{collision, collision_ex};
 
-pub(super) fn animation_collision<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationCollision, ShellParseError> {
+pub(super) fn animation_collision<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationCollision<S>, ShellParseError> {
     map(
         tuple((
-            preceded(tag("animation"), digit),
-            preceded(tag("."), collision),
+            preceded(keyword(options, "animation"), digit),
+            preceded(tag("."), collision(options)),
         )),
         |(id, c)| SurfaceAnimationCollision::new(id, c),
-    )(input)
+    )
 }
 
-pub(super) fn animation_collision_ex<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationCollisionEx, ShellParseError> {
+pub(super) fn animation_collision_ex<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationCollisionEx<S>, ShellParseError> {
     map(
         tuple((
-            preceded(tag("animation"), digit),
-            preceded(tag("."), collision_ex),
+            preceded(keyword(options, "animation"), digit),
+            preceded(tag("."), collision_ex(options)),
         )),
         |(id, c)| SurfaceAnimationCollisionEx::new(id, c),
-    )(input)
+    )
 }
 
 #[cfg(test)]
@@ -49,7 +50,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation2.collision0,10,10,200,100,Head";
-            let (remain, result) = animation_collision(case).unwrap();
+            let (remain, result) = animation_collision(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -63,7 +64,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation2.collision0,10,10,200,100";
-            assert!(animation_collision(case).is_err());
+            assert!(animation_collision::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -75,7 +76,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation2.collisionex0,Head,rect,100,100,200,300";
-            let (remain, result) = animation_collision_ex(case).unwrap();
+            let (remain, result) = animation_collision_ex(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -93,7 +94,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation2.collisionex0,Head,rect,100,100,200,";
-            assert!(animation_collision_ex(case).is_err());
+            assert!(animation_collision_ex::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 }
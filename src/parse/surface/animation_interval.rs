@@ -6,125 +6,137 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{AnimationInterval, SurfaceAnimationInterval},
-    parse::parts::digit,
+    parse::parts::{digit, keyword, keyword_comma},
+    parse::ParseOptions,
 };
 
 pub(super) fn animation_interval<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
-    alt((animation_interval_v0, animation_interval_v1))(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
+    alt((
+        animation_interval_v0(options),
+        animation_interval_v1(options),
+    ))
 }
 
 fn animation_interval_v0<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
     map(
-        tuple((digit, tag("interval,"), animation_interval_defines)),
+        tuple((
+            digit,
+            keyword_comma(options, "interval"),
+            animation_interval_defines(options),
+        )),
         |(id, _, intervals)| SurfaceAnimationInterval::new(id, intervals),
-    )(input)
+    )
 }
 
 fn animation_interval_v1<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationInterval, ShellParseError> {
     map(
         tuple((
-            tag("animation"),
+            keyword(options, "animation"),
             digit,
-            tag(".interval,"),
-            animation_interval_defines,
+            keyword_comma(options, ".interval"),
+            animation_interval_defines(options),
         )),
         |(_, id, _, intervals)| SurfaceAnimationInterval::new(id, intervals),
-    )(input)
+    )
 }
 
 fn animation_interval_defines<'a>(
-    input: &'a str,
-) -> IResult<&'a str, Vec<AnimationInterval>, ShellParseError> {
-    separated_list1(tag("+"), animation_interval_define)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<AnimationInterval>, ShellParseError> {
+    separated_list1(tag("+"), animation_interval_define(options))
 }
 
 fn animation_interval_define<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
     alt((
-        animation_interval_define_sometimes,
-        animation_interval_define_rarely,
-        animation_interval_define_random,
-        animation_interval_define_periodic,
-        animation_interval_define_always,
-        animation_interval_define_runonce,
-        animation_interval_define_never,
-        animation_interval_define_yen_e,
-        animation_interval_define_talk,
-        animation_interval_define_bind,
-    ))(input)
+        animation_interval_define_sometimes(options),
+        animation_interval_define_rarely(options),
+        animation_interval_define_random(options),
+        animation_interval_define_periodic(options),
+        animation_interval_define_always(options),
+        animation_interval_define_runonce(options),
+        animation_interval_define_never(options),
+        animation_interval_define_yen_e(options),
+        animation_interval_define_talk(options),
+        animation_interval_define_bind(options),
+    ))
 }
 
 fn animation_interval_define_sometimes<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("sometimes"), |_| AnimationInterval::Sometimes)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "sometimes"), |_| {
+        AnimationInterval::Sometimes
+    })
 }
 
 fn animation_interval_define_rarely<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("rarely"), |_| AnimationInterval::Rarely)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "rarely"), |_| AnimationInterval::Rarely)
 }
 
 fn animation_interval_define_random<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tuple((tag("random,"), digit)), |(_, v)| {
-        AnimationInterval::Random(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(
+        tuple((keyword_comma(options, "random"), digit)),
+        |(_, v)| AnimationInterval::Random(v),
+    )
 }
 
 fn animation_interval_define_periodic<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tuple((tag("periodic,"), digit)), |(_, v)| {
-        AnimationInterval::Periodic(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(
+        tuple((keyword_comma(options, "periodic"), digit)),
+        |(_, v)| AnimationInterval::Periodic(v),
+    )
 }
 
 fn animation_interval_define_always<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("always"), |_| AnimationInterval::Always)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "always"), |_| AnimationInterval::Always)
 }
 
 fn animation_interval_define_runonce<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("runonce"), |_| AnimationInterval::Runonce)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "runonce"), |_| AnimationInterval::Runonce)
 }
 
 fn animation_interval_define_never<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("never"), |_| AnimationInterval::Never)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "never"), |_| AnimationInterval::Never)
 }
 
 fn animation_interval_define_yen_e<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("yen-e"), |_| AnimationInterval::YenE)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "yen-e"), |_| AnimationInterval::YenE)
 }
 
 fn animation_interval_define_talk<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tuple((tag("talk,"), digit)), |(_, v)| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(tuple((keyword_comma(options, "talk"), digit)), |(_, v)| {
         AnimationInterval::Talk(v)
-    })(input)
+    })
 }
 
 fn animation_interval_define_bind<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationInterval, ShellParseError> {
-    map(tag("bind"), |_| AnimationInterval::Bind)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationInterval, ShellParseError> {
+    map(keyword(options, "bind"), |_| AnimationInterval::Bind)
 }
 
 #[cfg(test)]
@@ -137,7 +149,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "2interval,never";
-            let (remain, result) = animation_interval(case).unwrap();
+            let (remain, result) = animation_interval(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -145,7 +157,7 @@ mod tests {
             );
 
             let case = "animation2.interval,never";
-            let (remain, result) = animation_interval(case).unwrap();
+            let (remain, result) = animation_interval(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -156,7 +168,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation2.interval,ne";
-            assert!(animation_interval(case).is_err());
+            assert!(animation_interval(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -166,7 +178,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "2interval,never";
-            let (remain, result) = animation_interval_v0(case).unwrap();
+            let (remain, result) = animation_interval_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -177,7 +189,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation2.interval,never";
-            assert!(animation_interval_v0(case).is_err());
+            assert!(animation_interval_v0(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -187,7 +199,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation2.interval,never";
-            let (remain, result) = animation_interval_v1(case).unwrap();
+            let (remain, result) = animation_interval_v1(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -198,7 +210,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "2interval,never";
-            assert!(animation_interval_v1(case).is_err());
+            assert!(animation_interval_v1(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -208,7 +220,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "bind+always";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -216,7 +229,8 @@ mod tests {
             );
 
             let case = "bind+runonce";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -224,7 +238,8 @@ mod tests {
             );
 
             let case = "bind+random,10";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -232,7 +247,8 @@ mod tests {
             );
 
             let case = "bind+periodic,20";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -240,7 +256,8 @@ mod tests {
             );
 
             let case = "bind+runonce+random,5";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -252,7 +269,8 @@ mod tests {
             );
 
             let case = "bind";
-            let (remain, result) = animation_interval_defines(case).unwrap();
+            let (remain, result) =
+                animation_interval_defines(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, vec![AnimationInterval::Bind]);
         }
@@ -260,7 +278,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "somethingwrong";
-            assert!(animation_interval_defines(case).is_err());
+            assert!(animation_interval_defines(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -270,52 +288,62 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "sometimes";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Sometimes);
 
             let case = "rarely";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Rarely);
 
             let case = "random,10";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Random(10));
 
             let case = "periodic,20";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Periodic(20));
 
             let case = "always";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Always);
 
             let case = "runonce";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Runonce);
 
             let case = "never";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Never);
 
             let case = "yen-e";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::YenE);
 
             let case = "talk,5";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Talk(5));
 
             let case = "bind";
-            let (remain, result) = animation_interval_define(case).unwrap();
+            let (remain, result) =
+                animation_interval_define(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, AnimationInterval::Bind);
         }
@@ -323,7 +351,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "somethingwrong";
-            assert!(animation_interval_define(case).is_err());
+            assert!(animation_interval_define(&ParseOptions::default())(case).is_err());
         }
     }
 }
@@ -10,7 +10,8 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{AnimationOptionKind, SurfaceAnimationOption},
-    parse::parts::digit,
+    parse::parts::{comma, digit, keyword, keyword_comma},
+    parse::ParseOptions,
 };
 
 enum AnimationOptionKindTemp {
@@ -20,43 +21,47 @@ enum AnimationOptionKindTemp {
 }
 
 pub(super) fn animation_option<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
-    alt((animation_option_v0, animation_option_v1))(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
+    alt((animation_option_v0(options), animation_option_v1(options)))
 }
 
 fn animation_option_v0<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
     map(
-        tuple((digit, tag("option,"), animation_option_kinds)),
+        tuple((
+            digit,
+            keyword_comma(options, "option"),
+            animation_option_kinds(options),
+        )),
         |(id, _, options)| SurfaceAnimationOption::new(id, options),
-    )(input)
+    )
 }
 
 fn animation_option_v1<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationOption, ShellParseError> {
     map(
         tuple((
-            tag("animation"),
+            keyword(options, "animation"),
             digit,
-            tag(".option,"),
-            animation_option_kinds,
+            keyword_comma(options, ".option"),
+            animation_option_kinds(options),
         )),
         |(_, id, _, options)| SurfaceAnimationOption::new(id, options),
-    )(input)
+    )
 }
 
 fn animation_option_kinds<'a>(
-    input: &'a str,
-) -> IResult<&'a str, Vec<AnimationOptionKind>, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<AnimationOptionKind>, ShellParseError> {
     map(
         tuple((
-            animation_option_kind_temps,
+            animation_option_kind_temps(options),
             opt(preceded(
-                tag(","),
-                delimited(tag("("), separated_list1(tag(","), digit), tag(")")),
+                comma,
+                delimited(tag("("), separated_list1(comma, digit), tag(")")),
             )),
         )),
         |(temps, ids)| {
@@ -71,25 +76,29 @@ fn animation_option_kinds<'a>(
                 })
                 .collect()
         },
-    )(input)
+    )
 }
 
 fn animation_option_kind_temps<'a>(
-    input: &'a str,
-) -> IResult<&'a str, Vec<AnimationOptionKindTemp>, ShellParseError> {
-    separated_list1(tag("+"), animation_option_kind_temp)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<AnimationOptionKindTemp>, ShellParseError> {
+    separated_list1(tag("+"), animation_option_kind_temp(options))
 }
 
 fn animation_option_kind_temp<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationOptionKindTemp, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationOptionKindTemp, ShellParseError> {
     alt((
-        map(tag("exclusive"), |_| AnimationOptionKindTemp::Exclusive),
-        map(tag("background"), |_| AnimationOptionKindTemp::Background),
-        map(tag("shared-index"), |_| {
+        map(keyword(options, "exclusive"), |_| {
+            AnimationOptionKindTemp::Exclusive
+        }),
+        map(keyword(options, "background"), |_| {
+            AnimationOptionKindTemp::Background
+        }),
+        map(keyword(options, "shared-index"), |_| {
             AnimationOptionKindTemp::SharedIndex
         }),
-    ))(input)
+    ))
 }
 
 #[cfg(test)]
@@ -102,7 +111,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "5option,exclusive";
-            let (remain, result) = animation_option(case).unwrap();
+            let (remain, result) = animation_option(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -110,7 +119,7 @@ mod tests {
             );
 
             let case = "animation5.option,exclusive+background,(1,3,5)";
-            let (remain, result) = animation_option(case).unwrap();
+            let (remain, result) = animation_option(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -127,7 +136,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation5.option,exclusiv";
-            assert!(animation_option(case).is_err());
+            assert!(animation_option(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -137,7 +146,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "5option,exclusive";
-            let (remain, result) = animation_option_v0(case).unwrap();
+            let (remain, result) = animation_option_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -148,7 +157,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation5.option,exclusive+background,(1,3,5)";
-            assert!(animation_option_v0(case).is_err());
+            assert!(animation_option_v0(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -158,7 +167,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation5.option,exclusive+background,(1,3,5)";
-            let (remain, result) = animation_option_v1(case).unwrap();
+            let (remain, result) = animation_option_v1(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -175,7 +184,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "5option,exclusive";
-            assert!(animation_option_v1(case).is_err());
+            assert!(animation_option_v1(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -185,7 +194,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "exclusive+background,(1,3,5)";
-            let (remain, result) = animation_option_kinds(case).unwrap();
+            let (remain, result) = animation_option_kinds(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -195,7 +204,7 @@ mod tests {
                 ]
             );
             let case = "shared-index";
-            let (remain, result) = animation_option_kinds(case).unwrap();
+            let (remain, result) = animation_option_kinds(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, vec![AnimationOptionKind::SharedIndex]);
         }
@@ -203,7 +212,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "+background";
-            assert!(animation_option_kinds(case).is_err());
+            assert!(animation_option_kinds(&ParseOptions::default())(case).is_err());
         }
     }
 }
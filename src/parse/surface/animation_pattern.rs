@@ -1,6 +1,5 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     combinator::map,
     sequence::{preceded, tuple},
     IResult,
@@ -12,99 +11,100 @@ use crate::{
         AnimationPatternDrawMethod, AnimationPatternProperty, SurfaceAnimationPattern,
         SurfaceIdPointerType,
     },
-    parse::parts::{digit, digit_neg},
+    parse::parts::{comma, digit, digit_neg, keyword},
+    parse::ParseOptions,
 };
 
 use super::draw_method::{draw_method, draw_method_on_animation};
 
 pub(super) fn animation_pattern<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
-    alt((animation_pattern_v0, animation_pattern_v1))(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
+    alt((animation_pattern_v0(options), animation_pattern_v1(options)))
 }
 
 fn animation_pattern_v0<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
     map(
         tuple((
             digit,
-            tag("pattern"),
+            keyword(options, "pattern"),
             digit,
             alt((
-                animation_pattern_draw_method_normal_v0,
-                animation_pattern_draw_method_animation_v0,
+                animation_pattern_draw_method_normal_v0(options),
+                animation_pattern_draw_method_animation_v0(options),
             )),
         )),
         |(id, _, pattern_id, method)| SurfaceAnimationPattern::new(id, pattern_id, method),
-    )(input)
+    )
 }
 
 fn animation_pattern_draw_method_normal_v0<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
     map(
         tuple((
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit::<u32>),
-            preceded(tag(","), draw_method),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit::<u32>),
+            preceded(comma, draw_method(options)),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
         |(surface_id, weight, dm, x, y)| {
             let prop = AnimationPatternProperty::new(surface_id, weight * 10, x, y);
             AnimationPatternDrawMethod::Normal(dm, prop)
         },
-    )(input)
+    )
 }
 
 fn animation_pattern_draw_method_animation_v0<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
     map(
         tuple((
-            preceded(tag(","), digit_neg::<SurfaceIdPointerType>),
-            preceded(tag(","), digit::<u32>),
-            preceded(tag(","), draw_method_on_animation),
+            preceded(comma, digit_neg::<SurfaceIdPointerType>),
+            preceded(comma, digit::<u32>),
+            preceded(comma, draw_method_on_animation(options)),
         )),
         |(_, _, dm)| AnimationPatternDrawMethod::Animation(dm),
-    )(input)
+    )
 }
 
 fn animation_pattern_v1<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceAnimationPattern, ShellParseError> {
     map(
         tuple((
-            preceded(tag("animation"), digit),
-            preceded(tag(".pattern"), digit),
+            preceded(keyword(options, "animation"), digit),
+            preceded(keyword(options, ".pattern"), digit),
             alt((
-                animation_pattern_draw_method_normal_v1,
-                animation_pattern_draw_method_animation_v1,
+                animation_pattern_draw_method_normal_v1(options),
+                animation_pattern_draw_method_animation_v1(options),
             )),
         )),
         |(id, pattern_id, dm)| SurfaceAnimationPattern::new(id, pattern_id, dm),
-    )(input)
+    )
 }
 
 fn animation_pattern_draw_method_normal_v1<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
     map(
         tuple((
-            preceded(tag(","), draw_method),
+            preceded(comma, draw_method(options)),
             animation_pattern_property_v1,
         )),
         |(dm, app)| AnimationPatternDrawMethod::Normal(dm, app),
-    )(input)
+    )
 }
 
 fn animation_pattern_draw_method_animation_v1<'a>(
-    input: &'a str,
-) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
-    map(preceded(tag(","), draw_method_on_animation), |v| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, AnimationPatternDrawMethod, ShellParseError> {
+    map(preceded(comma, draw_method_on_animation(options)), |v| {
         AnimationPatternDrawMethod::Animation(v)
-    })(input)
+    })
 }
 
 fn animation_pattern_property_v1<'a>(
@@ -112,10 +112,10 @@ fn animation_pattern_property_v1<'a>(
 ) -> IResult<&'a str, AnimationPatternProperty, ShellParseError> {
     map(
         tuple((
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
         |(surface_id, weight, x, y)| AnimationPatternProperty::new(surface_id, weight, x, y),
     )(input)
@@ -131,7 +131,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation0.pattern1,overlay,-1,2,3,4";
-            let (remain, result) = animation_pattern(case).unwrap();
+            let (remain, result) = animation_pattern(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &0);
             assert_eq!(result.pattern_id(), &1);
@@ -147,7 +147,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "0pattern0,0,0,alternativestart,";
-            assert!(animation_pattern(case).is_err());
+            assert!(animation_pattern(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -157,7 +157,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "3pattern4,10,20,alternativestart,[1,2]";
-            let (remain, result) = animation_pattern_v0(case).unwrap();
+            let (remain, result) = animation_pattern_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &3);
             assert_eq!(result.pattern_id(), &4);
@@ -169,7 +169,7 @@ mod tests {
             );
 
             let case = "1pattern3,-1,70,overlay,0,0";
-            let (remain, result) = animation_pattern_v0(case).unwrap();
+            let (remain, result) = animation_pattern_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &1);
             assert_eq!(result.pattern_id(), &3);
@@ -185,7 +185,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "animation0.pattern0,alternativestart,(1,2)";
-            assert!(animation_pattern_v0(case).is_err());
+            assert!(animation_pattern_v0(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -195,7 +195,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = ",101,70,overlay,200,100";
-            let (remain, result) = animation_pattern_draw_method_normal_v0(case).unwrap();
+            let (remain, result) =
+                animation_pattern_draw_method_normal_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -209,7 +210,9 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = ",0,0,0,0";
-            assert!(animation_pattern_draw_method_normal_v0(case).is_err());
+            assert!(
+                animation_pattern_draw_method_normal_v0(&ParseOptions::default())(case).is_err()
+            );
         }
     }
 
@@ -219,7 +222,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = ",0,0,insert,10";
-            let (remain, result) = animation_pattern_draw_method_animation_v0(case).unwrap();
+            let (remain, result) =
+                animation_pattern_draw_method_animation_v0(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -232,7 +236,9 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = ",insert,10";
-            assert!(animation_pattern_draw_method_animation_v0(case).is_err());
+            assert!(
+                animation_pattern_draw_method_animation_v0(&ParseOptions::default())(case).is_err()
+            );
         }
     }
 
@@ -242,7 +248,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "animation3.pattern4,alternativestart,(1,2)";
-            let (remain, result) = animation_pattern_v1(case).unwrap();
+            let (remain, result) = animation_pattern_v1(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &3);
             assert_eq!(result.pattern_id(), &4);
@@ -254,7 +260,7 @@ mod tests {
             );
 
             let case = "animation1.pattern3,overlay,-1,700,0,0";
-            let (remain, result) = animation_pattern_v1(case).unwrap();
+            let (remain, result) = animation_pattern_v1(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &1);
             assert_eq!(result.pattern_id(), &3);
@@ -270,7 +276,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "0pattern0,0,0,alternativestart,[1,2]";
-            assert!(animation_pattern_v1(case).is_err());
+            assert!(animation_pattern_v1(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -1,6 +1,6 @@
 use nom::{
     branch::alt,
-    bytes::complete::{is_not, tag},
+    bytes::complete::is_not,
     combinator::{map, opt},
     multi::many1,
     sequence::{preceded, tuple},
@@ -9,119 +9,127 @@ use nom::{
 use shell_parser_common_rs::ShellParseError;
 
 use crate::{
-    parse::parts::{boolean, digit, digit_neg},
+    parse::parts::{boolean, comma, digit, digit_neg, keyword, text_field},
+    parse::ParseOptions,
     CollisionExKind, SurfaceCollision, SurfaceCollisionEx,
 };
 
-pub(super) fn collision<'a>(input: &'a str) -> IResult<&'a str, SurfaceCollision, ShellParseError> {
+pub(super) fn collision<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceCollision<S>, ShellParseError> {
     map(
         tuple((
-            preceded(tag("collision"), digit),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), is_not(",\r\n ")),
+            preceded(keyword(options, "collision"), digit),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, is_not(",\r\n ")),
         )),
         |(id, start_x, start_y, end_x, end_y, collision_id)| {
-            SurfaceCollision::new(id, start_x, start_y, end_x, end_y, collision_id.to_string())
+            SurfaceCollision::new(id, start_x, start_y, end_x, end_y, S::from(collision_id))
         },
-    )(input)
+    )
 }
 
-pub(super) fn collision_ex<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceCollisionEx, ShellParseError> {
+pub(super) fn collision_ex<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceCollisionEx<S>, ShellParseError> {
     map(
         tuple((
-            tag("collisionex"),
+            keyword(options, "collisionex"),
             digit,
-            preceded(tag(","), is_not(",")),
-            preceded(tag(","), collision_ex_kind),
+            preceded(comma, text_field(",")),
+            preceded(comma, collision_ex_kind(options)),
         )),
-        |(_, id, collision_id, kind)| SurfaceCollisionEx::new(id, collision_id.to_string(), kind),
-    )(input)
+        |(_, id, collision_id, kind)| SurfaceCollisionEx::new(id, S::from(collision_id), kind),
+    )
 }
 
-fn collision_ex_kind<'a>(input: &'a str) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     alt((
-        collision_ex_kind_rect,
-        collision_ex_kind_ellipse,
-        collision_ex_kind_circle,
-        collision_ex_kind_polygon,
-        collision_ex_kind_region,
-    ))(input)
+        collision_ex_kind_rect(options),
+        collision_ex_kind_ellipse(options),
+        collision_ex_kind_circle(options),
+        collision_ex_kind_polygon(options),
+        collision_ex_kind_region(options),
+    ))
 }
 
-fn collision_ex_kind_rect<'a>(
-    input: &'a str,
-) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind_rect<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     map(
         tuple((
-            tag("rect"),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            keyword(options, "rect"),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
         |(_, start_x, start_y, end_x, end_y)| CollisionExKind::Rect(start_x, start_y, end_x, end_y),
-    )(input)
+    )
 }
 
-fn collision_ex_kind_ellipse<'a>(
-    input: &'a str,
-) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind_ellipse<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     map(
         tuple((
-            tag("ellipse"),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            keyword(options, "ellipse"),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
         |(_, start_x, start_y, end_x, end_y)| {
             CollisionExKind::Ellipse(start_x, start_y, end_x, end_y)
         },
-    )(input)
+    )
 }
 
-fn collision_ex_kind_circle<'a>(
-    input: &'a str,
-) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind_circle<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     map(
         tuple((
-            tag("circle"),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            keyword(options, "circle"),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
         |(_, x, y, r)| CollisionExKind::Circle(x, y, r),
-    )(input)
+    )
 }
 
-fn collision_ex_kind_polygon<'a>(
-    input: &'a str,
-) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind_polygon<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     map(
-        tuple((tag("polygon"), many1(preceded(tag(","), digit_neg)))),
+        tuple((
+            keyword(options, "polygon"),
+            many1(preceded(comma, digit_neg)),
+        )),
         |(_, v)| CollisionExKind::Polygon(v),
-    )(input)
+    )
 }
 
-fn collision_ex_kind_region<'a>(
-    input: &'a str,
-) -> IResult<&'a str, CollisionExKind, ShellParseError> {
+fn collision_ex_kind_region<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, CollisionExKind<S>, ShellParseError> {
     map(
         tuple((
-            tag("region"),
-            preceded(tag(","), is_not(",")),
-            preceded(tag(","), digit),
-            preceded(tag(","), digit),
-            preceded(tag(","), digit),
-            opt(preceded(tag(","), boolean)),
+            keyword(options, "region"),
+            preceded(comma, text_field(",")),
+            preceded(comma, digit),
+            preceded(comma, digit),
+            preceded(comma, digit),
+            opt(preceded(comma, boolean(options))),
         )),
-        |(_, filename, r, g, b, flag)| CollisionExKind::Region(filename.to_string(), r, g, b, flag),
-    )(input)
+        |(_, filename, r, g, b, flag)| CollisionExKind::Region(S::from(filename), r, g, b, flag),
+    )
 }
 
 #[cfg(test)]
@@ -134,18 +142,26 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "collision2,10,10,100,100,Head";
-            let (remain, result) = collision(case).unwrap();
+            let (remain, result) = collision(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
                 SurfaceCollision::new(2, 10, 10, 100, 100, "Head".to_string())
             );
+
+            let case = "collision0,40, 56,95 ,\t90,Head";
+            let (remain, result) = collision(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                SurfaceCollision::new(0, 40, 56, 95, 90, "Head".to_string())
+            );
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "collision2,10,10,100,100";
-            assert!(collision(case).is_err());
+            assert!(collision::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -155,7 +171,7 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "collisionex0,Head,rect,100,100,200,300";
-            let (remain, result) = collision_ex(case).unwrap();
+            let (remain, result) = collision_ex(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -170,7 +186,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "collisionex0,Head,rect,100,100,200";
-            assert!(collision_ex(case).is_err());
+            assert!(collision_ex::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -180,22 +196,26 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "rect,100,100,200,300";
-            let (remain, result) = collision_ex_kind(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Rect(100, 100, 200, 300));
 
             let case = "ellipse,100,100,200,300";
-            let (remain, result) = collision_ex_kind(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Ellipse(100, 100, 200, 300));
 
             let case = "circle,100,200,20";
-            let (remain, result) = collision_ex_kind(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Circle(100, 200, 20));
 
             let case = "polygon,100,100,200,300,50,200";
-            let (remain, result) = collision_ex_kind(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -203,7 +223,7 @@ mod tests {
             );
 
             let case = "region,atari.png,0,255,0,true";
-            let (remain, result) = collision_ex_kind(case).unwrap();
+            let (remain, result) = collision_ex_kind(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -214,7 +234,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "polygon,";
-            assert!(collision_ex_kind(case).is_err());
+            assert!(collision_ex_kind::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -224,7 +244,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "rect,100,100,200,300";
-            let (remain, result) = collision_ex_kind_rect(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_rect::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Rect(100, 100, 200, 300));
         }
@@ -232,7 +253,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "rect,100,100,200";
-            assert!(collision_ex_kind_rect(case).is_err());
+            assert!(collision_ex_kind_rect::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -242,7 +263,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "ellipse,100,100,200,300";
-            let (remain, result) = collision_ex_kind_ellipse(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_ellipse::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Ellipse(100, 100, 200, 300));
         }
@@ -250,7 +272,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "ellipse,100,100,200";
-            assert!(collision_ex_kind_ellipse(case).is_err());
+            assert!(collision_ex_kind_ellipse::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -260,7 +282,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "circle,100,200,20";
-            let (remain, result) = collision_ex_kind_circle(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_circle::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, CollisionExKind::Circle(100, 200, 20));
         }
@@ -268,7 +291,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "circle,100,200";
-            assert!(collision_ex_kind_circle(case).is_err());
+            assert!(collision_ex_kind_circle::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -278,7 +301,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "polygon,100,100,200,300,50,200";
-            let (remain, result) = collision_ex_kind_polygon(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_polygon::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -289,7 +313,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "polygon,";
-            assert!(collision_ex_kind_polygon(case).is_err());
+            assert!(collision_ex_kind_polygon::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -299,7 +323,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "region,atari.png,0,255,0,true";
-            let (remain, result) = collision_ex_kind_region(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_region(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -307,7 +332,8 @@ mod tests {
             );
 
             let case = "region,atari.png,0,255,0";
-            let (remain, result) = collision_ex_kind_region(case).unwrap();
+            let (remain, result) =
+                collision_ex_kind_region(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -318,7 +344,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "region,atari.png,0,255,";
-            assert!(collision_ex_kind_region(case).is_err());
+            assert!(collision_ex_kind_region::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 }
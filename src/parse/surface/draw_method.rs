@@ -10,139 +10,168 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::{AnimationIdType, DrawMethod, DrawMethodOnAnimation},
-    parse::parts::digit,
+    parse::parts::{comma, digit, keyword, keyword_comma},
+    parse::ParseOptions,
 };
 
-pub(super) fn draw_method<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+pub(super) fn draw_method<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
     alt((
-        draw_method_base,
-        draw_method_overlayfast,
-        draw_method_overlaymultiply,
-        draw_method_overlay,
-        draw_method_replace,
-        draw_method_interpolate,
-        draw_method_asis,
-        draw_method_move,
-        draw_method_bind,
-        draw_method_add,
-        draw_method_reduce,
-    ))(input)
+        draw_method_base(options),
+        draw_method_overlayfast(options),
+        draw_method_overlaymultiply(options),
+        draw_method_overlay(options),
+        draw_method_replace(options),
+        draw_method_interpolate(options),
+        draw_method_asis(options),
+        draw_method_move(options),
+        draw_method_bind(options),
+        draw_method_add(options),
+        draw_method_reduce(options),
+    ))
 }
 
 pub(super) fn draw_method_on_animation<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
     alt((
-        draw_method_insert,
-        draw_method_start,
-        draw_method_stop,
-        draw_method_alternativestart,
-        draw_method_alternativestop,
-        draw_method_parallelstart,
-        draw_method_parallelstop,
-    ))(input)
+        draw_method_insert(options),
+        draw_method_start(options),
+        draw_method_stop(options),
+        draw_method_alternativestart(options),
+        draw_method_alternativestop(options),
+        draw_method_parallelstart(options),
+        draw_method_parallelstop(options),
+    ))
 }
 
-fn draw_method_base<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("base"), |_| DrawMethod::Base)(input)
+fn draw_method_base<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "base"), |_| DrawMethod::Base)
 }
 
-fn draw_method_overlay<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("overlay"), |_| DrawMethod::Overlay)(input)
+fn draw_method_overlay<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "overlay"), |_| DrawMethod::Overlay)
 }
 
-fn draw_method_overlayfast<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("overlayfast"), |_| DrawMethod::Overlayfast)(input)
+fn draw_method_overlayfast<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "overlayfast"), |_| DrawMethod::Overlayfast)
 }
 
 fn draw_method_overlaymultiply<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("overlaymultiply"), |_| DrawMethod::Overlaymultiply)(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "overlaymultiply"), |_| {
+        DrawMethod::Overlaymultiply
+    })
 }
 
-fn draw_method_replace<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("replace"), |_| DrawMethod::Replace)(input)
+fn draw_method_replace<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "replace"), |_| DrawMethod::Replace)
 }
 
-fn draw_method_interpolate<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("interpolate"), |_| DrawMethod::Interpolate)(input)
+fn draw_method_interpolate<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "interpolate"), |_| DrawMethod::Interpolate)
 }
 
-fn draw_method_asis<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("asis"), |_| DrawMethod::Asis)(input)
+fn draw_method_asis<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "asis"), |_| DrawMethod::Asis)
 }
 
-fn draw_method_move<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("move"), |_| DrawMethod::Move)(input)
+fn draw_method_move<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "move"), |_| DrawMethod::Move)
 }
 
-fn draw_method_bind<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("bind"), |_| DrawMethod::Bind)(input)
+fn draw_method_bind<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "bind"), |_| DrawMethod::Bind)
 }
 
-fn draw_method_add<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("add"), |_| DrawMethod::Add)(input)
+fn draw_method_add<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "add"), |_| DrawMethod::Add)
 }
 
-fn draw_method_reduce<'a>(input: &'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
-    map(tag("reduce"), |_| DrawMethod::Reduce)(input)
+fn draw_method_reduce<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethod, ShellParseError> {
+    map(keyword(options, "reduce"), |_| DrawMethod::Reduce)
 }
 
 fn draw_method_insert<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("insert,"), digit), |(_, v)| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(pair(keyword_comma(options, "insert"), digit), |(_, v)| {
         DrawMethodOnAnimation::Insert(v)
-    })(input)
+    })
 }
 
 fn draw_method_start<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("start,"), digit), |(_, v)| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(pair(keyword_comma(options, "start"), digit), |(_, v)| {
         DrawMethodOnAnimation::Start(v)
-    })(input)
+    })
 }
 
 fn draw_method_stop<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("stop,"), digit), |(_, v)| {
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(pair(keyword_comma(options, "stop"), digit), |(_, v)| {
         DrawMethodOnAnimation::Stop(v)
-    })(input)
+    })
 }
 
 fn draw_method_alternativestart<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("alternativestart,"), ids), |(_, v)| {
-        DrawMethodOnAnimation::Alternativestart(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(
+        pair(keyword_comma(options, "alternativestart"), ids),
+        |(_, v)| DrawMethodOnAnimation::Alternativestart(v),
+    )
 }
 
 fn draw_method_alternativestop<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("alternativestop,"), ids), |(_, v)| {
-        DrawMethodOnAnimation::Alternativestop(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(
+        pair(keyword_comma(options, "alternativestop"), ids),
+        |(_, v)| DrawMethodOnAnimation::Alternativestop(v),
+    )
 }
 
 fn draw_method_parallelstart<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("parallelstart,"), ids), |(_, v)| {
-        DrawMethodOnAnimation::Parallelstart(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(
+        pair(keyword_comma(options, "parallelstart"), ids),
+        |(_, v)| DrawMethodOnAnimation::Parallelstart(v),
+    )
 }
 
 fn draw_method_parallelstop<'a>(
-    input: &'a str,
-) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
-    map(pair(tag("parallelstop,"), ids), |(_, v)| {
-        DrawMethodOnAnimation::Parallelstop(v)
-    })(input)
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, DrawMethodOnAnimation, ShellParseError> {
+    map(
+        pair(keyword_comma(options, "parallelstop"), ids),
+        |(_, v)| DrawMethodOnAnimation::Parallelstop(v),
+    )
 }
 
 fn ids<'a>(input: &'a str) -> IResult<&'a str, Vec<AnimationIdType>, ShellParseError> {
@@ -162,18 +191,21 @@ fn ids_inner<'a>(input: &'a str) -> IResult<&'a str, Vec<AnimationIdType>, Shell
 }
 
 fn ids_inner_comma<'a>(input: &'a str) -> IResult<&'a str, Vec<AnimationIdType>, ShellParseError> {
-    ids_inner_body(",")(input)
+    ids_inner_body(comma)(input)
 }
 
 fn ids_inner_period<'a>(input: &'a str) -> IResult<&'a str, Vec<AnimationIdType>, ShellParseError> {
-    ids_inner_body(".")(input)
+    ids_inner_body(tag("."))(input)
 }
 
-fn ids_inner_body<'a>(
-    tag_str: &'static str,
-) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<AnimationIdType>, ShellParseError> {
+fn ids_inner_body<'a, F>(
+    separator: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<AnimationIdType>, ShellParseError>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, &'a str, ShellParseError>,
+{
     map(
-        pair(many1(terminated(digit, tag(tag_str))), digit),
+        pair(many1(terminated(digit, separator)), digit),
         |(mut vec, v)| {
             vec.push(v);
             vec
@@ -191,57 +223,57 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "base\r\n";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, DrawMethod::Base);
 
             let case = "overlay";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Overlay);
 
             let case = "overlayfast";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Overlayfast);
 
             let case = "overlaymultiply";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Overlaymultiply);
 
             let case = "replace";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Replace);
 
             let case = "interpolate";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Interpolate);
 
             let case = "asis";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Asis);
 
             let case = "move";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Move);
 
             let case = "bind";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Bind);
 
             let case = "add";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Add);
 
             let case = "reduce";
-            let (remain, result) = draw_method(case).unwrap();
+            let (remain, result) = draw_method(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethod::Reduce);
         }
@@ -249,7 +281,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "somethingwrong";
-            assert!(draw_method(case).is_err());
+            assert!(draw_method(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -259,22 +291,26 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "insert,10";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Insert(10));
 
             let case = "start,10";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Start(10));
 
             let case = "stop,10";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Stop(10));
 
             let case = "alternativestart,[10,20]";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
@@ -282,17 +318,20 @@ mod tests {
             );
 
             let case = "alternativestop,[10,20]";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Alternativestop(vec![10, 20]));
 
             let case = "parallelstart,[10,20]";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Parallelstart(vec![10, 20]));
 
             let case = "parallelstop,[10,20]";
-            let (remain, result) = draw_method_on_animation(case).unwrap();
+            let (remain, result) =
+                draw_method_on_animation(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, DrawMethodOnAnimation::Parallelstop(vec![10, 20]));
         }
@@ -300,7 +339,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "somethingwrong";
-            assert!(draw_method_on_animation(case).is_err());
+            assert!(draw_method_on_animation(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -430,6 +469,11 @@ mod tests {
             let (remain, result) = ids_inner_comma(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, vec![10, 20, 30]);
+
+            let case = "10, 20 ,30";
+            let (remain, result) = ids_inner_comma(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, vec![10, 20, 30]);
         }
 
         #[test]
@@ -1,5 +1,4 @@
 use nom::{
-    bytes::complete::{is_not, tag},
     combinator::map,
     sequence::{preceded, tuple},
     IResult,
@@ -8,25 +7,26 @@ use shell_parser_common_rs::ShellParseError;
 
 use crate::{
     ast::SurfaceElement,
-    parse::parts::{digit, digit_neg},
+    parse::parts::{comma, digit, digit_neg, keyword, text_field},
+    parse::ParseOptions,
 };
 
 use super::draw_method::draw_method;
 
-pub(super) fn element<'a>(input: &'a str) -> IResult<&'a str, SurfaceElement, ShellParseError> {
+pub(super) fn element<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceElement<S>, ShellParseError> {
     map(
         tuple((
-            tag("element"),
+            keyword(options, "element"),
             digit,
-            preceded(tag(","), draw_method),
-            preceded(tag(","), is_not(",")),
-            preceded(tag(","), digit_neg),
-            preceded(tag(","), digit_neg),
+            preceded(comma, draw_method(options)),
+            preceded(comma, text_field(",")),
+            preceded(comma, digit_neg),
+            preceded(comma, digit_neg),
         )),
-        |(_, id, method, filename, x, y)| {
-            SurfaceElement::new(id, method, filename.to_string(), x, y)
-        },
-    )(input)
+        |(_, id, method, filename, x, y)| SurfaceElement::new(id, method, S::from(filename), x, y),
+    )
 }
 
 #[cfg(test)]
@@ -41,19 +41,25 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "element0,overlay,body0.png,0,0";
-            let (remain, result) = element(case).unwrap();
+            let (remain, result) = element::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &0);
             assert_eq!(result.method(), &DrawMethod::Overlay);
             assert_eq!(result.filename(), &"body0.png".to_string());
             assert_eq!(result.x(), &0);
             assert_eq!(result.y(), &0);
+
+            let case = "element0, overlay, face.png , -5, 0";
+            let (remain, result) = element::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result.filename(), &"face.png".to_string());
+            assert_eq!(result.x(), &-5);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "element,overlay,s.png,0";
-            assert!(element(case).is_err());
+            assert!(element::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 }
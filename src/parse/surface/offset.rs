@@ -1,95 +1,102 @@
 use std::str::FromStr;
 
-use nom::{bytes::complete::tag, combinator::map, sequence::tuple, IResult};
+use nom::{combinator::map, sequence::tuple, IResult};
 use shell_parser_common_rs::ShellParseError;
 
-use crate::{parse::parts::digit_neg, SurfaceInner};
-
-pub(super) fn sakura_balloon_offset_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("sakura.balloon.offsetx,", |v| {
-        SurfaceInner::SakuraBalloonOffsetX(v)
-    })(input)
-}
-
-pub(super) fn sakura_balloon_offset_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("sakura.balloon.offsety,", |v| {
-        SurfaceInner::SakuraBalloonOffsetY(v)
-    })(input)
+use crate::{
+    parse::parts::{digit_neg, keyword_comma, surface_target_character_id},
+    parse::ParseOptions,
+    SurfaceInner,
+};
+
+pub(super) fn character_balloon_offset_x<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    map(
+        tuple((
+            surface_target_character_id(options),
+            keyword_comma(options, ".balloon.offsetx"),
+            digit_neg,
+        )),
+        |(id, _, v)| SurfaceInner::character_balloon_offset_x(id, v),
+    )
 }
 
-pub(super) fn kero_balloon_offset_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("kero.balloon.offsetx,", |v| {
-        SurfaceInner::KeroBalloonOffsetX(v)
-    })(input)
+pub(super) fn character_balloon_offset_y<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    map(
+        tuple((
+            surface_target_character_id(options),
+            keyword_comma(options, ".balloon.offsety"),
+            digit_neg,
+        )),
+        |(id, _, v)| SurfaceInner::character_balloon_offset_y(id, v),
+    )
 }
 
-pub(super) fn kero_balloon_offset_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("kero.balloon.offsety,", |v| {
-        SurfaceInner::KeroBalloonOffsetY(v)
-    })(input)
+pub(super) fn balloon_offset_x<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "balloon.offsetx", |v| {
+        SurfaceInner::BalloonOffsetX(v)
+    })
 }
 
-pub(super) fn balloon_offset_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("balloon.offsetx,", |v| SurfaceInner::BalloonOffsetX(v))(input)
+pub(super) fn balloon_offset_y<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "balloon.offsety", |v| {
+        SurfaceInner::BalloonOffsetY(v)
+    })
 }
 
-pub(super) fn balloon_offset_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("balloon.offsety,", |v| SurfaceInner::BalloonOffsetY(v))(input)
+pub(super) fn point_center_x<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.centerx", |v| SurfaceInner::PointCenterX(v))
 }
 
-pub(super) fn point_center_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.centerx,", |v| SurfaceInner::PointCenterX(v))(input)
+pub(super) fn point_center_y<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.centery", |v| SurfaceInner::PointCenterY(v))
 }
 
-pub(super) fn point_center_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.centery,", |v| SurfaceInner::PointCenterY(v))(input)
-}
-
-pub(super) fn point_kinoko_center_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.kinoko.centerx,", |v| {
+pub(super) fn point_kinoko_center_x<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.kinoko.centerx", |v| {
         SurfaceInner::PointKinokoCenterX(v)
-    })(input)
+    })
 }
 
-pub(super) fn point_kinoko_center_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.kinoko.centery,", |v| {
+pub(super) fn point_kinoko_center_y<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.kinoko.centery", |v| {
         SurfaceInner::PointKinokoCenterY(v)
-    })(input)
+    })
 }
 
-pub(super) fn point_basepos_x<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.basepos.x,", |v| SurfaceInner::PointBaseposX(v))(input)
+pub(super) fn point_basepos_x<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.basepos.x", |v| {
+        SurfaceInner::PointBaseposX(v)
+    })
 }
 
-pub(super) fn point_basepos_y<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceInner, ShellParseError> {
-    offset_base("point.basepos.y,", |v| SurfaceInner::PointBaseposY(v))(input)
+pub(super) fn point_basepos_y<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceInner<S>, ShellParseError> {
+    offset_base(options, "point.basepos.y", |v| {
+        SurfaceInner::PointBaseposY(v)
+    })
 }
 
 fn offset_base<'a, F, T, O>(
+    options: &ParseOptions,
     t: &'static str,
     mut f: F,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, T, ShellParseError>
@@ -97,82 +104,126 @@ where
     F: FnMut(O) -> T,
     O: FromStr + std::ops::Neg<Output = O>,
 {
-    map(tuple((tag(t), digit_neg)), move |(_, v)| f(v))
+    map(
+        tuple((keyword_comma(options, t), digit_neg)),
+        move |(_, v)| f(v),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SurfaceTargetCharacterId;
 
-    mod sakura_balloon_offset_x {
+    mod character_balloon_offset_x {
         use super::*;
 
         #[test]
-        fn success_when_valid_str() {
+        fn success_when_valid_str_sakura() {
             let case = "sakura.balloon.offsetx,10";
-            let (remain, result) = sakura_balloon_offset_x(case).unwrap();
+            let (remain, result) =
+                character_balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::SakuraBalloonOffsetX(10));
         }
 
         #[test]
-        fn failed_when_invalid_str() {
-            let case = "sakura.balloon.offsetx,";
-            assert!(sakura_balloon_offset_x(case).is_err());
+        fn success_when_valid_str_kero() {
+            let case = "kero.balloon.offsetx,10";
+            let (remain, result) =
+                character_balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, SurfaceInner::KeroBalloonOffsetX(10));
         }
-    }
-
-    mod sakura_balloon_offset_y {
-        use super::*;
 
         #[test]
-        fn success_when_valid_str() {
-            let case = "sakura.balloon.offsety,10";
-            let (remain, result) = sakura_balloon_offset_y(case).unwrap();
+        fn success_when_valid_str_char() {
+            let case = "char2.balloon.offsetx,-10";
+            let (remain, result) =
+                character_balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
-            assert_eq!(result, SurfaceInner::SakuraBalloonOffsetY(10));
+            assert_eq!(
+                result,
+                SurfaceInner::CharacterBalloonOffsetX(SurfaceTargetCharacterId::Char(2), -10)
+            );
+        }
+
+        #[test]
+        fn success_when_valid_str_char0_and_char1() {
+            let case = "char0.balloon.offsetx,10";
+            let (_, result) =
+                character_balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(result, SurfaceInner::SakuraBalloonOffsetX(10));
+
+            let case = "char1.balloon.offsetx,10";
+            let (_, result) =
+                character_balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(result, SurfaceInner::KeroBalloonOffsetX(10));
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = "sakura.balloon.offsety,";
-            assert!(sakura_balloon_offset_y(case).is_err());
+            let case = "sakura.balloon.offsetx,";
+            assert!(character_balloon_offset_x::<String>(&ParseOptions::default())(case).is_err());
+        }
+
+        #[test]
+        fn failed_when_no_character_number() {
+            let case = "char.balloon.offsetx,10";
+            assert!(character_balloon_offset_x::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
-    mod kero_balloon_offset_x {
+    mod character_balloon_offset_y {
         use super::*;
 
         #[test]
-        fn success_when_valid_str() {
-            let case = "kero.balloon.offsetx,10";
-            let (remain, result) = kero_balloon_offset_x(case).unwrap();
+        fn success_when_valid_str_sakura() {
+            let case = "sakura.balloon.offsety,10";
+            let (remain, result) =
+                character_balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
-            assert_eq!(result, SurfaceInner::KeroBalloonOffsetX(10));
+            assert_eq!(result, SurfaceInner::SakuraBalloonOffsetY(10));
         }
 
         #[test]
-        fn failed_when_invalid_str() {
-            let case = "kero.balloon.offsetx,";
-            assert!(kero_balloon_offset_x(case).is_err());
+        fn success_when_valid_str_kero() {
+            let case = "kero.balloon.offsety,10";
+            let (remain, result) =
+                character_balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, SurfaceInner::KeroBalloonOffsetY(10));
         }
-    }
-
-    mod kero_balloon_offset_y {
-        use super::*;
 
         #[test]
-        fn success_when_valid_str() {
-            let case = "kero.balloon.offsety,10";
-            let (remain, result) = kero_balloon_offset_y(case).unwrap();
+        fn success_when_valid_str_char() {
+            let case = "char2.balloon.offsety,10";
+            let (remain, result) =
+                character_balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                SurfaceInner::CharacterBalloonOffsetY(SurfaceTargetCharacterId::Char(2), 10)
+            );
+        }
+
+        #[test]
+        fn success_when_valid_str_char0_and_char1() {
+            let case = "char0.balloon.offsety,10";
+            let (_, result) =
+                character_balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
+            assert_eq!(result, SurfaceInner::SakuraBalloonOffsetY(10));
+
+            let case = "char1.balloon.offsety,10";
+            let (_, result) =
+                character_balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(result, SurfaceInner::KeroBalloonOffsetY(10));
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "kero.balloon.offsety,";
-            assert!(kero_balloon_offset_y(case).is_err());
+            assert!(character_balloon_offset_y::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -182,7 +233,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "balloon.offsetx,10";
-            let (remain, result) = balloon_offset_x(case).unwrap();
+            let (remain, result) =
+                balloon_offset_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::BalloonOffsetX(10));
         }
@@ -190,7 +242,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "balloon.offsetx,";
-            assert!(balloon_offset_x(case).is_err());
+            assert!(balloon_offset_x::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -200,7 +252,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "balloon.offsety,10";
-            let (remain, result) = balloon_offset_y(case).unwrap();
+            let (remain, result) =
+                balloon_offset_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::BalloonOffsetY(10));
         }
@@ -208,7 +261,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "balloon.offsety,";
-            assert!(balloon_offset_y(case).is_err());
+            assert!(balloon_offset_y::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -218,7 +271,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.centerx,10";
-            let (remain, result) = point_center_x(case).unwrap();
+            let (remain, result) =
+                point_center_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointCenterX(10));
         }
@@ -226,7 +280,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.centerx,";
-            assert!(point_center_x(case).is_err());
+            assert!(point_center_x::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -236,7 +290,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.centery,10";
-            let (remain, result) = point_center_y(case).unwrap();
+            let (remain, result) =
+                point_center_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointCenterY(10));
         }
@@ -244,7 +299,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.centery,";
-            assert!(point_center_y(case).is_err());
+            assert!(point_center_y::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -254,7 +309,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.kinoko.centerx,10";
-            let (remain, result) = point_kinoko_center_x(case).unwrap();
+            let (remain, result) =
+                point_kinoko_center_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointKinokoCenterX(10));
         }
@@ -262,7 +318,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.kinoko.centerx,";
-            assert!(point_kinoko_center_x(case).is_err());
+            assert!(point_kinoko_center_x::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -272,7 +328,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.kinoko.centery,10";
-            let (remain, result) = point_kinoko_center_y(case).unwrap();
+            let (remain, result) =
+                point_kinoko_center_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointKinokoCenterY(10));
         }
@@ -280,7 +337,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.kinoko.centery,";
-            assert!(point_kinoko_center_y(case).is_err());
+            assert!(point_kinoko_center_y::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -290,7 +347,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.basepos.x,10";
-            let (remain, result) = point_basepos_x(case).unwrap();
+            let (remain, result) =
+                point_basepos_x::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointBaseposX(10));
         }
@@ -298,7 +356,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.basepos.x,";
-            assert!(point_basepos_x(case).is_err());
+            assert!(point_basepos_x::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -308,7 +366,8 @@ mod tests {
         #[test]
         fn success_when_valid_str() {
             let case = "point.basepos.y,10";
-            let (remain, result) = point_basepos_y(case).unwrap();
+            let (remain, result) =
+                point_basepos_y::<String>(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, SurfaceInner::PointBaseposY(10));
         }
@@ -316,7 +375,7 @@ mod tests {
         #[test]
         fn failed_when_invalid_str() {
             let case = "point.basepos.y,";
-            assert!(point_basepos_y(case).is_err());
+            assert!(point_basepos_y::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -327,18 +386,28 @@ mod tests {
 
         #[test]
         fn success_when_valid_str() {
-            let case_func = |v| SurfaceInner::SakuraBalloonOffsetX(v);
+            let case_func = |v| SurfaceInner::<String>::SakuraBalloonOffsetX(v);
             let case = "sakura.balloon.offsetx,10\r\n";
-            let (remain, result) = offset_base("sakura.balloon.offsetx,", case_func)(case).unwrap();
+            let (remain, result) = offset_base(
+                &ParseOptions::default(),
+                "sakura.balloon.offsetx",
+                case_func,
+            )(case)
+            .unwrap();
             assert_eq!(remain, "\r\n");
             assert_eq!(result, SurfaceInner::SakuraBalloonOffsetX(10));
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case_func = |v| SurfaceInner::SakuraBalloonOffsetX(v);
+            let case_func = |v| SurfaceInner::<String>::SakuraBalloonOffsetX(v);
             let case = "sakura.balloon.offsetx,\r\n";
-            assert!(offset_base("sakura.balloon.offsetx,", case_func)(case).is_err());
+            assert!(offset_base(
+                &ParseOptions::default(),
+                "sakura.balloon.offsetx",
+                case_func
+            )(case)
+            .is_err());
         }
     }
 }
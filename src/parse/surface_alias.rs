@@ -1,5 +1,5 @@
 use nom::{
-    bytes::complete::{is_not, tag},
+    bytes::complete::tag,
     combinator::map,
     multi::separated_list1,
     sequence::{delimited, terminated, tuple},
@@ -8,47 +8,61 @@ use nom::{
 use shell_parser_common_rs::ShellParseError;
 
 use crate::{
-    ast::{SurfaceAlias, SurfaceAliasInner},
-    Brace, BraceContainer, SurfaceTargetCharacterId,
+    ast::{AliasTarget, SurfaceAlias, SurfaceAliasInner},
+    parse::ParseOptions,
+    Brace, BraceContainer, BraceLayout, SurfaceTargetCharacterId,
 };
 
 use super::parts::{
-    brace_name_func, digit, header_comments_func, inner_brace_func, surface_target_character_id,
+    brace_container, brace_name_func, comma, digit, header_comments_func, inner_brace_func,
+    keyword, surface_target_character_id, text_field,
 };
 
-pub(super) fn brace_surface_alias<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_surface_alias<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
-        tuple((header_comments_func(surface_alias_name), surface_alias)),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::SurfaceAlias(body)),
-    )(input)
+        tuple((
+            header_comments_func(surface_alias_name(options)),
+            surface_alias(options),
+        )),
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::SurfaceAlias(body), layout)
+        },
+    )
 }
 
-fn surface_alias<'a>(input: &'a str) -> IResult<&'a str, SurfaceAlias, ShellParseError> {
+fn surface_alias<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (SurfaceAlias<S>, BraceLayout), ShellParseError> {
     map(
-        tuple((surface_alias_name, inner_brace_func(surface_alias_inner))),
-        |(id, lines)| SurfaceAlias::new(id, lines),
-    )(input)
+        tuple((
+            surface_alias_name(options),
+            inner_brace_func(surface_alias_inner),
+        )),
+        |(id, (layout, lines))| (SurfaceAlias::new(id, lines), layout),
+    )
 }
 
-fn surface_alias_name<'a>(
-    input: &'a str,
-) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+pub(super) fn surface_alias_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
     brace_name_func(terminated(
-        surface_target_character_id,
-        tag(".surface.alias"),
-    ))(input)
+        surface_target_character_id(options),
+        keyword(options, ".surface.alias"),
+    ))
 }
 
-fn surface_alias_inner<'a>(input: &'a str) -> IResult<&'a str, SurfaceAliasInner, ShellParseError> {
+fn surface_alias_inner<'a, S: From<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, SurfaceAliasInner<S>, ShellParseError> {
     map(
         tuple((
-            is_not("},\r\n"),
-            tag(","),
-            delimited(tag("["), separated_list1(tag(","), digit), tag("]")),
+            text_field("},\r\n"),
+            comma,
+            delimited(tag("["), separated_list1(comma, digit), tag("]")),
         )),
-        |(target, _, v)| SurfaceAliasInner::new(target.to_string(), v),
+        |(target, _, v)| SurfaceAliasInner::new(AliasTarget::from(target), v),
     )(input)
 }
 
@@ -72,7 +86,7 @@ sakura.surface.alias
 照れ,[1,101,201]
 驚き,[2]
 }"#;
-            let (remain, result) = brace_surface_alias(case).unwrap();
+            let (remain, result) = brace_surface_alias(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -86,12 +100,18 @@ sakura.surface.alias
                 &Brace::SurfaceAlias(SurfaceAlias::new(
                     crate::ast::SurfaceTargetCharacterId::Sakura,
                     vec![
-                        LineContainer::Body(SurfaceAliasInner::new("素".to_string(), vec![0])),
-                        LineContainer::Body(SurfaceAliasInner::new(
-                            "照れ".to_string(),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("素".to_string()),
+                            vec![0]
+                        )),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("照れ".to_string()),
                             vec![1, 101, 201]
                         )),
-                        LineContainer::Body(SurfaceAliasInner::new("驚き".to_string(), vec![2])),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("驚き".to_string()),
+                            vec![2]
+                        )),
                     ]
                 ))
             );
@@ -100,12 +120,12 @@ sakura.surface.alias
         #[test]
         fn failed_when_invalid_str() {
             let case = r#"
-sakura.surface.alias{
+sakura.surface.alias x{
 素,[0]
 照れ,[1,101,201]
 驚き,[2]
 }"#;
-            assert!(brace_surface_alias(case).is_err());
+            assert!(brace_surface_alias::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -122,19 +142,25 @@ sakura.surface.alias{
 照れ,[1,101,201]
 驚き,[2]
 }"#;
-            let (remain, result) = surface_alias(case).unwrap();
+            let (remain, (result, _)) = surface_alias(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result,
                 SurfaceAlias::new(
                     crate::ast::SurfaceTargetCharacterId::Sakura,
                     vec![
-                        LineContainer::Body(SurfaceAliasInner::new("素".to_string(), vec![0])),
-                        LineContainer::Body(SurfaceAliasInner::new(
-                            "照れ".to_string(),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("素".to_string()),
+                            vec![0]
+                        )),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("照れ".to_string()),
                             vec![1, 101, 201]
                         )),
-                        LineContainer::Body(SurfaceAliasInner::new("驚き".to_string(), vec![2])),
+                        LineContainer::body_line(SurfaceAliasInner::new(
+                            AliasTarget::Name("驚き".to_string()),
+                            vec![2]
+                        )),
                     ]
                 )
             );
@@ -148,7 +174,7 @@ sakura.surface.alias{
 照れ,[1,101,201]
 驚き,[2]
 }"#;
-            assert!(surface_alias(case).is_err());
+            assert!(surface_alias::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -158,15 +184,15 @@ sakura.surface.alias{
         #[test]
         fn success_when_valid_str() {
             let case = "sakura.surface.alias\r\n{";
-            let (remain, result) = surface_alias_name(case).unwrap();
-            assert_eq!(remain, "{");
+            let (remain, result) = surface_alias_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n{");
             assert_eq!(result, SurfaceTargetCharacterId::Sakura);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "sakura.surface.alias";
-            assert!(surface_alias_name(case).is_err());
+            assert!(surface_alias_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -180,14 +206,29 @@ sakura.surface.alias{
             assert_eq!(remain, "\r\n");
             assert_eq!(
                 result,
-                SurfaceAliasInner::new("照れ".to_string(), vec![1, 101, 201])
+                SurfaceAliasInner::new(AliasTarget::Name("照れ".to_string()), vec![1, 101, 201])
             );
         }
 
+        #[test]
+        fn success_when_numeric_target() {
+            let case = "0,[0,100]\r\n";
+            let (remain, result) = surface_alias_inner::<String>(case).unwrap();
+            assert_eq!(remain, "\r\n");
+            assert_eq!(
+                result,
+                SurfaceAliasInner::new(AliasTarget::Id(0), vec![0, 100])
+            );
+
+            let case = "00,[1]\r\n";
+            let (_, result) = surface_alias_inner::<String>(case).unwrap();
+            assert_eq!(result.target(), &AliasTarget::Name("00".to_string()));
+        }
+
         #[test]
         fn failed_when_invalid_str() {
             let case = "照れ,1,101,201\r\n";
-            assert!(surface_alias_inner(case).is_err());
+            assert!(surface_alias_inner::<String>(case).is_err());
         }
     }
 }
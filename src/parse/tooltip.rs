@@ -1,42 +1,59 @@
 use nom::{
-    bytes::complete::{is_not, tag},
     combinator::map,
     sequence::{terminated, tuple},
     IResult,
 };
 use shell_parser_common_rs::ShellParseError;
 
-use crate::{Brace, BraceContainer, SurfaceTargetCharacterId, Tooltip, TooltipInner};
+use crate::{
+    parse::ParseOptions, Brace, BraceContainer, BraceLayout, SurfaceTargetCharacterId, Tooltip,
+    TooltipInner,
+};
 
 use super::parts::{
-    brace_name_func, header_comments_func, inner_brace_func, surface_target_character_id,
+    brace_container, brace_name_func, comma, header_comments_func, inner_brace_func, keyword,
+    surface_target_character_id, text_before_comment, text_field,
 };
 
-pub(super) fn brace_tooltip<'a>(
-    input: &'a str,
-) -> IResult<&'a str, BraceContainer, ShellParseError> {
+pub(super) fn brace_tooltip<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, BraceContainer<S>, ShellParseError> {
     map(
-        tuple((header_comments_func(tooltip_name), tooltip)),
-        |(header_comments, body)| BraceContainer::new(header_comments, Brace::Tooltip(body)),
-    )(input)
+        tuple((
+            header_comments_func(tooltip_name(options)),
+            tooltip(options),
+        )),
+        |(header_comments, (body, layout))| {
+            brace_container(header_comments, Brace::Tooltip(body), layout)
+        },
+    )
 }
 
-fn tooltip<'a>(input: &'a str) -> IResult<&'a str, Tooltip, ShellParseError> {
+fn tooltip<'a, S: From<&'a str>>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (Tooltip<S>, BraceLayout), ShellParseError> {
     map(
-        tuple((tooltip_name, inner_brace_func(tooltip_inner))),
-        |(id, lines)| Tooltip::new(id, lines),
-    )(input)
+        tuple((tooltip_name(options), inner_brace_func(tooltip_inner))),
+        |(id, (layout, lines))| (Tooltip::new(id, lines), layout),
+    )
 }
 
-fn tooltip_name<'a>(input: &'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
-    brace_name_func(terminated(surface_target_character_id, tag(".tooltips")))(input)
+pub(super) fn tooltip_name<'a>(
+    options: &ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, SurfaceTargetCharacterId, ShellParseError> {
+    brace_name_func(terminated(
+        surface_target_character_id(options),
+        keyword(options, ".tooltips"),
+    ))
 }
 
-fn tooltip_inner<'a>(input: &'a str) -> IResult<&'a str, TooltipInner, ShellParseError> {
+fn tooltip_inner<'a, S: From<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, TooltipInner<S>, ShellParseError> {
     map(
-        tuple((terminated(is_not(",\r\n"), tag(",")), is_not("\r\n"))),
+        tuple((terminated(text_field(",\r\n"), comma), text_before_comment)),
         |(collision, description): (&'a str, &'a str)| {
-            TooltipInner::new(collision.to_string(), description.to_string())
+            TooltipInner::new(S::from(collision), S::from(description))
         },
     )(input)
 }
@@ -61,7 +78,7 @@ Bust,怒ります。
 Head,つつかれると痛いです。
 Shoulder,つつくとコミュニケートボックスを表示します。
 }"#;
-            let (remain, result) = brace_tooltip(case).unwrap();
+            let (remain, result) = brace_tooltip(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(
                 result.header_comments(),
@@ -75,15 +92,15 @@ Shoulder,つつくとコミュニケートボックスを表示します。
                 &Brace::Tooltip(Tooltip::new(
                     SurfaceTargetCharacterId::Sakura,
                     vec![
-                        LineContainer::Body(TooltipInner::new(
+                        LineContainer::body_line(TooltipInner::new(
                             "Bust".to_string(),
                             "怒ります。".to_string()
                         )),
-                        LineContainer::Body(TooltipInner::new(
+                        LineContainer::body_line(TooltipInner::new(
                             "Head".to_string(),
                             "つつかれると痛いです。".to_string()
                         )),
-                        LineContainer::Body(TooltipInner::new(
+                        LineContainer::body_line(TooltipInner::new(
                             "Shoulder".to_string(),
                             "つつくとコミュニケートボックスを表示します。".to_string()
                         )),
@@ -97,7 +114,7 @@ Shoulder,つつくとコミュニケートボックスを表示します。
             let case = r#"sakura.tooooltips
 {
 }"#;
-            assert!(brace_tooltip(case).is_err());
+            assert!(brace_tooltip::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -114,21 +131,21 @@ Bust,怒ります。
 Head,つつかれると痛いです。
 Shoulder,つつくとコミュニケートボックスを表示します。
 }"#;
-            let (remain, result) = tooltip(case).unwrap();
+            let (remain, (result, _)) = tooltip(&ParseOptions::default())(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result.id(), &SurfaceTargetCharacterId::Sakura);
             assert_eq!(
                 result.lines(),
                 &vec![
-                    LineContainer::Body(TooltipInner::new(
+                    LineContainer::body_line(TooltipInner::new(
                         "Bust".to_string(),
                         "怒ります。".to_string()
                     )),
-                    LineContainer::Body(TooltipInner::new(
+                    LineContainer::body_line(TooltipInner::new(
                         "Head".to_string(),
                         "つつかれると痛いです。".to_string()
                     )),
-                    LineContainer::Body(TooltipInner::new(
+                    LineContainer::body_line(TooltipInner::new(
                         "Shoulder".to_string(),
                         "つつくとコミュニケートボックスを表示します。".to_string()
                     )),
@@ -141,7 +158,7 @@ Shoulder,つつくとコミュニケートボックスを表示します。
             let case = r#"sakura.tooooltips
 {
 }"#;
-            assert!(tooltip(case).is_err());
+            assert!(tooltip::<String>(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -151,15 +168,15 @@ Shoulder,つつくとコミュニケートボックスを表示します。
         #[test]
         fn success_when_valid_str() {
             let case = "sakura.tooltips\r\n{";
-            let (remain, result) = tooltip_name(case).unwrap();
-            assert_eq!(remain, "{");
+            let (remain, result) = tooltip_name(&ParseOptions::default())(case).unwrap();
+            assert_eq!(remain, "\r\n{");
             assert_eq!(result, SurfaceTargetCharacterId::Sakura);
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "sakura.tooltips";
-            assert!(tooltip_name(case).is_err());
+            assert!(tooltip_name(&ParseOptions::default())(case).is_err());
         }
     }
 
@@ -183,7 +200,7 @@ Shoulder,つつくとコミュニケートボックスを表示します。
         #[test]
         fn failed_when_invalid_str() {
             let case = "Shoulder";
-            assert!(tooltip_inner(case).is_err());
+            assert!(tooltip_inner::<String>(case).is_err());
         }
     }
 }
@@ -0,0 +1,814 @@
+//! Resolved view of each surface after merging braces, appends and aliases.
+//!
+//! Lines of every `surface` brace defining a surface and every `surface.append` brace targeting it
+//! are applied in the order of braces. A later line with the same id replaces the earlier one.
+//! Braces with `*` or `N-` (e.g. `surface.append*`) apply to every surface they match, but do not
+//! define any surface.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+use crate::ast::{
+    AliasTarget, AnimationIdType, AnimationInterval, AnimationOptionKind, Brace, CoordinateType,
+    LineContainer, ShellSurfaces, SurfaceAnimationPattern, SurfaceCollision, SurfaceCollisionEx,
+    SurfaceElement, SurfaceId, SurfaceIdType, SurfaceInner, SurfaceTargetCharacterId,
+};
+
+/// An alias pointing to a surface.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedAlias {
+    character: SurfaceTargetCharacterId,
+    target: AliasTarget,
+}
+
+impl ResolvedAlias {
+    pub fn new(character: SurfaceTargetCharacterId, target: AliasTarget) -> ResolvedAlias {
+        ResolvedAlias { character, target }
+    }
+
+    pub fn character(&self) -> &SurfaceTargetCharacterId {
+        &self.character
+    }
+    pub fn target(&self) -> &AliasTarget {
+        &self.target
+    }
+}
+
+/// Axis of an [`OffsetKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// A balloon offset or a point of a resolved surface.
+///
+/// Written as its name in `surfaces.txt` (e.g. `sakura.balloon.offsetx`), also as a JSON key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OffsetKey {
+    /// `balloon.offsetx` and `balloon.offsety`.
+    Balloon(Axis),
+    /// `sakura.balloon.offsetx`, `charN.balloon.offsety` and so on, by the number of the
+    /// character. `sakura` is 0 and `kero` is 1.
+    CharacterBalloon(u32, Axis),
+    PointCenter(Axis),
+    PointKinokoCenter(Axis),
+    PointBasepos(Axis),
+}
+
+impl OffsetKey {
+    /// Returns the key and the value of a balloon offset or a point line, or `None` for other
+    /// lines.
+    pub fn of(inner: &SurfaceInner) -> Option<(OffsetKey, CoordinateType)> {
+        let (key, v) = match inner {
+            SurfaceInner::SakuraBalloonOffsetX(v) => (OffsetKey::CharacterBalloon(0, Axis::X), v),
+            SurfaceInner::SakuraBalloonOffsetY(v) => (OffsetKey::CharacterBalloon(0, Axis::Y), v),
+            SurfaceInner::KeroBalloonOffsetX(v) => (OffsetKey::CharacterBalloon(1, Axis::X), v),
+            SurfaceInner::KeroBalloonOffsetY(v) => (OffsetKey::CharacterBalloon(1, Axis::Y), v),
+            SurfaceInner::CharacterBalloonOffsetX(id, v) => {
+                (OffsetKey::CharacterBalloon(id.index(), Axis::X), v)
+            }
+            SurfaceInner::CharacterBalloonOffsetY(id, v) => {
+                (OffsetKey::CharacterBalloon(id.index(), Axis::Y), v)
+            }
+            SurfaceInner::BalloonOffsetX(v) => (OffsetKey::Balloon(Axis::X), v),
+            SurfaceInner::BalloonOffsetY(v) => (OffsetKey::Balloon(Axis::Y), v),
+            SurfaceInner::PointCenterX(v) => (OffsetKey::PointCenter(Axis::X), v),
+            SurfaceInner::PointCenterY(v) => (OffsetKey::PointCenter(Axis::Y), v),
+            SurfaceInner::PointKinokoCenterX(v) => (OffsetKey::PointKinokoCenter(Axis::X), v),
+            SurfaceInner::PointKinokoCenterY(v) => (OffsetKey::PointKinokoCenter(Axis::Y), v),
+            SurfaceInner::PointBaseposX(v) => (OffsetKey::PointBasepos(Axis::X), v),
+            SurfaceInner::PointBaseposY(v) => (OffsetKey::PointBasepos(Axis::Y), v),
+            SurfaceInner::Element(_)
+            | SurfaceInner::AnimationInterval(_)
+            | SurfaceInner::AnimationPattern(_)
+            | SurfaceInner::AnimationOption(_)
+            | SurfaceInner::AnimationCollision(_)
+            | SurfaceInner::AnimationCollisionEx(_)
+            | SurfaceInner::Collision(_)
+            | SurfaceInner::CollisionEx(_) => return None,
+        };
+        Some((key, *v))
+    }
+}
+
+impl Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Axis::X => write!(f, "x"),
+            Axis::Y => write!(f, "y"),
+        }
+    }
+}
+
+impl Display for OffsetKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffsetKey::Balloon(a) => write!(f, "balloon.offset{}", a),
+            OffsetKey::CharacterBalloon(0, a) => write!(f, "sakura.balloon.offset{}", a),
+            OffsetKey::CharacterBalloon(1, a) => write!(f, "kero.balloon.offset{}", a),
+            OffsetKey::CharacterBalloon(n, a) => write!(f, "char{}.balloon.offset{}", n, a),
+            OffsetKey::PointCenter(a) => write!(f, "point.center{}", a),
+            OffsetKey::PointKinokoCenter(a) => write!(f, "point.kinoko.center{}", a),
+            OffsetKey::PointBasepos(a) => write!(f, "point.basepos.{}", a),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OffsetKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// An animation of a resolved surface.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedAnimation {
+    id: AnimationIdType,
+    intervals: Vec<AnimationInterval>,
+    options: Vec<AnimationOptionKind>,
+    patterns: Vec<SurfaceAnimationPattern>,
+    collisions: Vec<SurfaceCollision>,
+    collisions_ex: Vec<SurfaceCollisionEx>,
+}
+
+impl ResolvedAnimation {
+    fn new(id: AnimationIdType) -> ResolvedAnimation {
+        ResolvedAnimation {
+            id,
+            intervals: Vec::new(),
+            options: Vec::new(),
+            patterns: Vec::new(),
+            collisions: Vec::new(),
+            collisions_ex: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    pub fn intervals(&self) -> &Vec<AnimationInterval> {
+        &self.intervals
+    }
+    pub fn options(&self) -> &Vec<AnimationOptionKind> {
+        &self.options
+    }
+    /// Patterns sorted by pattern id.
+    pub fn patterns(&self) -> &Vec<SurfaceAnimationPattern> {
+        &self.patterns
+    }
+    pub fn collisions(&self) -> &Vec<SurfaceCollision> {
+        &self.collisions
+    }
+    pub fn collisions_ex(&self) -> &Vec<SurfaceCollisionEx> {
+        &self.collisions_ex
+    }
+}
+
+/// A surface after merging all lines applied to it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedSurface {
+    id: SurfaceIdType,
+    aliases: Vec<ResolvedAlias>,
+    elements: Vec<SurfaceElement>,
+    animations: Vec<ResolvedAnimation>,
+    collisions: Vec<SurfaceCollision>,
+    collisions_ex: Vec<SurfaceCollisionEx>,
+    offsets: BTreeMap<OffsetKey, CoordinateType>,
+}
+
+impl ResolvedSurface {
+    pub fn id(&self) -> &SurfaceIdType {
+        &self.id
+    }
+    /// Aliases whose candidates include this surface.
+    pub fn aliases(&self) -> &Vec<ResolvedAlias> {
+        &self.aliases
+    }
+    /// Elements sorted by id.
+    pub fn elements(&self) -> &Vec<SurfaceElement> {
+        &self.elements
+    }
+    /// Animations sorted by id.
+    pub fn animations(&self) -> &Vec<ResolvedAnimation> {
+        &self.animations
+    }
+    /// Collisions sorted by id.
+    pub fn collisions(&self) -> &Vec<SurfaceCollision> {
+        &self.collisions
+    }
+    /// Collisions of `collisionex` sorted by id.
+    pub fn collisions_ex(&self) -> &Vec<SurfaceCollisionEx> {
+        &self.collisions_ex
+    }
+    /// Balloon offsets and points. `sakura` and `char0` set the same offset.
+    pub fn offsets(&self) -> &BTreeMap<OffsetKey, CoordinateType> {
+        &self.offsets
+    }
+
+    /// Returns a copy of this surface as surface `id`.
+    pub(crate) fn with_id(&self, id: SurfaceIdType) -> ResolvedSurface {
+        ResolvedSurface { id, ..self.clone() }
+    }
+}
+
+/// Resolves surface `id`, or returns `None` when no `surface` brace defines it.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{
+///     parse,
+///     resolve::{resolve_surface, Axis, OffsetKey},
+/// };
+///
+/// let case = r#"charset,UTF-8
+/// surface0-3
+/// {
+/// element0,overlay,body0.png,0,0
+/// collision0,0,0,10,10,Head
+/// }
+/// surface.append2
+/// {
+/// collision0,0,0,20,20,Head
+/// sakura.balloon.offsetx,80
+/// }
+/// "#;
+///
+/// let shell_surfaces = parse(case).unwrap();
+/// let surface = resolve_surface(&shell_surfaces, 2).unwrap();
+/// assert_eq!(surface.elements().len(), 1);
+/// assert_eq!(surface.collisions()[0].end_x(), &20);
+/// let key = OffsetKey::CharacterBalloon(0, Axis::X);
+/// assert_eq!(surface.offsets().get(&key), Some(&80));
+/// assert_eq!(key.to_string(), "sakura.balloon.offsetx");
+/// assert!(resolve_surface(&shell_surfaces, 4).is_none());
+/// ```
+pub fn resolve_surface(
+    shell_surfaces: &ShellSurfaces,
+    id: SurfaceIdType,
+) -> Option<ResolvedSurface> {
+    let mut defined = false;
+    let mut elements = BTreeMap::new();
+    let mut animations: BTreeMap<AnimationIdType, ResolvedAnimation> = BTreeMap::new();
+    let mut patterns = BTreeMap::new();
+    let mut animation_collisions = BTreeMap::new();
+    let mut animation_collisions_ex = BTreeMap::new();
+    let mut collisions = BTreeMap::new();
+    let mut collisions_ex = BTreeMap::new();
+    let mut offsets = BTreeMap::new();
+    let mut aliases = Vec::new();
+
+    for brace in shell_surfaces.braces() {
+        let lines = match brace.body() {
+            Brace::Surface(v) if v.defines(id) => {
+                defined = true;
+                v.lines()
+            }
+            Brace::Surface(v) if v.is_target(id) => v.lines(),
+            Brace::SurfaceAppend(v) if v.is_target(id) => v.lines(),
+            Brace::SurfaceAlias(v) => {
+                for inner in v.lines().iter().filter_map(|v| v.body()) {
+                    if inner.surfaces().contains(&id) {
+                        aliases.push(ResolvedAlias::new(v.id().clone(), inner.target().clone()));
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        for inner in lines.iter().filter_map(LineContainer::body) {
+            match inner {
+                SurfaceInner::Element(v) => {
+                    elements.insert(*v.id(), v.clone());
+                }
+                SurfaceInner::AnimationInterval(v) => {
+                    animations
+                        .entry(*v.id())
+                        .or_insert_with(|| ResolvedAnimation::new(*v.id()))
+                        .intervals = v.intervals().clone();
+                }
+                SurfaceInner::AnimationOption(v) => {
+                    animations
+                        .entry(*v.id())
+                        .or_insert_with(|| ResolvedAnimation::new(*v.id()))
+                        .options = v.options().clone();
+                }
+                SurfaceInner::AnimationPattern(v) => {
+                    animations
+                        .entry(*v.id())
+                        .or_insert_with(|| ResolvedAnimation::new(*v.id()));
+                    patterns.insert((*v.id(), *v.pattern_id()), v.clone());
+                }
+                SurfaceInner::AnimationCollision(v) => {
+                    animations
+                        .entry(*v.id())
+                        .or_insert_with(|| ResolvedAnimation::new(*v.id()));
+                    animation_collisions
+                        .insert((*v.id(), *v.collision().id()), v.collision().clone());
+                }
+                SurfaceInner::AnimationCollisionEx(v) => {
+                    animations
+                        .entry(*v.id())
+                        .or_insert_with(|| ResolvedAnimation::new(*v.id()));
+                    animation_collisions_ex
+                        .insert((*v.id(), *v.collision().id()), v.collision().clone());
+                }
+                SurfaceInner::Collision(v) => {
+                    collisions.insert(*v.id(), v.clone());
+                }
+                SurfaceInner::CollisionEx(v) => {
+                    collisions_ex.insert(*v.id(), v.clone());
+                }
+                offset => {
+                    if let Some((key, value)) = OffsetKey::of(offset) {
+                        offsets.insert(key, value);
+                    }
+                }
+            }
+        }
+    }
+
+    if !defined {
+        return None;
+    }
+
+    for ((animation_id, _), v) in patterns {
+        if let Some(a) = animations.get_mut(&animation_id) {
+            a.patterns.push(v);
+        }
+    }
+    for ((animation_id, _), v) in animation_collisions {
+        if let Some(a) = animations.get_mut(&animation_id) {
+            a.collisions.push(v);
+        }
+    }
+    for ((animation_id, _), v) in animation_collisions_ex {
+        if let Some(a) = animations.get_mut(&animation_id) {
+            a.collisions_ex.push(v);
+        }
+    }
+
+    Some(ResolvedSurface {
+        id,
+        aliases,
+        elements: elements.into_values().collect(),
+        animations: animations.into_values().collect(),
+        collisions: collisions.into_values().collect(),
+        collisions_ex: collisions_ex.into_values().collect(),
+        offsets,
+    })
+}
+
+/// Resolves all surfaces defined in `shell_surfaces`, sorted by id.
+///
+/// Surfaces are resolved once per range of ids sharing the same braces and aliases, and yielded
+/// one by one, so a large range such as `surface0-4294967295` is never collected.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{parse, resolve::resolve_surfaces};
+///
+/// let case = "charset,UTF-8\r\nsurface0-4294967295\r\n{\r\nelement0,overlay,body0.png,0,0\r\n}\r\n";
+/// let shell_surfaces = parse(case).unwrap();
+///
+/// let ids: Vec<_> = resolve_surfaces(&shell_surfaces).take(2).map(|v| *v.id()).collect();
+/// assert_eq!(ids, vec![0, 1]);
+/// ```
+pub fn resolve_surfaces(
+    shell_surfaces: &ShellSurfaces,
+) -> impl Iterator<Item = ResolvedSurface> + '_ {
+    resolved_ranges(shell_surfaces, bounds(shell_surfaces))
+        .filter_map(|(range, v)| v.map(|v| (range, v)))
+        .flat_map(|(range, v)| range.map(move |id| v.with_id(id)))
+}
+
+/// Returns the ids where braces and alias lines applying to a surface change.
+pub(crate) fn bounds(shell_surfaces: &ShellSurfaces) -> BTreeSet<SurfaceIdType> {
+    let mut result = BTreeSet::new();
+    for brace in shell_surfaces.braces() {
+        match brace.body() {
+            Brace::Surface(v) => result.extend(v.ids().iter().flat_map(SurfaceId::bounds)),
+            Brace::SurfaceAppend(v) => result.extend(v.ids().iter().flat_map(SurfaceId::bounds)),
+            Brace::SurfaceAlias(v) => {
+                for inner in v.lines().iter().filter_map(LineContainer::body) {
+                    result.extend(
+                        inner
+                            .surfaces()
+                            .iter()
+                            .flat_map(|v| SurfaceId::Unit(*v).bounds()),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Splits every id at `bounds` and resolves the first id of each range, which every other id
+/// of the range resolves alike when `bounds` include [`bounds`] of `shell_surfaces`.
+pub(crate) fn resolved_ranges(
+    shell_surfaces: &ShellSurfaces,
+    bounds: BTreeSet<SurfaceIdType>,
+) -> impl Iterator<Item = (RangeInclusive<SurfaceIdType>, Option<ResolvedSurface>)> + '_ {
+    SurfaceId::split(bounds).map(|range| {
+        let resolved = resolve_surface(shell_surfaces, *range.start());
+        (range, resolved)
+    })
+}
+
+/// Returns candidate surfaces of alias `target` for `character`.
+///
+/// `sakura` and `kero` match `char0` and `char1`. A later alias line with the same target
+/// replaces the earlier one.
+pub fn alias_surfaces(
+    shell_surfaces: &ShellSurfaces,
+    character: &SurfaceTargetCharacterId,
+    target: &AliasTarget,
+) -> Option<Vec<SurfaceIdType>> {
+    shell_surfaces
+        .braces()
+        .iter()
+        .filter_map(|v| match v.body() {
+            Brace::SurfaceAlias(a) if a.id().index() == character.index() => Some(a.lines()),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(LineContainer::body)
+        .rev()
+        .find(|v| v.target() == target)
+        .map(|v| v.surfaces().clone())
+}
+
+/// Picks the surface shown for alias `target` of `character`.
+///
+/// Candidates which are not defined are skipped. When more than one remains, `rng` receives
+/// their number and returns the index of the chosen one, wrapped around when it is out of
+/// range. An id without any alias shows the surface itself.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::{parse, resolve::resolve_alias, AliasTarget, SurfaceTargetCharacterId};
+///
+/// let case = "charset,UTF-8\r\nsurface0,1\r\n{\r\n}\r\nsakura.surface.alias\r\n{\r\n笑顔,[1,0,9]\r\n}\r\n";
+/// let shell_surfaces = parse(case).unwrap();
+///
+/// let target = AliasTarget::Name("笑顔".to_string());
+/// let result = resolve_alias(&shell_surfaces, &SurfaceTargetCharacterId::Sakura, &target, |len| {
+///     assert_eq!(len, 2);
+///     1
+/// });
+/// assert_eq!(result, Some(0));
+/// ```
+pub fn resolve_alias(
+    shell_surfaces: &ShellSurfaces,
+    character: &SurfaceTargetCharacterId,
+    target: &AliasTarget,
+    mut rng: impl FnMut(usize) -> usize,
+) -> Option<SurfaceIdType> {
+    let candidates: Vec<SurfaceIdType> = match alias_surfaces(shell_surfaces, character, target) {
+        Some(v) => v
+            .into_iter()
+            .filter(|id| shell_surfaces.defines_surface(*id))
+            .collect(),
+        None => match target {
+            AliasTarget::Id(id) if shell_surfaces.defines_surface(*id) => vec![*id],
+            _ => Vec::new(),
+        },
+    };
+    match candidates.len() {
+        0 => None,
+        1 => Some(candidates[0]),
+        len => Some(candidates[rng(len) % len]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    const CASE: &str = r#"charset,UTF-8
+surface0-2,!1
+{
+element1,overlay,face0.png,0,0
+element0,overlay,body0.png,0,0
+animation0.interval,sometimes
+animation0.pattern1,overlay,101,100,0,0
+animation0.pattern0,overlay,100,100,0,0
+collision1,0,0,10,10,Face
+collisionex0,Head,circle,10,10,5
+point.basepos.x,100
+char2.balloon.offsetx,30
+}
+surface2
+{
+element1,overlay,face2.png,0,0
+animation0.interval,rarely
+animation0.option,exclusive
+animation0.collision0,0,0,5,5,Mouth
+}
+surface.append0-2
+{
+animation0.pattern0,overlay,100,50,0,0
+collision1,0,0,20,20,Face
+}
+sakura.surface.alias
+{
+通常,[0,2]
+}
+kero.surface.alias
+{
+通常,[10]
+}
+"#;
+
+    mod resolve_surface {
+        use super::*;
+
+        #[test]
+        fn success_when_defined_id() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result = resolve_surface(&shell_surfaces, 2).unwrap();
+            assert_eq!(result.id(), &2);
+            assert_eq!(
+                result.aliases(),
+                &vec![ResolvedAlias::new(
+                    SurfaceTargetCharacterId::Sakura,
+                    AliasTarget::Name("通常".to_string())
+                )]
+            );
+            assert_eq!(
+                result
+                    .elements()
+                    .iter()
+                    .map(|v| v.filename().as_str())
+                    .collect::<Vec<_>>(),
+                vec!["body0.png", "face2.png"]
+            );
+
+            let animation = &result.animations()[0];
+            assert_eq!(animation.intervals(), &vec![AnimationInterval::Rarely]);
+            assert_eq!(
+                animation.options(),
+                &vec![AnimationOptionKind::Exclusive(None)]
+            );
+            assert_eq!(
+                animation
+                    .patterns()
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>(),
+                vec![
+                    "animation0.pattern0,overlay,100,50,0,0",
+                    "animation0.pattern1,overlay,101,100,0,0"
+                ]
+            );
+            assert_eq!(animation.collisions()[0].target_id(), "Mouth");
+
+            assert_eq!(result.collisions().len(), 1);
+            assert_eq!(result.collisions()[0].end_x(), &20);
+            assert_eq!(result.collisions_ex()[0].target_id(), "Head");
+            assert_eq!(
+                result.offsets().get(&OffsetKey::PointBasepos(Axis::X)),
+                Some(&100)
+            );
+            assert_eq!(
+                result
+                    .offsets()
+                    .get(&OffsetKey::CharacterBalloon(2, Axis::X)),
+                Some(&30)
+            );
+        }
+
+        #[test]
+        fn success_when_offsets_of_same_character() {
+            let case = "surface0\r\n{\r\nsakura.balloon.offsetx,10\r\nchar0.balloon.offsetx,20\r\nchar1.balloon.offsety,30\r\n}\r\n";
+            let shell_surfaces = parse(case).unwrap();
+            let result = resolve_surface(&shell_surfaces, 0).unwrap();
+            assert_eq!(
+                result.offsets(),
+                &BTreeMap::from([
+                    (OffsetKey::CharacterBalloon(0, Axis::X), 20),
+                    (OffsetKey::CharacterBalloon(1, Axis::Y), 30)
+                ])
+            );
+
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                serde_json::to_value(result.offsets()).unwrap(),
+                serde_json::json!({
+                    "sakura.balloon.offsetx": 20,
+                    "kero.balloon.offsety": 30
+                })
+            );
+        }
+
+        #[test]
+        fn success_when_open_ids() {
+            let case = r#"charset,UTF-8
+surface0,5
+{
+element0,overlay,body0.png,0,0
+}
+surface3-
+{
+collision0,0,0,10,10,Head
+}
+surface.append*,!0
+{
+element1,overlay,face.png,0,0
+}
+"#;
+            let shell_surfaces = parse(case).unwrap();
+            let result = resolve_surface(&shell_surfaces, 5).unwrap();
+            assert_eq!(result.elements().len(), 2);
+            assert_eq!(result.collisions().len(), 1);
+
+            let result = resolve_surface(&shell_surfaces, 0).unwrap();
+            assert_eq!(result.elements().len(), 1);
+            assert!(result.collisions().is_empty());
+
+            assert!(resolve_surface(&shell_surfaces, 3).is_none());
+            assert_eq!(shell_surfaces.surface_ids().collect::<Vec<_>>(), vec![0, 5]);
+        }
+
+        #[test]
+        fn failed_when_undefined_id() {
+            let shell_surfaces = parse(CASE).unwrap();
+            assert!(resolve_surface(&shell_surfaces, 1).is_none());
+            assert!(resolve_surface(&shell_surfaces, 10).is_none());
+        }
+    }
+
+    mod resolve_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result: Vec<_> = resolve_surfaces(&shell_surfaces).map(|v| *v.id()).collect();
+            assert_eq!(result, vec![0, 2]);
+        }
+
+        #[test]
+        fn success_when_large_range() {
+            let case = r#"charset,UTF-8
+surface0-4294967295,!3
+{
+element0,overlay,body0.png,0,0
+}
+surface.append2-4
+{
+element1,overlay,face.png,0,0
+}
+sakura.surface.alias
+{
+通常,[4]
+}
+"#;
+            let shell_surfaces = parse(case).unwrap();
+            assert_eq!(
+                shell_surfaces.surface_id_ranges(),
+                vec![0..=2, 4..=SurfaceIdType::MAX]
+            );
+
+            let result: Vec<_> = resolve_surfaces(&shell_surfaces).take(5).collect();
+            assert_eq!(
+                result.iter().map(|v| *v.id()).collect::<Vec<_>>(),
+                vec![0, 1, 2, 4, 5]
+            );
+            assert_eq!(
+                result
+                    .iter()
+                    .map(|v| (v.elements().len(), v.aliases().len()))
+                    .collect::<Vec<_>>(),
+                vec![(1, 0), (1, 0), (2, 0), (2, 1), (1, 0)]
+            );
+        }
+    }
+
+    mod alias_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_defined_alias() {
+            let shell_surfaces = parse(CASE).unwrap();
+            assert_eq!(
+                alias_surfaces(
+                    &shell_surfaces,
+                    &SurfaceTargetCharacterId::Kero,
+                    &AliasTarget::Name("通常".to_string())
+                ),
+                Some(vec![10])
+            );
+        }
+
+        #[test]
+        fn success_when_char_brace() {
+            let case = "charset,UTF-8\r\nchar0.surface.alias\r\n{\r\n通常,[0]\r\n}\r\n";
+            let shell_surfaces = parse(case).unwrap();
+            assert_eq!(
+                alias_surfaces(
+                    &shell_surfaces,
+                    &SurfaceTargetCharacterId::Sakura,
+                    &AliasTarget::Name("通常".to_string())
+                ),
+                Some(vec![0])
+            );
+        }
+
+        #[test]
+        fn failed_when_undefined_alias() {
+            let shell_surfaces = parse(CASE).unwrap();
+            assert_eq!(
+                alias_surfaces(
+                    &shell_surfaces,
+                    &SurfaceTargetCharacterId::Char(2),
+                    &AliasTarget::Name("通常".to_string())
+                ),
+                None
+            );
+        }
+    }
+
+    mod resolve_alias {
+        use super::*;
+
+        #[test]
+        fn success_when_multiple_candidates() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let target = AliasTarget::Name("通常".to_string());
+            let mut lens = Vec::new();
+            let result = resolve_alias(
+                &shell_surfaces,
+                &SurfaceTargetCharacterId::Sakura,
+                &target,
+                |len| {
+                    lens.push(len);
+                    1
+                },
+            );
+            assert_eq!(result, Some(2));
+            assert_eq!(lens, vec![2]);
+        }
+
+        #[test]
+        fn success_when_index_out_of_range() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let target = AliasTarget::Name("通常".to_string());
+            let sakura = SurfaceTargetCharacterId::Sakura;
+            assert_eq!(
+                resolve_alias(&shell_surfaces, &sakura, &target, |len| len),
+                Some(0)
+            );
+            assert_eq!(
+                resolve_alias(&shell_surfaces, &sakura, &target, |_| usize::MAX),
+                Some(2)
+            );
+        }
+
+        #[test]
+        fn success_when_id_without_alias() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result = resolve_alias(
+                &shell_surfaces,
+                &SurfaceTargetCharacterId::Sakura,
+                &AliasTarget::Id(2),
+                |_| unreachable!(),
+            );
+            assert_eq!(result, Some(2));
+        }
+
+        #[test]
+        fn failed_when_no_defined_candidate() {
+            let shell_surfaces = parse(CASE).unwrap();
+            let result = resolve_alias(
+                &shell_surfaces,
+                &SurfaceTargetCharacterId::Kero,
+                &AliasTarget::Name("通常".to_string()),
+                |_| unreachable!(),
+            );
+            assert_eq!(result, None);
+            let result = resolve_alias(
+                &shell_surfaces,
+                &SurfaceTargetCharacterId::Sakura,
+                &AliasTarget::Id(1),
+                |_| unreachable!(),
+            );
+            assert_eq!(result, None);
+        }
+    }
+}
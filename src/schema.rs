@@ -0,0 +1,87 @@
+//! JSON Schema of the serialized [`ShellSurfaces`].
+//!
+//! The schema is also shipped as `schema/shell_surfaces.schema.json`,
+//! which is checked against the AST types by a test.
+//! Run the tests with `UPDATE_SCHEMA=1` to regenerate the file after changing the AST.
+
+use schemars::{gen::SchemaSettings, schema::RootSchema};
+
+use crate::ast::ShellSurfaces;
+
+/// Path of the shipped schema, relative to the crate root.
+pub const SCHEMA_PATH: &str = "schema/shell_surfaces.schema.json";
+
+/// Generates the JSON Schema of [`ShellSurfaces`] serialized with the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_surfaces_rs::schema::shell_surfaces_schema;
+///
+/// let schema = shell_surfaces_schema();
+/// assert!(schema.definitions.contains_key("Brace"));
+/// ```
+pub fn shell_surfaces_schema() -> RootSchema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<ShellSurfaces>()
+}
+
+/// Generates the JSON Schema of [`ShellSurfaces`] as pretty-printed JSON text.
+pub fn shell_surfaces_schema_json() -> String {
+    let mut json = serde_json::to_string_pretty(&shell_surfaces_schema())
+        .expect("JSON Schema is always serializable");
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{fs, path::PathBuf};
+
+    use crate::parse::parse;
+
+    mod shell_surfaces_schema {
+        use super::*;
+
+        #[test]
+        fn success_when_shipped_schema_is_in_sync() {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SCHEMA_PATH);
+            let generated = shell_surfaces_schema_json();
+            if std::env::var_os("UPDATE_SCHEMA").is_some() {
+                fs::write(&path, &generated).unwrap();
+            }
+            let shipped = fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+            assert!(
+                shipped == generated,
+                "{} is out of date. Run the tests with UPDATE_SCHEMA=1 to regenerate it.",
+                SCHEMA_PATH
+            );
+        }
+
+        #[test]
+        fn success_when_describing_serialized_model() {
+            let schema = shell_surfaces_schema();
+            for name in [
+                "BraceContainer",
+                "Brace",
+                "SurfaceInner",
+                "CollisionExKind",
+                "AnimationInterval",
+                "AnimationOptionKind",
+                "Charset",
+            ] {
+                assert!(schema.definitions.contains_key(name), "{}", name);
+            }
+
+            let case = "charset,UTF-8\r\nsurface0\r\n{\r\nanimation0.interval,random,2\r\n}\r\n";
+            let value = serde_json::to_value(parse(case).unwrap()).unwrap();
+            let properties = &schema.schema.object.as_ref().unwrap().properties;
+            for key in value.as_object().unwrap().keys() {
+                assert!(properties.contains_key(key), "{}", key);
+            }
+        }
+    }
+}
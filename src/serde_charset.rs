@@ -0,0 +1,264 @@
+//! Serde support for [`Charset`] from `shell-parser-common-rs`.
+//!
+//! [`Charset`] is serialized as the name written in `surfaces.txt` (e.g. `"UTF-8"`, `"Shift_JIS"`).
+
+use std::fmt;
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use shell_parser_common_rs::charset::Charset;
+
+use crate::display::{charset_name, CHARSETS};
+
+/// Wrapper of [`Charset`] implementing `Serialize` and `Deserialize`.
+///
+/// # Examples
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_surfaces_rs::serde_charset::SerdeCharset;
+///
+/// let json = serde_json::to_string(&SerdeCharset(Charset::ShiftJIS)).unwrap();
+/// assert_eq!(json, r#""Shift_JIS""#);
+///
+/// let charset: SerdeCharset = serde_json::from_str(&json).unwrap();
+/// assert_eq!(charset.0, Charset::ShiftJIS);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerdeCharset(pub Charset);
+
+impl From<Charset> for SerdeCharset {
+    fn from(value: Charset) -> Self {
+        SerdeCharset(value)
+    }
+}
+
+impl From<SerdeCharset> for Charset {
+    fn from(value: SerdeCharset) -> Self {
+        value.0
+    }
+}
+
+impl Serialize for SerdeCharset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SerdeCharset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(SerdeCharset)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for SerdeCharset {
+    fn schema_name() -> String {
+        "Charset".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Metadata, SchemaObject};
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("Charset name written in surfaces.txt.".to_string()),
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(CHARSETS.iter().map(|v| charset_name(v).into()).collect()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+struct CharsetVisitor;
+
+impl<'de> Visitor<'de> for CharsetVisitor {
+    type Value = Charset;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a charset name of surfaces.txt")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        CHARSETS
+            .iter()
+            .find(|charset| charset_name(charset) == v)
+            .cloned()
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(
+    charset: &Charset,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(charset_name(charset))
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Charset, D::Error> {
+    deserializer.deserialize_str(CharsetVisitor)
+}
+
+/// Default of `charset_declared` of `ShellSurfaces`, for JSON written before the field existed.
+pub(crate) fn charset_declared_default() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast::*, parse::parse};
+
+    mod serde_charset {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_name() {
+            for (charset, name) in [
+                (Charset::ASCII, r#""ASCII""#),
+                (Charset::ShiftJIS, r#""Shift_JIS""#),
+                (Charset::UTF8, r#""UTF-8""#),
+            ] {
+                let json = serde_json::to_string(&SerdeCharset(charset.clone())).unwrap();
+                assert_eq!(json, name);
+                let result: SerdeCharset = serde_json::from_str(&json).unwrap();
+                assert_eq!(result.0, charset);
+            }
+        }
+
+        #[test]
+        fn failed_when_invalid_name() {
+            assert!(serde_json::from_str::<SerdeCharset>(r#""UTF-8x""#).is_err());
+            assert!(serde_json::from_str::<SerdeCharset>(r#""utf8""#).is_err());
+            assert!(serde_json::from_str::<SerdeCharset>("8").is_err());
+        }
+    }
+
+    mod shell_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_round_trip() {
+            let case = r#"// header
+charset,Shift_JIS
+descript
+{
+version,1
+collision-sort,ascend
+}
+surface0-3,!2
+{
+element0,overlay,body0.png,0,0
+animation0.interval,random,2+bind
+animation0.pattern0,overlay,101,100,168,67
+animation0.pattern1,alternativestart,(1,2)
+animation0.option,exclusive,(1)
+animation0.collisionex0,Head,polygon,0,0,10,0,10,10
+collision0,40,56,95,90,Head
+collisionex1,Face,region,mask.png,255,0,0
+// comment
+sakura.balloon.offsetx,-20
+}
+surface.append10
+{
+point.basepos.x,100
+}
+sakura.surface.alias
+{
+通常,[0]
+}
+kero.cursor
+{
+mouseup0,Head,cursor.cur
+}
+char2.tooltips
+{
+Bust,怒ります。
+}
+// footer
+"#;
+            let shell_surfaces = parse(case).unwrap();
+            let json = serde_json::to_string(&shell_surfaces).unwrap();
+            let result: ShellSurfaces = serde_json::from_str(&json).unwrap();
+            assert_eq!(result, shell_surfaces);
+        }
+
+        #[test]
+        fn success_when_documented_representation() {
+            let case = ShellSurfaces::new(
+                vec![],
+                Charset::UTF8,
+                vec![BraceContainer::new(
+                    vec![],
+                    Brace::Surface(Surface::new(
+                        vec![
+                            SurfaceId::Range(0, 3),
+                            SurfaceId::Not(Box::new(SurfaceId::Unit(2))),
+                        ],
+                        vec![
+                            LineContainer::Comment(CommentLine::new("// c".to_string())),
+                            LineContainer::body_line(SurfaceInner::PointCenterX(10)),
+                            LineContainer::Body(
+                                SurfaceInner::PointCenterY(20),
+                                LineFormat::new(
+                                    Some("point.centery, 20".to_string()),
+                                    Some("// c".to_string()),
+                                ),
+                            ),
+                        ],
+                    )),
+                )],
+                vec![],
+            );
+            let expected = serde_json::json!({
+                "header_comments": [],
+                "charset": "UTF-8",
+                "charset_declared": true,
+                "braces": [{
+                    "header_comments": [],
+                    "layout": "OwnLines",
+                    "body": {
+                        "Surface": {
+                            "ids": [{ "Range": [0, 3] }, { "Not": { "Unit": 2 } }],
+                            "lines": [
+                                { "Comment": { "body": "// c" } },
+                                { "Body": { "PointCenterX": 10 } },
+                                {
+                                    "FormattedBody": {
+                                        "body": { "PointCenterY": 20 },
+                                        "format": {
+                                            "raw": "point.centery, 20",
+                                            "comment": "// c"
+                                        }
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                }],
+                "footer_comments": []
+            });
+            assert_eq!(serde_json::to_value(&case).unwrap(), expected);
+            assert_eq!(
+                serde_json::from_value::<ShellSurfaces>(expected.clone()).unwrap(),
+                case
+            );
+
+            let mut legacy = expected;
+            legacy.as_object_mut().unwrap().remove("charset_declared");
+            legacy["braces"][0]
+                .as_object_mut()
+                .unwrap()
+                .remove("layout");
+            assert_eq!(
+                serde_json::from_value::<ShellSurfaces>(legacy).unwrap(),
+                case
+            );
+        }
+    }
+}
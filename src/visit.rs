@@ -0,0 +1,457 @@
+//! Traverses the AST by shared reference.
+//!
+//! Each method of [`Visit`] calls the free function of the same name by default,
+//! which visits the children of the node. Override only the methods for nodes of interest,
+//! and call the free function inside to keep visiting children.
+//!
+//! # Examples
+//!
+//! ```
+//! use shell_parser_surfaces_rs::{
+//!     parse,
+//!     visit::{self, Visit},
+//!     SurfaceCollision,
+//! };
+//!
+//! struct CollisionCounter(usize);
+//!
+//! impl Visit for CollisionCounter {
+//!     fn visit_surface_collision(&mut self, node: &SurfaceCollision) {
+//!         self.0 += 1;
+//!         visit::visit_surface_collision(self, node);
+//!     }
+//! }
+//!
+//! let case = r#"charset,UTF-8
+//! surface0
+//! {
+//! collision0,40,56,95,90,Head
+//! animation0.collision1,10,10,20,20,Face
+//! }
+//! "#;
+//!
+//! let mut counter = CollisionCounter(0);
+//! counter.visit_shell_surfaces(&parse(case).unwrap());
+//! assert_eq!(counter.0, 2);
+//! ```
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AnimationInterval, AnimationOptionKind, AnimationPatternDrawMethod, AnimationPatternProperty,
+    BlockComment, Brace, BraceContainer, CollisionExKind, CommentLine, Descript, DescriptInner,
+    DrawMethod, DrawMethodOnAnimation, GestureKind, LineContainer, ShellSurfaces,
+    ShellSurfacesCursor, ShellSurfacesCursorGesture, SortOrder, Surface, SurfaceAlias,
+    SurfaceAliasInner, SurfaceAnimationCollision, SurfaceAnimationCollisionEx,
+    SurfaceAnimationInterval, SurfaceAnimationOption, SurfaceAnimationPattern, SurfaceAppend,
+    SurfaceCollision, SurfaceCollisionEx, SurfaceElement, SurfaceId, SurfaceInner,
+    SurfaceTargetCharacterId, Tooltip, TooltipInner,
+};
+
+pub trait Visit {
+    fn visit_shell_surfaces(&mut self, node: &ShellSurfaces) {
+        visit_shell_surfaces(self, node)
+    }
+    fn visit_comment_line(&mut self, node: &CommentLine) {
+        visit_comment_line(self, node)
+    }
+    fn visit_block_comment(&mut self, node: &BlockComment) {
+        visit_block_comment(self, node)
+    }
+    fn visit_charset(&mut self, node: &Charset) {
+        visit_charset(self, node)
+    }
+    fn visit_brace_container(&mut self, node: &BraceContainer) {
+        visit_brace_container(self, node)
+    }
+    fn visit_brace(&mut self, node: &Brace) {
+        visit_brace(self, node)
+    }
+    fn visit_descript(&mut self, node: &Descript) {
+        visit_descript(self, node)
+    }
+    fn visit_descript_inner(&mut self, node: &DescriptInner) {
+        visit_descript_inner(self, node)
+    }
+    fn visit_sort_order(&mut self, node: &SortOrder) {
+        visit_sort_order(self, node)
+    }
+    fn visit_surface(&mut self, node: &Surface) {
+        visit_surface(self, node)
+    }
+    fn visit_surface_append(&mut self, node: &SurfaceAppend) {
+        visit_surface_append(self, node)
+    }
+    fn visit_surface_id(&mut self, node: &SurfaceId) {
+        visit_surface_id(self, node)
+    }
+    fn visit_surface_inner(&mut self, node: &SurfaceInner) {
+        visit_surface_inner(self, node)
+    }
+    fn visit_surface_element(&mut self, node: &SurfaceElement) {
+        visit_surface_element(self, node)
+    }
+    fn visit_draw_method(&mut self, node: &DrawMethod) {
+        visit_draw_method(self, node)
+    }
+    fn visit_draw_method_on_animation(&mut self, node: &DrawMethodOnAnimation) {
+        visit_draw_method_on_animation(self, node)
+    }
+    fn visit_surface_animation_interval(&mut self, node: &SurfaceAnimationInterval) {
+        visit_surface_animation_interval(self, node)
+    }
+    fn visit_animation_interval(&mut self, node: &AnimationInterval) {
+        visit_animation_interval(self, node)
+    }
+    fn visit_surface_animation_pattern(&mut self, node: &SurfaceAnimationPattern) {
+        visit_surface_animation_pattern(self, node)
+    }
+    fn visit_animation_pattern_draw_method(&mut self, node: &AnimationPatternDrawMethod) {
+        visit_animation_pattern_draw_method(self, node)
+    }
+    fn visit_animation_pattern_property(&mut self, node: &AnimationPatternProperty) {
+        visit_animation_pattern_property(self, node)
+    }
+    fn visit_surface_animation_option(&mut self, node: &SurfaceAnimationOption) {
+        visit_surface_animation_option(self, node)
+    }
+    fn visit_animation_option_kind(&mut self, node: &AnimationOptionKind) {
+        visit_animation_option_kind(self, node)
+    }
+    fn visit_surface_animation_collision(&mut self, node: &SurfaceAnimationCollision) {
+        visit_surface_animation_collision(self, node)
+    }
+    fn visit_surface_animation_collision_ex(&mut self, node: &SurfaceAnimationCollisionEx) {
+        visit_surface_animation_collision_ex(self, node)
+    }
+    fn visit_surface_collision(&mut self, node: &SurfaceCollision) {
+        visit_surface_collision(self, node)
+    }
+    fn visit_surface_collision_ex(&mut self, node: &SurfaceCollisionEx) {
+        visit_surface_collision_ex(self, node)
+    }
+    fn visit_collision_ex_kind(&mut self, node: &CollisionExKind) {
+        visit_collision_ex_kind(self, node)
+    }
+    fn visit_surface_alias(&mut self, node: &SurfaceAlias) {
+        visit_surface_alias(self, node)
+    }
+    fn visit_surface_target_character_id(&mut self, node: &SurfaceTargetCharacterId) {
+        visit_surface_target_character_id(self, node)
+    }
+    fn visit_surface_alias_inner(&mut self, node: &SurfaceAliasInner) {
+        visit_surface_alias_inner(self, node)
+    }
+    fn visit_shell_surfaces_cursor(&mut self, node: &ShellSurfacesCursor) {
+        visit_shell_surfaces_cursor(self, node)
+    }
+    fn visit_shell_surfaces_cursor_gesture(&mut self, node: &ShellSurfacesCursorGesture) {
+        visit_shell_surfaces_cursor_gesture(self, node)
+    }
+    fn visit_gesture_kind(&mut self, node: &GestureKind) {
+        visit_gesture_kind(self, node)
+    }
+    fn visit_tooltip(&mut self, node: &Tooltip) {
+        visit_tooltip(self, node)
+    }
+    fn visit_tooltip_inner(&mut self, node: &TooltipInner) {
+        visit_tooltip_inner(self, node)
+    }
+}
+
+fn visit_lines<V, T, F>(v: &mut V, lines: &[LineContainer<T>], mut f: F)
+where
+    V: Visit + ?Sized,
+    F: FnMut(&mut V, &T),
+{
+    for line in lines {
+        match line {
+            LineContainer::Comment(c) => v.visit_comment_line(c),
+            LineContainer::BlockComment(c) => v.visit_block_comment(c),
+            LineContainer::Body(b, _) => f(v, b),
+        }
+    }
+}
+
+pub fn visit_shell_surfaces<V: Visit + ?Sized>(v: &mut V, node: &ShellSurfaces) {
+    for c in node.header_comments() {
+        v.visit_comment_line(c);
+    }
+    v.visit_charset(node.charset());
+    for b in node.braces() {
+        v.visit_brace_container(b);
+    }
+    for c in node.footer_comments() {
+        v.visit_comment_line(c);
+    }
+}
+
+pub fn visit_comment_line<V: Visit + ?Sized>(_v: &mut V, _node: &CommentLine) {}
+
+pub fn visit_block_comment<V: Visit + ?Sized>(_v: &mut V, _node: &BlockComment) {}
+
+pub fn visit_charset<V: Visit + ?Sized>(_v: &mut V, _node: &Charset) {}
+
+pub fn visit_brace_container<V: Visit + ?Sized>(v: &mut V, node: &BraceContainer) {
+    for c in node.header_comments() {
+        v.visit_comment_line(c);
+    }
+    v.visit_brace(node.body());
+}
+
+pub fn visit_brace<V: Visit + ?Sized>(v: &mut V, node: &Brace) {
+    match node {
+        Brace::Descript(b) => v.visit_descript(b),
+        Brace::Surface(b) => v.visit_surface(b),
+        Brace::SurfaceAppend(b) => v.visit_surface_append(b),
+        Brace::SurfaceAlias(b) => v.visit_surface_alias(b),
+        Brace::Cursor(b) => v.visit_shell_surfaces_cursor(b),
+        Brace::Tooltip(b) => v.visit_tooltip(b),
+        Brace::BlockComment(b) => v.visit_block_comment(b),
+    }
+}
+
+pub fn visit_descript<V: Visit + ?Sized>(v: &mut V, node: &Descript) {
+    visit_lines(v, node.lines(), |v, b| v.visit_descript_inner(b));
+}
+
+pub fn visit_descript_inner<V: Visit + ?Sized>(v: &mut V, node: &DescriptInner) {
+    match node {
+        DescriptInner::CollistionSort(o) | DescriptInner::AnimationSort(o) => v.visit_sort_order(o),
+        DescriptInner::Version(_) | DescriptInner::MaxWidth(_) => {}
+    }
+}
+
+pub fn visit_sort_order<V: Visit + ?Sized>(_v: &mut V, _node: &SortOrder) {}
+
+pub fn visit_surface<V: Visit + ?Sized>(v: &mut V, node: &Surface) {
+    for id in node.ids() {
+        v.visit_surface_id(id);
+    }
+    visit_lines(v, node.lines(), |v, b| v.visit_surface_inner(b));
+}
+
+pub fn visit_surface_append<V: Visit + ?Sized>(v: &mut V, node: &SurfaceAppend) {
+    for id in node.ids() {
+        v.visit_surface_id(id);
+    }
+    visit_lines(v, node.lines(), |v, b| v.visit_surface_inner(b));
+}
+
+pub fn visit_surface_id<V: Visit + ?Sized>(v: &mut V, node: &SurfaceId) {
+    match node {
+        SurfaceId::Not(inner) => v.visit_surface_id(inner),
+        SurfaceId::Unit(_) | SurfaceId::Range(_, _) | SurfaceId::RangeFrom(_) | SurfaceId::All => {}
+    }
+}
+
+pub fn visit_surface_inner<V: Visit + ?Sized>(v: &mut V, node: &SurfaceInner) {
+    match node {
+        SurfaceInner::Element(b) => v.visit_surface_element(b),
+        SurfaceInner::AnimationInterval(b) => v.visit_surface_animation_interval(b),
+        SurfaceInner::AnimationPattern(b) => v.visit_surface_animation_pattern(b),
+        SurfaceInner::AnimationOption(b) => v.visit_surface_animation_option(b),
+        SurfaceInner::AnimationCollision(b) => v.visit_surface_animation_collision(b),
+        SurfaceInner::AnimationCollisionEx(b) => v.visit_surface_animation_collision_ex(b),
+        SurfaceInner::Collision(b) => v.visit_surface_collision(b),
+        SurfaceInner::CollisionEx(b) => v.visit_surface_collision_ex(b),
+        SurfaceInner::CharacterBalloonOffsetX(id, _)
+        | SurfaceInner::CharacterBalloonOffsetY(id, _) => v.visit_surface_target_character_id(id),
+        SurfaceInner::SakuraBalloonOffsetX(_)
+        | SurfaceInner::SakuraBalloonOffsetY(_)
+        | SurfaceInner::KeroBalloonOffsetX(_)
+        | SurfaceInner::KeroBalloonOffsetY(_)
+        | SurfaceInner::BalloonOffsetX(_)
+        | SurfaceInner::BalloonOffsetY(_)
+        | SurfaceInner::PointCenterX(_)
+        | SurfaceInner::PointCenterY(_)
+        | SurfaceInner::PointKinokoCenterX(_)
+        | SurfaceInner::PointKinokoCenterY(_)
+        | SurfaceInner::PointBaseposX(_)
+        | SurfaceInner::PointBaseposY(_) => {}
+    }
+}
+
+pub fn visit_surface_element<V: Visit + ?Sized>(v: &mut V, node: &SurfaceElement) {
+    v.visit_draw_method(node.method());
+}
+
+pub fn visit_draw_method<V: Visit + ?Sized>(_v: &mut V, _node: &DrawMethod) {}
+
+pub fn visit_draw_method_on_animation<V: Visit + ?Sized>(
+    _v: &mut V,
+    _node: &DrawMethodOnAnimation,
+) {
+}
+
+pub fn visit_surface_animation_interval<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &SurfaceAnimationInterval,
+) {
+    for i in node.intervals() {
+        v.visit_animation_interval(i);
+    }
+}
+
+pub fn visit_animation_interval<V: Visit + ?Sized>(_v: &mut V, _node: &AnimationInterval) {}
+
+pub fn visit_surface_animation_pattern<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &SurfaceAnimationPattern,
+) {
+    v.visit_animation_pattern_draw_method(node.method());
+}
+
+pub fn visit_animation_pattern_draw_method<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &AnimationPatternDrawMethod,
+) {
+    match node {
+        AnimationPatternDrawMethod::Normal(m, p) => {
+            v.visit_draw_method(m);
+            v.visit_animation_pattern_property(p);
+        }
+        AnimationPatternDrawMethod::Animation(m) => v.visit_draw_method_on_animation(m),
+    }
+}
+
+pub fn visit_animation_pattern_property<V: Visit + ?Sized>(
+    _v: &mut V,
+    _node: &AnimationPatternProperty,
+) {
+}
+
+pub fn visit_surface_animation_option<V: Visit + ?Sized>(v: &mut V, node: &SurfaceAnimationOption) {
+    for o in node.options() {
+        v.visit_animation_option_kind(o);
+    }
+}
+
+pub fn visit_animation_option_kind<V: Visit + ?Sized>(_v: &mut V, _node: &AnimationOptionKind) {}
+
+pub fn visit_surface_animation_collision<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &SurfaceAnimationCollision,
+) {
+    v.visit_surface_collision(node.collision());
+}
+
+pub fn visit_surface_animation_collision_ex<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &SurfaceAnimationCollisionEx,
+) {
+    v.visit_surface_collision_ex(node.collision());
+}
+
+pub fn visit_surface_collision<V: Visit + ?Sized>(_v: &mut V, _node: &SurfaceCollision) {}
+
+pub fn visit_surface_collision_ex<V: Visit + ?Sized>(v: &mut V, node: &SurfaceCollisionEx) {
+    v.visit_collision_ex_kind(node.kind());
+}
+
+pub fn visit_collision_ex_kind<V: Visit + ?Sized>(_v: &mut V, _node: &CollisionExKind) {}
+
+pub fn visit_surface_alias<V: Visit + ?Sized>(v: &mut V, node: &SurfaceAlias) {
+    v.visit_surface_target_character_id(node.id());
+    visit_lines(v, node.lines(), |v, b| v.visit_surface_alias_inner(b));
+}
+
+pub fn visit_surface_target_character_id<V: Visit + ?Sized>(
+    _v: &mut V,
+    _node: &SurfaceTargetCharacterId,
+) {
+}
+
+pub fn visit_surface_alias_inner<V: Visit + ?Sized>(_v: &mut V, _node: &SurfaceAliasInner) {}
+
+pub fn visit_shell_surfaces_cursor<V: Visit + ?Sized>(v: &mut V, node: &ShellSurfacesCursor) {
+    v.visit_surface_target_character_id(node.id());
+    visit_lines(v, node.lines(), |v, b| {
+        v.visit_shell_surfaces_cursor_gesture(b)
+    });
+}
+
+pub fn visit_shell_surfaces_cursor_gesture<V: Visit + ?Sized>(
+    v: &mut V,
+    node: &ShellSurfacesCursorGesture,
+) {
+    v.visit_gesture_kind(node.kind());
+}
+
+pub fn visit_gesture_kind<V: Visit + ?Sized>(_v: &mut V, _node: &GestureKind) {}
+
+pub fn visit_tooltip<V: Visit + ?Sized>(v: &mut V, node: &Tooltip) {
+    v.visit_surface_target_character_id(node.id());
+    visit_lines(v, node.lines(), |v, b| v.visit_tooltip_inner(b));
+}
+
+pub fn visit_tooltip_inner<V: Visit + ?Sized>(_v: &mut V, _node: &TooltipInner) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    #[derive(Default)]
+    struct Counter {
+        comments: usize,
+        braces: usize,
+        surface_ids: usize,
+        draw_methods: usize,
+        characters: usize,
+    }
+
+    impl Visit for Counter {
+        fn visit_comment_line(&mut self, _node: &CommentLine) {
+            self.comments += 1;
+        }
+        fn visit_brace(&mut self, node: &Brace) {
+            self.braces += 1;
+            visit_brace(self, node);
+        }
+        fn visit_surface_id(&mut self, node: &SurfaceId) {
+            self.surface_ids += 1;
+            visit_surface_id(self, node);
+        }
+        fn visit_draw_method(&mut self, _node: &DrawMethod) {
+            self.draw_methods += 1;
+        }
+        fn visit_surface_target_character_id(&mut self, _node: &SurfaceTargetCharacterId) {
+            self.characters += 1;
+        }
+    }
+
+    mod visit_shell_surfaces {
+        use super::*;
+
+        #[test]
+        fn success_when_visiting_every_node() {
+            let case = r#"// header
+charset,UTF-8
+surface0,!1-3
+{
+element0,overlay,body0.png,0,0
+animation0.pattern0,overlay,101,100,168,67
+animation0.pattern1,start,2
+char2.balloon.offsetx,10
+// comment
+}
+sakura.surface.alias
+{
+素,[0]
+}
+kero.tooltips
+{
+Bust,怒ります。
+}
+"#;
+            let mut counter = Counter::default();
+            counter.visit_shell_surfaces(&parse(case).unwrap());
+            assert_eq!(counter.comments, 2);
+            assert_eq!(counter.braces, 3);
+            assert_eq!(counter.surface_ids, 3);
+            assert_eq!(counter.draw_methods, 2);
+            assert_eq!(counter.characters, 3);
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Traverses the AST by mutable reference.
+//!
+//! Each method of [`VisitMut`] calls the free function of the same name by default,
+//! which visits the children of the node. Override only the methods for nodes of interest,
+//! and call the free function inside to keep visiting children.
+//!
+//! # Examples
+//!
+//! ```
+//! use shell_parser_surfaces_rs::{
+//!     parse,
+//!     visit_mut::{self, VisitMut},
+//!     SurfaceCollision,
+//! };
+//!
+//! struct Renamer;
+//!
+//! impl VisitMut for Renamer {
+//!     fn visit_surface_collision_mut(&mut self, node: &mut SurfaceCollision) {
+//!         if node.target_id() == "Head" {
+//!             *node.target_id_mut() = "Face".to_string();
+//!         }
+//!         visit_mut::visit_surface_collision_mut(self, node);
+//!     }
+//! }
+//!
+//! let case = r#"charset,UTF-8
+//! surface0
+//! {
+//! collision0,40,56,95,90,Head
+//! }
+//! "#;
+//!
+//! let mut shell_surfaces = parse(case).unwrap();
+//! Renamer.visit_shell_surfaces_mut(&mut shell_surfaces);
+//! assert!(shell_surfaces.to_string().contains("collision0,40,56,95,90,Face"));
+//! ```
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AnimationInterval, AnimationOptionKind, AnimationPatternDrawMethod, AnimationPatternProperty,
+    BlockComment, Brace, BraceContainer, CollisionExKind, CommentLine, Descript, DescriptInner,
+    DrawMethod, DrawMethodOnAnimation, GestureKind, LineContainer, ShellSurfaces,
+    ShellSurfacesCursor, ShellSurfacesCursorGesture, SortOrder, Surface, SurfaceAlias,
+    SurfaceAliasInner, SurfaceAnimationCollision, SurfaceAnimationCollisionEx,
+    SurfaceAnimationInterval, SurfaceAnimationOption, SurfaceAnimationPattern, SurfaceAppend,
+    SurfaceCollision, SurfaceCollisionEx, SurfaceElement, SurfaceId, SurfaceInner,
+    SurfaceTargetCharacterId, Tooltip, TooltipInner,
+};
+
+pub trait VisitMut {
+    fn visit_shell_surfaces_mut(&mut self, node: &mut ShellSurfaces) {
+        visit_shell_surfaces_mut(self, node)
+    }
+    fn visit_comment_line_mut(&mut self, node: &mut CommentLine) {
+        visit_comment_line_mut(self, node)
+    }
+    fn visit_block_comment_mut(&mut self, node: &mut BlockComment) {
+        visit_block_comment_mut(self, node)
+    }
+    fn visit_charset_mut(&mut self, node: &mut Charset) {
+        visit_charset_mut(self, node)
+    }
+    fn visit_brace_container_mut(&mut self, node: &mut BraceContainer) {
+        visit_brace_container_mut(self, node)
+    }
+    fn visit_brace_mut(&mut self, node: &mut Brace) {
+        visit_brace_mut(self, node)
+    }
+    fn visit_descript_mut(&mut self, node: &mut Descript) {
+        visit_descript_mut(self, node)
+    }
+    fn visit_descript_inner_mut(&mut self, node: &mut DescriptInner) {
+        visit_descript_inner_mut(self, node)
+    }
+    fn visit_sort_order_mut(&mut self, node: &mut SortOrder) {
+        visit_sort_order_mut(self, node)
+    }
+    fn visit_surface_mut(&mut self, node: &mut Surface) {
+        visit_surface_mut(self, node)
+    }
+    fn visit_surface_append_mut(&mut self, node: &mut SurfaceAppend) {
+        visit_surface_append_mut(self, node)
+    }
+    fn visit_surface_id_mut(&mut self, node: &mut SurfaceId) {
+        visit_surface_id_mut(self, node)
+    }
+    fn visit_surface_inner_mut(&mut self, node: &mut SurfaceInner) {
+        visit_surface_inner_mut(self, node)
+    }
+    fn visit_surface_element_mut(&mut self, node: &mut SurfaceElement) {
+        visit_surface_element_mut(self, node)
+    }
+    fn visit_draw_method_mut(&mut self, node: &mut DrawMethod) {
+        visit_draw_method_mut(self, node)
+    }
+    fn visit_draw_method_on_animation_mut(&mut self, node: &mut DrawMethodOnAnimation) {
+        visit_draw_method_on_animation_mut(self, node)
+    }
+    fn visit_surface_animation_interval_mut(&mut self, node: &mut SurfaceAnimationInterval) {
+        visit_surface_animation_interval_mut(self, node)
+    }
+    fn visit_animation_interval_mut(&mut self, node: &mut AnimationInterval) {
+        visit_animation_interval_mut(self, node)
+    }
+    fn visit_surface_animation_pattern_mut(&mut self, node: &mut SurfaceAnimationPattern) {
+        visit_surface_animation_pattern_mut(self, node)
+    }
+    fn visit_animation_pattern_draw_method_mut(&mut self, node: &mut AnimationPatternDrawMethod) {
+        visit_animation_pattern_draw_method_mut(self, node)
+    }
+    fn visit_animation_pattern_property_mut(&mut self, node: &mut AnimationPatternProperty) {
+        visit_animation_pattern_property_mut(self, node)
+    }
+    fn visit_surface_animation_option_mut(&mut self, node: &mut SurfaceAnimationOption) {
+        visit_surface_animation_option_mut(self, node)
+    }
+    fn visit_animation_option_kind_mut(&mut self, node: &mut AnimationOptionKind) {
+        visit_animation_option_kind_mut(self, node)
+    }
+    fn visit_surface_animation_collision_mut(&mut self, node: &mut SurfaceAnimationCollision) {
+        visit_surface_animation_collision_mut(self, node)
+    }
+    fn visit_surface_animation_collision_ex_mut(&mut self, node: &mut SurfaceAnimationCollisionEx) {
+        visit_surface_animation_collision_ex_mut(self, node)
+    }
+    fn visit_surface_collision_mut(&mut self, node: &mut SurfaceCollision) {
+        visit_surface_collision_mut(self, node)
+    }
+    fn visit_surface_collision_ex_mut(&mut self, node: &mut SurfaceCollisionEx) {
+        visit_surface_collision_ex_mut(self, node)
+    }
+    fn visit_collision_ex_kind_mut(&mut self, node: &mut CollisionExKind) {
+        visit_collision_ex_kind_mut(self, node)
+    }
+    fn visit_surface_alias_mut(&mut self, node: &mut SurfaceAlias) {
+        visit_surface_alias_mut(self, node)
+    }
+    fn visit_surface_target_character_id_mut(&mut self, node: &mut SurfaceTargetCharacterId) {
+        visit_surface_target_character_id_mut(self, node)
+    }
+    fn visit_surface_alias_inner_mut(&mut self, node: &mut SurfaceAliasInner) {
+        visit_surface_alias_inner_mut(self, node)
+    }
+    fn visit_shell_surfaces_cursor_mut(&mut self, node: &mut ShellSurfacesCursor) {
+        visit_shell_surfaces_cursor_mut(self, node)
+    }
+    fn visit_shell_surfaces_cursor_gesture_mut(&mut self, node: &mut ShellSurfacesCursorGesture) {
+        visit_shell_surfaces_cursor_gesture_mut(self, node)
+    }
+    fn visit_gesture_kind_mut(&mut self, node: &mut GestureKind) {
+        visit_gesture_kind_mut(self, node)
+    }
+    fn visit_tooltip_mut(&mut self, node: &mut Tooltip) {
+        visit_tooltip_mut(self, node)
+    }
+    fn visit_tooltip_inner_mut(&mut self, node: &mut TooltipInner) {
+        visit_tooltip_inner_mut(self, node)
+    }
+}
+
+fn visit_lines_mut<V, T, F>(v: &mut V, lines: &mut [LineContainer<T>], mut f: F)
+where
+    V: VisitMut + ?Sized,
+    T: Clone + PartialEq,
+    F: FnMut(&mut V, &mut T),
+{
+    for line in lines {
+        match line {
+            LineContainer::Comment(c) => v.visit_comment_line_mut(c),
+            LineContainer::BlockComment(c) => v.visit_block_comment_mut(c),
+            LineContainer::Body(b, format) => {
+                // The original text is kept only while the body is unchanged.
+                let before = format.raw().is_some().then(|| b.clone());
+                f(v, b);
+                if before.is_some_and(|before| &before != b) {
+                    *format.raw_mut() = None;
+                }
+            }
+        }
+    }
+}
+
+pub fn visit_shell_surfaces_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ShellSurfaces) {
+    for c in node.header_comments_mut() {
+        v.visit_comment_line_mut(c);
+    }
+    v.visit_charset_mut(node.charset_mut());
+    for b in node.braces_mut() {
+        v.visit_brace_container_mut(b);
+    }
+    for c in node.footer_comments_mut() {
+        v.visit_comment_line_mut(c);
+    }
+}
+
+pub fn visit_comment_line_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut CommentLine) {}
+
+pub fn visit_block_comment_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut BlockComment) {}
+
+pub fn visit_charset_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut Charset) {}
+
+pub fn visit_brace_container_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut BraceContainer) {
+    for c in node.header_comments_mut() {
+        v.visit_comment_line_mut(c);
+    }
+    v.visit_brace_mut(node.body_mut());
+}
+
+pub fn visit_brace_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Brace) {
+    match node {
+        Brace::Descript(b) => v.visit_descript_mut(b),
+        Brace::Surface(b) => v.visit_surface_mut(b),
+        Brace::SurfaceAppend(b) => v.visit_surface_append_mut(b),
+        Brace::SurfaceAlias(b) => v.visit_surface_alias_mut(b),
+        Brace::Cursor(b) => v.visit_shell_surfaces_cursor_mut(b),
+        Brace::Tooltip(b) => v.visit_tooltip_mut(b),
+        Brace::BlockComment(b) => v.visit_block_comment_mut(b),
+    }
+}
+
+pub fn visit_descript_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Descript) {
+    visit_lines_mut(v, node.lines_mut(), |v, b| v.visit_descript_inner_mut(b));
+}
+
+pub fn visit_descript_inner_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut DescriptInner) {
+    match node {
+        DescriptInner::CollistionSort(o) | DescriptInner::AnimationSort(o) => {
+            v.visit_sort_order_mut(o)
+        }
+        DescriptInner::Version(_) | DescriptInner::MaxWidth(_) => {}
+    }
+}
+
+pub fn visit_sort_order_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut SortOrder) {}
+
+pub fn visit_surface_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Surface) {
+    for id in node.ids_mut() {
+        v.visit_surface_id_mut(id);
+    }
+    visit_lines_mut(v, node.lines_mut(), |v, b| v.visit_surface_inner_mut(b));
+}
+
+pub fn visit_surface_append_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SurfaceAppend) {
+    for id in node.ids_mut() {
+        v.visit_surface_id_mut(id);
+    }
+    visit_lines_mut(v, node.lines_mut(), |v, b| v.visit_surface_inner_mut(b));
+}
+
+pub fn visit_surface_id_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SurfaceId) {
+    match node {
+        SurfaceId::Not(inner) => v.visit_surface_id_mut(inner),
+        SurfaceId::Unit(_) | SurfaceId::Range(_, _) | SurfaceId::RangeFrom(_) | SurfaceId::All => {}
+    }
+}
+
+pub fn visit_surface_inner_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SurfaceInner) {
+    match node {
+        SurfaceInner::Element(b) => v.visit_surface_element_mut(b),
+        SurfaceInner::AnimationInterval(b) => v.visit_surface_animation_interval_mut(b),
+        SurfaceInner::AnimationPattern(b) => v.visit_surface_animation_pattern_mut(b),
+        SurfaceInner::AnimationOption(b) => v.visit_surface_animation_option_mut(b),
+        SurfaceInner::AnimationCollision(b) => v.visit_surface_animation_collision_mut(b),
+        SurfaceInner::AnimationCollisionEx(b) => v.visit_surface_animation_collision_ex_mut(b),
+        SurfaceInner::Collision(b) => v.visit_surface_collision_mut(b),
+        SurfaceInner::CollisionEx(b) => v.visit_surface_collision_ex_mut(b),
+        SurfaceInner::CharacterBalloonOffsetX(id, _)
+        | SurfaceInner::CharacterBalloonOffsetY(id, _) => {
+            v.visit_surface_target_character_id_mut(id)
+        }
+        SurfaceInner::SakuraBalloonOffsetX(_)
+        | SurfaceInner::SakuraBalloonOffsetY(_)
+        | SurfaceInner::KeroBalloonOffsetX(_)
+        | SurfaceInner::KeroBalloonOffsetY(_)
+        | SurfaceInner::BalloonOffsetX(_)
+        | SurfaceInner::BalloonOffsetY(_)
+        | SurfaceInner::PointCenterX(_)
+        | SurfaceInner::PointCenterY(_)
+        | SurfaceInner::PointKinokoCenterX(_)
+        | SurfaceInner::PointKinokoCenterY(_)
+        | SurfaceInner::PointBaseposX(_)
+        | SurfaceInner::PointBaseposY(_) => {}
+    }
+}
+
+pub fn visit_surface_element_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SurfaceElement) {
+    v.visit_draw_method_mut(node.method_mut());
+}
+
+pub fn visit_draw_method_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut DrawMethod) {}
+
+pub fn visit_draw_method_on_animation_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut DrawMethodOnAnimation,
+) {
+}
+
+pub fn visit_surface_animation_interval_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceAnimationInterval,
+) {
+    for i in node.intervals_mut() {
+        v.visit_animation_interval_mut(i);
+    }
+}
+
+pub fn visit_animation_interval_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut AnimationInterval,
+) {
+}
+
+pub fn visit_surface_animation_pattern_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceAnimationPattern,
+) {
+    v.visit_animation_pattern_draw_method_mut(node.method_mut());
+}
+
+pub fn visit_animation_pattern_draw_method_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut AnimationPatternDrawMethod,
+) {
+    match node {
+        AnimationPatternDrawMethod::Normal(m, p) => {
+            v.visit_draw_method_mut(m);
+            v.visit_animation_pattern_property_mut(p);
+        }
+        AnimationPatternDrawMethod::Animation(m) => v.visit_draw_method_on_animation_mut(m),
+    }
+}
+
+pub fn visit_animation_pattern_property_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut AnimationPatternProperty,
+) {
+}
+
+pub fn visit_surface_animation_option_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceAnimationOption,
+) {
+    for o in node.options_mut() {
+        v.visit_animation_option_kind_mut(o);
+    }
+}
+
+pub fn visit_animation_option_kind_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut AnimationOptionKind,
+) {
+}
+
+pub fn visit_surface_animation_collision_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceAnimationCollision,
+) {
+    v.visit_surface_collision_mut(node.collision_mut());
+}
+
+pub fn visit_surface_animation_collision_ex_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceAnimationCollisionEx,
+) {
+    v.visit_surface_collision_ex_mut(node.collision_mut());
+}
+
+pub fn visit_surface_collision_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut SurfaceCollision) {
+}
+
+pub fn visit_surface_collision_ex_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut SurfaceCollisionEx,
+) {
+    v.visit_collision_ex_kind_mut(node.kind_mut());
+}
+
+pub fn visit_collision_ex_kind_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut CollisionExKind) {}
+
+pub fn visit_surface_alias_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SurfaceAlias) {
+    v.visit_surface_target_character_id_mut(node.id_mut());
+    visit_lines_mut(v, node.lines_mut(), |v, b| {
+        v.visit_surface_alias_inner_mut(b)
+    });
+}
+
+pub fn visit_surface_target_character_id_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut SurfaceTargetCharacterId,
+) {
+}
+
+pub fn visit_surface_alias_inner_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _node: &mut SurfaceAliasInner,
+) {
+}
+
+pub fn visit_shell_surfaces_cursor_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut ShellSurfacesCursor,
+) {
+    v.visit_surface_target_character_id_mut(node.id_mut());
+    visit_lines_mut(v, node.lines_mut(), |v, b| {
+        v.visit_shell_surfaces_cursor_gesture_mut(b)
+    });
+}
+
+pub fn visit_shell_surfaces_cursor_gesture_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut ShellSurfacesCursorGesture,
+) {
+    v.visit_gesture_kind_mut(node.kind_mut());
+}
+
+pub fn visit_gesture_kind_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut GestureKind) {}
+
+pub fn visit_tooltip_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Tooltip) {
+    v.visit_surface_target_character_id_mut(node.id_mut());
+    visit_lines_mut(v, node.lines_mut(), |v, b| v.visit_tooltip_inner_mut(b));
+}
+
+pub fn visit_tooltip_inner_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut TooltipInner) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    struct Shift(i64);
+
+    impl VisitMut for Shift {
+        fn visit_surface_inner_mut(&mut self, node: &mut SurfaceInner) {
+            match node {
+                SurfaceInner::Element(e) => *e.x_mut() += self.0,
+                SurfaceInner::SakuraBalloonOffsetX(x)
+                | SurfaceInner::CharacterBalloonOffsetX(_, x) => *x += self.0,
+                _ => {}
+            }
+            visit_surface_inner_mut(self, node);
+        }
+        fn visit_surface_collision_mut(&mut self, node: &mut SurfaceCollision) {
+            *node.start_x_mut() += self.0;
+            *node.end_x_mut() += self.0;
+        }
+        fn visit_comment_line_mut(&mut self, node: &mut CommentLine) {
+            node.body_mut().clear();
+        }
+    }
+
+    mod visit_shell_surfaces_mut {
+        use super::*;
+
+        #[test]
+        fn success_when_editing_every_node() {
+            let case = r#"charset,UTF-8
+surface0
+{
+element0,overlay,body0.png,0,0
+animation0.collision0,10,10,20,20,Head
+sakura.balloon.offsetx,80
+char2.balloon.offsetx,-10
+// comment
+}
+"#;
+            let mut result = parse(case).unwrap();
+            Shift(5).visit_shell_surfaces_mut(&mut result);
+
+            let expected = r#"charset,UTF-8
+surface0
+{
+element0,overlay,body0.png,5,0
+animation0.collision0,15,10,25,20,Head
+sakura.balloon.offsetx,85
+char2.balloon.offsetx,-5
+
+}
+"#;
+            assert_eq!(result, parse(expected).unwrap());
+        }
+
+        #[test]
+        fn success_when_editing_spaced_line() {
+            let case = "surface0\r\n{\r\nelement0, overlay, body0.png, 0, 0\r\nsakura.balloon.offsety, 80\r\n}\r\n";
+            let mut result = parse(case).unwrap();
+            Shift(5).visit_shell_surfaces_mut(&mut result);
+
+            let expected = "surface0\r\n{\r\nelement0,overlay,body0.png,5,0\r\nsakura.balloon.offsety, 80\r\n}\r\n";
+            assert_eq!(result.to_string(), expected);
+        }
+    }
+}